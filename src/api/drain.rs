@@ -0,0 +1,183 @@
+// In-flight request tracking, so shutdown can let running requests finish
+// before endpoints are stopped
+
+use axum::{
+    Json,
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+/// Counts requests being handled and turns new ones away once draining starts
+#[derive(Clone, Default)]
+pub(crate) struct InFlight {
+    inner: Arc<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    active: AtomicUsize,
+    draining: AtomicBool,
+    idle: Notify,
+}
+
+/// Marks a request as in flight until dropped
+pub(crate) struct InFlightGuard {
+    inner: Arc<Inner>,
+}
+
+impl InFlight {
+    /// Admit a request, unless the server is draining
+    pub(crate) fn enter(&self) -> Option<InFlightGuard> {
+        // Count first, so `drain` either sees this request or we see the drain
+        self.inner.active.fetch_add(1, Ordering::SeqCst);
+        let guard = InFlightGuard {
+            inner: self.inner.clone(),
+        };
+        (!self.inner.draining.load(Ordering::SeqCst)).then_some(guard)
+    }
+
+    /// Stop admitting requests and wait up to `timeout` for admitted ones to finish
+    /// Returns whether they all finished in time
+    pub(crate) async fn drain(&self, timeout: Duration) -> bool {
+        self.inner.draining.store(true, Ordering::SeqCst);
+
+        tokio::time::timeout(timeout, async {
+            loop {
+                let idle = self.inner.idle.notified();
+                if self.active() == 0 {
+                    return;
+                }
+                idle.await;
+            }
+        })
+        .await
+        .is_ok()
+    }
+
+    pub(crate) fn active(&self) -> usize {
+        self.inner.active.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        if self.inner.active.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.inner.idle.notify_waiters();
+        }
+    }
+}
+
+/// Reject requests with 503 once shutdown has started draining
+pub(crate) async fn track_in_flight(
+    State(in_flight): State<InFlight>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(_guard) = in_flight.enter() else {
+        let status = StatusCode::SERVICE_UNAVAILABLE;
+        return (
+            status,
+            Json(json!({
+                "error": "Server is shutting down",
+                "code": status.as_u16(),
+            })),
+        )
+            .into_response();
+    };
+
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, body::Body, routing::get};
+    use tokio::sync::oneshot;
+    use tower::ServiceExt;
+
+    /// App whose `/slow` route answers once `release` fires
+    fn app(in_flight: &InFlight, release: oneshot::Receiver<()>) -> Router {
+        let release = Arc::new(tokio::sync::Mutex::new(Some(release)));
+        Router::new()
+            .route(
+                "/slow",
+                get(move || async move {
+                    if let Some(release) = release.lock().await.take() {
+                        let _ = release.await;
+                    }
+                    "done"
+                }),
+            )
+            .route("/fast", get(|| async { "fast" }))
+            .layer(axum::middleware::from_fn_with_state(
+                in_flight.clone(),
+                track_in_flight,
+            ))
+    }
+
+    fn get_request(path: &str) -> Request {
+        Request::builder().uri(path).body(Body::empty()).unwrap()
+    }
+
+    async fn wait_for_active(in_flight: &InFlight, count: usize) {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while in_flight.active() != count {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_drain_lets_in_flight_request_finish_and_rejects_new_ones() {
+        let in_flight = InFlight::default();
+        let (release, released) = oneshot::channel();
+        let app = app(&in_flight, released);
+
+        let slow = tokio::spawn(app.clone().oneshot(get_request("/slow")));
+        wait_for_active(&in_flight, 1).await;
+
+        let drain = tokio::spawn({
+            let in_flight = in_flight.clone();
+            async move { in_flight.drain(Duration::from_secs(5)).await }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!drain.is_finished());
+
+        let rejected = app.oneshot(get_request("/fast")).await.unwrap();
+        assert_eq!(rejected.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        release.send(()).unwrap();
+        let slow = slow.await.unwrap().unwrap();
+        assert_eq!(slow.status(), StatusCode::OK);
+        assert!(drain.await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_drain_gives_up_after_timeout() {
+        let in_flight = InFlight::default();
+        let (_release, released) = oneshot::channel();
+        let app = app(&in_flight, released);
+
+        let _slow = tokio::spawn(app.oneshot(get_request("/slow")));
+        wait_for_active(&in_flight, 1).await;
+
+        assert!(!in_flight.drain(Duration::from_millis(50)).await);
+    }
+
+    #[tokio::test]
+    async fn test_drain_without_requests_returns_immediately() {
+        let in_flight = InFlight::default();
+        assert!(in_flight.drain(Duration::from_millis(10)).await);
+        assert!(in_flight.enter().is_none());
+        assert_eq!(in_flight.active(), 0);
+    }
+}
@@ -0,0 +1,74 @@
+// Per-endpoint MCP routes (SSE bridges for local endpoints, reverse proxies for remote ones)
+// These are dispatched from the app's fallback rather than mounted as axum routes, so
+// endpoints added or removed through the management API take effect without a restart
+
+use super::handlers::ApiState;
+use super::middleware;
+use crate::endpoint::{EndpointKind, EndpointManager, HttpTransportAdapter};
+use crate::error::Result;
+use axum::{
+    Router,
+    extract::{Request, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use dashmap::DashMap;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+/// Routers for `/mcp/{path}`, keyed by endpoint path
+#[derive(Clone, Default)]
+pub struct EndpointRoutes {
+    routers: Arc<DashMap<String, Router>>,
+}
+
+impl EndpointRoutes {
+    /// Build the transport route for an endpoint and mount it at `/mcp/{path}`
+    pub(crate) async fn attach(
+        &self,
+        manager: &Arc<EndpointManager>,
+        path: &str,
+        endpoint_name: &str,
+    ) -> Result<()> {
+        let endpoint = manager.get_endpoint(endpoint_name)?;
+        let endpoint_guard = endpoint.read().await;
+        let router =
+            endpoint_guard.attach_http_route(Router::new(), path, manager.session_token())?;
+
+        // Local endpoints are started on the first SSE connection if needed,
+        // so stopped endpoints don't prevent the route from being mounted
+        let router = if matches!(*endpoint_guard, EndpointKind::Local(_)) {
+            router.layer(axum::middleware::from_fn_with_state(
+                (manager.clone(), Arc::<str>::from(endpoint_name)),
+                middleware::ensure_endpoint_running,
+            ))
+        } else {
+            router
+        };
+
+        self.routers.insert(path.to_string(), router);
+        Ok(())
+    }
+
+    /// Unmount the route at `/mcp/{path}`; in-flight requests finish on the old router
+    pub(crate) fn detach(&self, path: &str) {
+        self.routers.remove(path);
+    }
+
+    fn get(&self, request: &Request) -> Option<Router> {
+        let path = request.uri().path().strip_prefix("/mcp/")?;
+        let path = path.split('/').next()?;
+        self.routers.get(path).map(|router| router.clone())
+    }
+}
+
+/// Fallback handler forwarding `/mcp/{path}/...` to the endpoint's transport route
+pub(crate) async fn dispatch(State(state): State<ApiState>, request: Request) -> Response {
+    match state.endpoint_routes.get(&request) {
+        Some(router) => match router.oneshot(request).await {
+            Ok(response) => response,
+            Err(never) => match never {},
+        },
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
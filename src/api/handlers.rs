@@ -1,30 +1,105 @@
+use crate::api::endpoint_routes::EndpointRoutes;
+use crate::config::{AggregateToolNames, EndpointConfig};
 use crate::endpoint::EndpointManager;
+use crate::endpoint::registry::EndpointStatus;
 use crate::error::ProxyError;
+use crate::mcp::aggregate::{namespaced_tool_name, split_namespaced_tool_name};
+use crate::mcp::types::PromptGetRequest;
+use crate::mcp::{McpClient, ToolCallRequest, ToolCallResponse, ToolDefinition};
+use crate::metrics::{Metrics, Operation};
 use crate::routing::{PathRouter, tool_filter};
 use axum::{
     Json,
-    extract::{Path, State},
-    response::IntoResponse,
+    body::{Body, Bytes},
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode, header},
+    response::{IntoResponse, Response},
 };
+use futures::StreamExt;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
 use serde_json::{Value, json};
+use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::Duration;
-use tracing::info;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::{Span, field, info, instrument, warn};
+
+/// Calls of one batch in flight at a time
+const BATCH_CONCURRENCY: usize = 4;
 
 /// Application state shared across handlers
 #[derive(Clone)]
 pub struct ApiState {
     pub manager: Arc<EndpointManager>,
     pub router: Arc<PathRouter>,
+    /// Counters exported at /metrics, shared with the endpoint manager
+    pub metrics: Arc<Metrics>,
+    /// Transport routes under /mcp/{path}, attached and detached as endpoints come and go
+    pub endpoint_routes: EndpointRoutes,
     pub mcp_request_timeout: Duration,
+    /// Validate structured tool output against declared output schemas
+    pub validate_output: bool,
+    /// Reject tool calls whose arguments violate the tool's input schema
+    pub validate_arguments: bool,
+    /// Maximum nesting depth accepted in tool call payloads
+    pub max_json_depth: usize,
+    /// Include argument values in the tool call access log
+    pub log_tool_arguments: bool,
+    /// Naming of tools on `/mcp/tools` and the `/mcp` server
+    pub aggregate_tool_names: AggregateToolNames,
 }
 
-pub(crate) async fn health_check() -> impl IntoResponse {
-    Json(json!({
+#[derive(Default, Deserialize)]
+pub(crate) struct HealthQuery {
+    /// Also check every endpoint, answering 503 unless all are running or stopped
+    #[serde(default)]
+    deep: bool,
+}
+
+/// `GET /health`: liveness by default, endpoint health with `?deep=true`
+pub(crate) async fn health_check(
+    State(state): State<ApiState>,
+    Query(query): Query<HealthQuery>,
+) -> Response {
+    let mut body = json!({
         "status": "ok",
         "service": "rusted-tools",
         "version": env!("CARGO_PKG_VERSION"),
-    }))
+    });
+    if !query.deep {
+        return Json(body).into_response();
+    }
+
+    let mut healthy = true;
+    let mut endpoints = serde_json::Map::new();
+    for info in state.manager.list_endpoints() {
+        healthy &= matches!(
+            info.status,
+            EndpointStatus::Running | EndpointStatus::Stopped
+        );
+        // `/health` is public, so error details (stderr tails, upstream URLs) stay behind
+        // the authenticated `/servers/{name}/status`
+        endpoints.insert(info.name, json!({"status": info.status}));
+    }
+    body["endpoints"] = Value::Object(endpoints);
+    if healthy {
+        return Json(body).into_response();
+    }
+    body["status"] = json!("degraded");
+    (StatusCode::SERVICE_UNAVAILABLE, Json(body)).into_response()
+}
+
+/// `GET /ready`: 503 until every auto-start endpoint has had its first start attempt
+pub(crate) async fn readiness_check(State(state): State<ApiState>) -> Response {
+    if state.manager.is_ready() {
+        Json(json!({"status": "ready"})).into_response()
+    } else {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({"status": "starting"})),
+        )
+            .into_response()
+    }
 }
 
 pub(crate) async fn server_info() -> impl IntoResponse {
@@ -36,16 +111,45 @@ pub(crate) async fn server_info() -> impl IntoResponse {
     }))
 }
 
-pub(crate) async fn list_servers(State(state): State<ApiState>) -> impl IntoResponse {
+/// `GET /version`: what was built, from which commit and when
+pub(crate) async fn version() -> impl IntoResponse {
+    Json(json!({
+        "name": env!("CARGO_PKG_NAME"),
+        "version": env!("CARGO_PKG_VERSION"),
+        "git_hash": env!("GIT_HASH"),
+        "built_at": env!("BUILD_TIMESTAMP"),
+    }))
+}
+
+pub(crate) async fn metrics(
+    State(state): State<ApiState>,
+) -> Result<impl IntoResponse, ProxyError> {
+    let body = state.metrics.render(&state.manager.list_endpoints())?;
+    Ok(([(header::CONTENT_TYPE, prometheus::TEXT_FORMAT)], body))
+}
+
+/// `GET /servers`, narrowed to endpoints carrying every `tag=key=value` query parameter
+pub(crate) async fn list_servers(
+    State(state): State<ApiState>,
+    Query(query): Query<Vec<(String, String)>>,
+) -> impl IntoResponse {
+    let tags: Vec<&str> = query
+        .iter()
+        .filter(|(key, _)| key == "tag")
+        .map(|(_, value)| value.as_str())
+        .collect();
     let endpoints = state.manager.list_endpoints();
     let endpoint_list: Vec<Value> = endpoints
         .into_iter()
+        .filter(|info| info.metadata.has_tags(tags.iter().copied()))
         .map(|info| {
             json!({
                 "name": info.name,
                 "path": info.path,
                 "type": info.endpoint_type.to_string(),
                 "status": info.status.to_string(),
+                "tags": info.metadata.tags,
+                "filter": info.tool_filter,
             })
         })
         .collect();
@@ -59,15 +163,137 @@ pub(crate) async fn server_status(
     State(state): State<ApiState>,
     Path(name): Path<String>,
 ) -> Result<impl IntoResponse, ProxyError> {
+    // Served from the registry, so a slow start or stop doesn't hold up status
     let info = state.manager.get_endpoint_info(&name)?;
-    Ok(Json(json!({
+    let diagnostics = state.manager.get_endpoint_diagnostics(&name)?;
+    let mut body = json!({
         "name": info.name,
         "path": info.path,
         "type": info.endpoint_type.to_string(),
         "status": info.status.to_string(),
+        "started_at": info
+            .started_at
+            .map(|started| humantime::format_rfc3339_seconds(started).to_string()),
+        "uptime_seconds": info.uptime().map(|uptime| uptime.as_secs()),
+        "last_error": info.last_error,
+        "pid": info.pid,
+        "last_stderr": diagnostics.last_stderr(),
+        "url": diagnostics.url(),
+        "filter": info.tool_filter,
+    });
+    if let Some(reason) = info.status_reason {
+        body["reason"] = json!(reason);
+    }
+    if let Some(check) = info.last_health_check {
+        body["health_check"] = json!({
+            "checked_at": unix_secs(check.checked_at),
+            "ok": check.error.is_none(),
+            "error": check.error,
+        });
+    }
+    Ok(Json(body))
+}
+
+pub(crate) async fn server_capabilities(
+    State(state): State<ApiState>,
+    Path(name): Path<String>,
+) -> Result<impl IntoResponse, ProxyError> {
+    let info = state.manager.get_endpoint_info(&name)?;
+    // Capabilities are only known once the handshake has happened
+    let capabilities = state
+        .manager
+        .get_client(&name)
+        .await?
+        .capabilities()
+        .await?;
+
+    Ok(Json(json!({
+        "name": info.name,
+        "type": info.endpoint_type.to_string(),
+        "protocol_version": capabilities.protocol_version,
+        "server_info": {
+            "name": capabilities.server_name,
+            "version": capabilities.server_version,
+        },
+        "capabilities": {
+            "tools": capabilities.tools,
+            "resources": capabilities.resources,
+            "prompts": capabilities.prompts,
+            "logging": capabilities.logging,
+        },
+        "transports": {
+            "rest": format!("/mcp/{}/tools", info.path),
+            "sse": format!("/mcp/{}", info.path),
+        },
     })))
 }
 
+pub(crate) async fn server_tool(
+    State(state): State<ApiState>,
+    Path((name, tool_name)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, ProxyError> {
+    let info = state.manager.get_endpoint_info(&name)?;
+    let (client, filter) = state.router.get_client(&info.path).await?;
+    let filter = tool_filter::resolve_filter(filter, &headers);
+
+    let tools = list_upstream_tools(&state, &client).await?;
+
+    // Filtered-out tools are reported as missing so their existence isn't leaked
+    tool_filter::apply_tool_filter(tools, filter.as_ref())
+        .into_iter()
+        .find(|tool| tool.name == tool_name)
+        .map(Json)
+        .ok_or(ProxyError::ToolNotFound(tool_name))
+}
+
+/// Await an upstream MCP request under the request timeout, recording its latency
+/// Every upstream request counts against the endpoint's rate limit, then queues for a
+/// slot when the endpoint bounds its concurrency
+async fn upstream<T>(
+    state: &ApiState,
+    endpoint: &str,
+    operation: Operation,
+    request: impl Future<Output = Result<T, ProxyError>>,
+) -> Result<T, ProxyError> {
+    state.manager.check_rate_limit(endpoint)?;
+    let _slot = state
+        .manager
+        .acquire_request_slot(endpoint, state.mcp_request_timeout)
+        .await?;
+    let started = Instant::now();
+    let result = tokio::time::timeout(state.mcp_request_timeout, request)
+        .await
+        .unwrap_or_else(|_| Err(ProxyError::mcp_timeout(state.mcp_request_timeout)));
+    state
+        .metrics
+        .observe_request(endpoint, operation, started.elapsed(), &result);
+    result
+}
+
+/// List an endpoint's unfiltered tools, reusing a cached listing while it is fresh
+async fn list_upstream_tools(
+    state: &ApiState,
+    client: &McpClient,
+) -> Result<Vec<ToolDefinition>, ProxyError> {
+    let endpoint = client.server_name();
+    if let Some(tools) = state.manager.cached_tools(endpoint) {
+        return Ok(tools);
+    }
+
+    let tools = upstream(state, endpoint, Operation::List, client.list_tools()).await;
+    state.metrics.record_list_tools(endpoint, &tools);
+    let tools = tools?;
+    state.manager.cache_tools(endpoint, &tools);
+    Ok(tools)
+}
+
+fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
 pub(crate) async fn start_server(
     State(state): State<ApiState>,
     Path(name): Path<String>,
@@ -88,7 +314,7 @@ pub(crate) async fn stop_server(
 ) -> Result<impl IntoResponse, ProxyError> {
     info!("Received request to stop endpoint: {}", name);
 
-    state.manager.stop_endpoint(&name).await?;
+    state.manager.stop_endpoint_by_operator(&name).await?;
     Ok(Json(json!({
         "name": name,
         "action": "stop",
@@ -110,223 +336,2913 @@ pub(crate) async fn restart_server(
     })))
 }
 
-// MCP-specific handlers
-
-pub(crate) async fn mcp_list_tools(
+/// Re-read the config file and recreate the endpoint if its settings there changed
+pub(crate) async fn reload_server(
     State(state): State<ApiState>,
-    Path(path): Path<String>,
+    Path(name): Path<String>,
 ) -> Result<impl IntoResponse, ProxyError> {
-    let (client, filter) = state.router.get_client(&path).await?;
+    info!("Received request to reload endpoint: {}", name);
 
-    // Call list_tools on the actual MCP client
-    let tools = tokio::time::timeout(state.mcp_request_timeout, client.list_tools())
+    let current = state
+        .manager
+        .endpoint_config(&name)
+        .ok_or_else(|| ProxyError::server_not_found(name.clone()))?;
+    let path = state
+        .manager
+        .config_path()
+        .map(ToOwned::to_owned)
+        .ok_or_else(|| {
+            ProxyError::InvalidRequest("The proxy was not started from a config file".to_string())
+        })?;
+    // Reading and parsing the file blocks, so keep it off the async workers
+    let config = tokio::task::spawn_blocking(move || crate::config::load_config(path))
         .await
-        .map_err(|_| ProxyError::mcp_timeout(state.mcp_request_timeout))??;
+        .map_err(|e| ProxyError::Internal(format!("Config reload task failed: {}", e)))?
+        .map_err(|e| ProxyError::config(format!("{:#}", e)))?;
 
-    // Apply filter using the centralized function
-    let filtered_tools = tool_filter::apply_tool_filter(tools, filter.as_ref());
+    // An endpoint dropped from the file keeps running until it is removed explicitly
+    let reloaded = config
+        .endpoints
+        .into_iter()
+        .find(|endpoint| endpoint.name == name && endpoint.enabled)
+        .ok_or_else(|| ProxyError::server_not_found(name.clone()))?;
 
+    let changed = reloaded != current;
+    if changed {
+        let running = state.manager.get_endpoint_info(&name)?.status == EndpointStatus::Running;
+        super::reload::replace_endpoint(&state, reloaded, running).await?;
+    }
     Ok(Json(json!({
-        "server": client.server_name(),
-        "tools": filtered_tools,
-        "filter_active": filter.is_some()
+        "name": name,
+        "action": "reload",
+        "status": "success",
+        "changed": changed
     })))
 }
 
-pub(crate) async fn mcp_call_tool(
+#[derive(Deserialize)]
+pub(crate) struct AddServerQuery {
+    /// Start the endpoint once it is registered
+    #[serde(default)]
+    start: bool,
+}
+
+/// Register a new endpoint from an `EndpointConfig` body and mount its MCP route
+pub(crate) async fn add_server(
     State(state): State<ApiState>,
-    Path(path): Path<String>,
-    Json(payload): Json<Value>,
+    Query(query): Query<AddServerQuery>,
+    body: Bytes,
 ) -> Result<impl IntoResponse, ProxyError> {
-    let (client, filter) = state.router.get_client(&path).await?;
+    let config: EndpointConfig = parse_json_body(&state, &body)?;
+    let name = config.name.clone();
+    info!("Received request to add endpoint: {}", name);
 
-    // Parse the tool call request
-    let request: crate::mcp::ToolCallRequest =
-        serde_json::from_value(payload).map_err(ProxyError::invalid_request)?;
+    state.manager.add_endpoint(config).await?;
 
-    // Check if tool is allowed using the centralized function
-    if !tool_filter::is_tool_allowed(&request.name, filter.as_ref()) {
-        return Err(ProxyError::ToolNotAllowed(request.name));
+    let info = state.manager.get_endpoint_info(&name)?;
+    let mut result = Ok(());
+    for path in info.paths() {
+        result = state
+            .endpoint_routes
+            .attach(&state.manager, path, &name)
+            .await;
+        if result.is_err() {
+            break;
+        }
+    }
+    if result.is_ok()
+        && query.start
+        && state.manager.get_endpoint_info(&name)?.status != EndpointStatus::Running
+    {
+        result = state.manager.start_endpoint(&name).await;
+    }
+    if let Err(e) = result {
+        // Leave nothing behind for an endpoint the caller will see as not added
+        for path in info.paths() {
+            state.endpoint_routes.detach(path);
+        }
+        let _ = state.manager.remove_endpoint(&name).await;
+        return Err(e);
     }
 
-    // Call the tool
-    let response = tokio::time::timeout(state.mcp_request_timeout, client.call_tool(request))
-        .await
-        .map_err(|_| ProxyError::mcp_timeout(state.mcp_request_timeout))??;
-    Ok(Json(json!(response)))
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({
+            "name": name,
+            "action": "add",
+            "status": "success"
+        })),
+    ))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use axum::http::StatusCode;
-    use serde_json::Value;
-
-    async fn create_test_state() -> ApiState {
-        // Use a simple inline config for unit tests
-        use crate::config::{EndpointConfig, EndpointKindConfig};
-        use std::collections::HashMap;
-        use std::time::Duration;
+/// Stop an endpoint, unmount its MCP route and deregister it
+pub(crate) async fn remove_server(
+    State(state): State<ApiState>,
+    Path(name): Path<String>,
+) -> Result<impl IntoResponse, ProxyError> {
+    info!("Received request to remove endpoint: {}", name);
 
-        let manager = Arc::new(EndpointManager::new());
+    let info = state.manager.get_endpoint_info(&name)?;
+    for path in info.paths() {
+        state.endpoint_routes.detach(path);
+    }
+    state.manager.remove_endpoint(&name).await?;
+    Ok(Json(json!({
+        "name": name,
+        "action": "remove",
+        "status": "success"
+    })))
+}
 
-        let configs = vec![
-            EndpointConfig {
-                name: "test-local".to_string(),
-                endpoint_type: EndpointKindConfig::Local {
-                    command: "echo".to_string(),
-                    args: vec!["hello".to_string()],
-                    env: HashMap::new(),
-                    auto_start: true,
-                },
-                tools: None,
-            },
-            EndpointConfig {
-                name: "test-remote".to_string(),
-                endpoint_type: EndpointKindConfig::Remote {
-                    url: "http://localhost:8080".to_string(),
-                },
-                tools: None,
-            },
-        ];
+/// Body of a remote connectivity check
+#[derive(Deserialize)]
+struct TestRemoteRequest {
+    url: String,
+    #[serde(default)]
+    headers: HashMap<String, String>,
+}
 
-        manager.init_from_config(configs.clone()).await.unwrap();
+pub(crate) async fn test_remote(
+    State(state): State<ApiState>,
+    Json(payload): Json<Value>,
+) -> Result<impl IntoResponse, ProxyError> {
+    let request: TestRemoteRequest =
+        serde_json::from_value(payload).map_err(ProxyError::invalid_request)?;
+    info!("Received request to test remote endpoint: {}", request.url);
 
-        let router = Arc::new(PathRouter::new(manager.clone()));
+    let result = tokio::time::timeout(
+        state.mcp_request_timeout,
+        state.manager.test_remote(&request.url, &request.headers),
+    )
+    .await
+    .unwrap_or_else(|_| Err(ProxyError::mcp_timeout(state.mcp_request_timeout)));
 
-        ApiState {
-            manager,
-            router,
-            mcp_request_timeout: Duration::from_secs(30),
-        }
+    // Connection problems are the answer to the check, not a failure of the request
+    match result {
+        Ok(tool_count) => Ok(Json(json!({
+            "url": request.url,
+            "status": "success",
+            "tool_count": tool_count,
+        }))),
+        Err(e @ ProxyError::InvalidRequest(_)) => Err(e),
+        Err(e) => Ok(Json(json!({
+            "url": request.url,
+            "status": "failure",
+            "error": e.to_string(),
+        }))),
     }
+}
 
-    #[tokio::test]
-    async fn test_health_check() {
-        let response = health_check().await.into_response();
-        assert_eq!(response.status(), StatusCode::OK);
+// MCP-specific handlers
 
-        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
-            .await
-            .unwrap();
-        let json: Value = serde_json::from_slice(&body).unwrap();
+#[derive(Default, Deserialize)]
+pub(crate) struct ListToolsQuery {
+    cursor: Option<String>,
+    limit: Option<usize>,
+}
 
-        assert_eq!(json["status"], "ok");
-        assert_eq!(json["service"], "rusted-tools");
-        assert!(json["version"].is_string());
-    }
+#[instrument(skip_all, fields(path = %path, endpoint = field::Empty))]
+pub(crate) async fn mcp_list_tools(
+    State(state): State<ApiState>,
+    Path(path): Path<String>,
+    Query(query): Query<ListToolsQuery>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, ProxyError> {
+    let (client, filter) = state.router.get_client(&path).await?;
+    Span::current().record("endpoint", field::display(client.server_name()));
+    let filter = tool_filter::resolve_filter(filter, &headers);
 
-    #[tokio::test]
-    async fn test_server_info() {
-        let response = server_info().await.into_response();
-        assert_eq!(response.status(), StatusCode::OK);
+    if query.limit == Some(0) {
+        return Err(ProxyError::invalid_request("limit must be at least 1"));
+    }
+    let paged = query.cursor.is_some() || query.limit.is_some();
+    let (tools, next_cursor) = if paged {
+        let (upstream_cursor, skip) = parse_tools_cursor(query.cursor.as_deref())?;
+        let page = upstream(
+            &state,
+            client.server_name(),
+            Operation::List,
+            client.list_tools_page(upstream_cursor.clone()),
+        )
+        .await;
+        state.metrics.record_list_tools(client.server_name(), &page);
+        let page = page?;
 
-        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
-            .await
-            .unwrap();
-        let json: Value = serde_json::from_slice(&body).unwrap();
+        // The filter applies per page, so a page may come back shorter than `limit`
+        let mut tools: Vec<_> = tool_filter::apply_tool_filter(page.tools, filter.as_ref())
+            .into_iter()
+            .skip(skip)
+            .collect();
+        let next_cursor = match query.limit {
+            Some(limit) if tools.len() > limit => {
+                tools.truncate(limit);
+                Some(tools_cursor(upstream_cursor.as_deref(), skip + limit))
+            }
+            _ => page.next_cursor.map(|c| tools_cursor(Some(&c), 0)),
+        };
+        (tools, next_cursor)
+    } else {
+        let tools = list_upstream_tools(&state, &client).await?;
+        (tool_filter::apply_tool_filter(tools, filter.as_ref()), None)
+    };
+    let metadata = state.manager.get_endpoint_info_by_path(&path)?.metadata;
 
-        assert_eq!(json["name"], "rusted-tools");
-        assert!(json["version"].is_string());
-        assert!(json["description"].is_string());
+    let mut body = json!({
+        "server": client.server_name(),
+        "endpoint": {
+            "display_name": metadata.display_name,
+            "description": metadata.description,
+            "tags": metadata.tags,
+        },
+        "tools": tools,
+        "filter_active": filter.is_some()
+    });
+    if paged {
+        body["next_cursor"] = json!(next_cursor);
     }
+    Ok(Json(body))
+}
 
-    #[tokio::test]
-    async fn test_list_servers() {
-        let state = create_test_state().await;
-        let response = list_servers(State(state)).await.into_response();
+/// Cursor handed to REST clients: tools to skip on the upstream page at `upstream`,
+/// so a `limit` smaller than the upstream page can resume mid-page
+fn tools_cursor(upstream: Option<&str>, skip: usize) -> String {
+    format!("{}:{}", skip, upstream.unwrap_or_default())
+}
 
-        assert_eq!(response.status(), StatusCode::OK);
+fn parse_tools_cursor(cursor: Option<&str>) -> Result<(Option<String>, usize), ProxyError> {
+    let Some(cursor) = cursor else {
+        return Ok((None, 0));
+    };
+    let (skip, upstream) = cursor
+        .split_once(':')
+        .and_then(|(skip, upstream)| Some((skip.parse().ok()?, upstream)))
+        .ok_or_else(|| ProxyError::invalid_request(format!("malformed cursor '{}'", cursor)))?;
+    let upstream = (!upstream.is_empty()).then(|| upstream.to_string());
+    Ok((upstream, skip))
+}
 
-        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
-            .await
-            .unwrap();
-        let json: Value = serde_json::from_slice(&body).unwrap();
+/// List the tools of every running endpoint, named as `mcp.aggregate_tool_names` says
+/// Endpoints that aren't running or fail to answer are reported as warnings
+#[instrument(skip_all)]
+pub(crate) async fn mcp_list_all_tools(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, ProxyError> {
+    let (tools, warnings) = list_namespaced_tools(&state, &headers).await?;
+    let tools: Vec<Value> = tools
+        .into_iter()
+        .map(|namespaced| {
+            let mut entry = json!(namespaced.tool);
+            entry["endpoint"] = json!(namespaced.endpoint);
+            entry["original_name"] = json!(namespaced.original_name);
+            entry
+        })
+        .collect();
 
-        let servers = json["servers"].as_array().unwrap();
-        assert_eq!(servers.len(), 2);
+    Ok(Json(json!({
+        "tools": tools,
+        "warnings": warnings,
+    })))
+}
 
-        // Check local server
-        let local = servers.iter().find(|s| s["name"] == "test-local").unwrap();
-        assert_eq!(local["type"], "local");
-        assert_eq!(local["path"], "test-local");
+/// A tool of a running endpoint under its aggregate name
+pub(crate) struct NamespacedTool {
+    pub(crate) endpoint: String,
+    /// Path of the endpoint, which calls are routed to
+    pub(crate) path: String,
+    /// Name at the endpoint, after the endpoint's tool filter
+    pub(crate) endpoint_tool: String,
+    pub(crate) original_name: String,
+    pub(crate) tool: ToolDefinition,
+}
 
-        // Check remote server
-        let remote = servers.iter().find(|s| s["name"] == "test-remote").unwrap();
-        assert_eq!(remote["type"], "remote");
-        assert_eq!(remote["path"], "test-remote");
+/// The filtered tools of every running endpoint under aggregate names, plus a warning
+/// for each endpoint that was skipped or tool name that had to be prefixed; shared by
+/// `GET /mcp/tools` and the `/mcp` server
+/// Fails if two endpoints offer the same tool name with `aggregate_tool_names = "error"`
+pub(crate) async fn list_namespaced_tools(
+    state: &ApiState,
+    headers: &HeaderMap,
+) -> Result<(Vec<NamespacedTool>, Vec<String>), ProxyError> {
+    let (tools, warnings, collisions) = collect_aggregate_tools(state, headers).await;
+    if state.aggregate_tool_names == AggregateToolNames::Error
+        && let Some((name, endpoints)) = collisions.iter().min()
+    {
+        return Err(ProxyError::tool_name_conflict(name, endpoints));
     }
+    Ok((tools, warnings))
+}
 
-    #[tokio::test]
-    async fn test_server_status_found() {
-        let state = create_test_state().await;
-        let response = server_status(State(state), Path("test-local".to_string()))
-            .await
-            .unwrap()
-            .into_response();
+/// The named tools and warnings of `list_namespaced_tools`, plus the endpoints sharing
+/// each colliding name
+async fn collect_aggregate_tools(
+    state: &ApiState,
+    headers: &HeaderMap,
+) -> (Vec<NamespacedTool>, Vec<String>, HashMap<String, String>) {
+    let mut endpoints = state.manager.list_endpoints();
+    endpoints.sort_by(|a, b| a.path.cmp(&b.path));
 
-        assert_eq!(response.status(), StatusCode::OK);
+    let mut tools = Vec::new();
+    let mut warnings = Vec::new();
+    for info in endpoints {
+        if info.status != EndpointStatus::Running {
+            warnings.push(format!("Skipped endpoint {}: {}", info.name, info.status));
+            continue;
+        }
 
-        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
-            .await
-            .unwrap();
-        let json: Value = serde_json::from_slice(&body).unwrap();
+        let result = match state.router.get_client(&info.path).await {
+            Ok((client, _filter)) => list_upstream_tools(state, &client).await,
+            Err(e) => Err(e),
+        };
 
-        assert_eq!(json["name"], "test-local");
-        assert_eq!(json["type"], "local");
-        assert!(json["status"].is_string());
+        match result {
+            Ok(endpoint_tools) => {
+                let filter = tool_filter::resolve_filter(info.tool_filter, headers);
+                tools.extend(
+                    tool_filter::apply_tool_filter(endpoint_tools, filter.as_ref())
+                        .into_iter()
+                        .map(|tool| NamespacedTool {
+                            endpoint: info.name.clone(),
+                            path: info.path.clone(),
+                            endpoint_tool: tool.name.clone(),
+                            // The filter may have renamed it; report the upstream name
+                            original_name: filter
+                                .as_ref()
+                                .and_then(|filter| filter.upstream_name(&tool.name))
+                                .unwrap_or(&tool.name)
+                                .to_string(),
+                            tool,
+                        }),
+                );
+            }
+            Err(e) => {
+                warn!("Skipping tools of endpoint {}: {}", info.name, e);
+                warnings.push(format!("Skipped endpoint {}: {}", info.name, e));
+            }
+        }
     }
 
-    #[tokio::test]
-    async fn test_server_status_not_found() {
-        let state = create_test_state().await;
-        let result = server_status(State(state), Path("nonexistent".to_string())).await;
+    let collisions = name_aggregate_tools(&mut tools, state.aggregate_tool_names, &mut warnings);
+    (tools, warnings, collisions)
+}
 
-        assert!(result.is_err());
+/// Give each tool its aggregate name, returning the endpoints of each name offered by
+/// more than one endpoint; with `AggregateToolNames::Error` those keep their own name
+fn name_aggregate_tools(
+    tools: &mut [NamespacedTool],
+    naming: AggregateToolNames,
+    warnings: &mut Vec<String>,
+) -> HashMap<String, String> {
+    let mut owners: HashMap<&str, Vec<&str>> = HashMap::new();
+    for tool in tools.iter() {
+        owners
+            .entry(&tool.endpoint_tool)
+            .or_default()
+            .push(&tool.endpoint);
     }
+    let collisions: HashMap<String, String> = owners
+        .into_iter()
+        .filter(|(_, endpoints)| endpoints.len() > 1)
+        .map(|(name, endpoints)| (name.to_string(), endpoints.join(", ")))
+        .collect();
 
-    #[tokio::test]
-    async fn test_start_server_not_found() {
-        let state = create_test_state().await;
-        let result = start_server(State(state), Path("nonexistent".to_string())).await;
-
-        assert!(result.is_err());
+    let mut reported = std::collections::HashSet::new();
+    for tool in tools.iter_mut() {
+        let collides = collisions.get(&tool.endpoint_tool);
+        tool.tool.name = match (naming, collides) {
+            (AggregateToolNames::Namespaced, _) => {
+                namespaced_tool_name(&tool.path, &tool.endpoint_tool)
+            }
+            (AggregateToolNames::Disambiguate, Some(endpoints)) => {
+                if reported.insert(tool.endpoint_tool.clone()) {
+                    let warning = format!(
+                        "Tool {} is offered by endpoints {}; each is listed as <endpoint>:{}",
+                        tool.endpoint_tool, endpoints, tool.endpoint_tool
+                    );
+                    warn!("{}", warning);
+                    warnings.push(warning);
+                }
+                namespaced_tool_name(&tool.path, &tool.endpoint_tool)
+            }
+            _ => tool.endpoint_tool.clone(),
+        };
     }
 
-    #[tokio::test]
-    async fn test_stop_server_not_found() {
-        let state = create_test_state().await;
-        let result = stop_server(State(state), Path("nonexistent".to_string())).await;
+    collisions
+}
 
-        assert!(result.is_err());
+/// Endpoint path and endpoint tool name an aggregate tool name refers to
+///
+/// `<path>:<tool>` names an endpoint's tool in every naming mode and is split without
+/// asking any endpoint. Otherwise the name is looked up in the aggregate listing, so
+/// calls reach whichever endpoint it was listed for; only a name several endpoints
+/// share fails with `aggregate_tool_names = "error"`.
+pub(crate) async fn resolve_namespaced_tool(
+    state: &ApiState,
+    headers: &HeaderMap,
+    name: &str,
+) -> Result<(String, String), ProxyError> {
+    if state.aggregate_tool_names == AggregateToolNames::Namespaced {
+        let (path, tool) = split_namespaced_tool_name(name)?;
+        return Ok((path.to_string(), tool.to_string()));
+    }
+    if let Ok((path, tool)) = split_namespaced_tool_name(name)
+        && state.manager.get_endpoint_info_by_path(path).is_ok()
+    {
+        return Ok((path.to_string(), tool.to_string()));
     }
 
-    #[tokio::test]
-    async fn test_restart_server_not_found() {
-        let state = create_test_state().await;
-        let result = restart_server(State(state), Path("nonexistent".to_string())).await;
-
-        assert!(result.is_err());
+    let (tools, _warnings, collisions) = collect_aggregate_tools(state, headers).await;
+    if state.aggregate_tool_names == AggregateToolNames::Error
+        && let Some(endpoints) = collisions.get(name)
+    {
+        return Err(ProxyError::tool_name_conflict(name, endpoints));
     }
+    tools
+        .into_iter()
+        .find(|tool| tool.tool.name == name)
+        .map(|tool| (tool.path, tool.endpoint_tool))
+        .ok_or_else(|| ProxyError::ToolNotFound(name.to_string()))
+}
 
-    #[tokio::test]
-    async fn test_mcp_list_tools_server_not_found() {
-        let state = create_test_state().await;
-        let result = mcp_list_tools(State(state), Path("nonexistent".to_string())).await;
+#[instrument(skip_all, fields(path = %path, endpoint = field::Empty))]
+pub(crate) async fn mcp_tools_fingerprint(
+    State(state): State<ApiState>,
+    Path(path): Path<String>,
+) -> Result<impl IntoResponse, ProxyError> {
+    let (client, _filter) = state.router.get_client(&path).await?;
+    Span::current().record("endpoint", field::display(client.server_name()));
 
-        assert!(result.is_err());
-    }
+    // Fingerprint the unfiltered upstream list so filter changes don't mask upstream changes
+    let tools = list_upstream_tools(&state, &client).await?;
 
-    #[tokio::test]
-    async fn test_mcp_call_tool_server_not_found() {
-        let state = create_test_state().await;
-        let payload = json!({
+    Ok(Json(json!({
+        "server": client.server_name(),
+        "fingerprint": crate::mcp::fingerprint_tools(&tools),
+        "tool_count": tools.len(),
+    })))
+}
+
+#[derive(Default, Deserialize)]
+pub(crate) struct CallToolQuery {
+    /// Answer with newline-delimited JSON, one line per content block
+    #[serde(default)]
+    stream: bool,
+}
+
+#[instrument(skip_all, fields(path = %path, endpoint = field::Empty, tool = field::Empty))]
+pub(crate) async fn mcp_call_tool(
+    State(state): State<ApiState>,
+    Path(path): Path<String>,
+    Query(query): Query<CallToolQuery>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Response, ProxyError> {
+    let request: ToolCallRequest = parse_json_body(&state, &body)?;
+    Span::current().record("tool", field::display(&request.name));
+
+    let response = call_endpoint_tool(&state, &path, &headers, request).await?;
+    if query.stream {
+        Ok(ndjson_response(response))
+    } else {
+        Ok(Json(response.into_json()).into_response())
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct ToolCallBatch {
+    calls: Vec<ToolCallRequest>,
+}
+
+/// `POST /mcp/{path}/tools/call-batch`: run independent calls against one endpoint
+/// Each call is checked, timed out and logged on its own, so one failure doesn't fail the
+/// batch; results keep the order of `calls`
+#[instrument(skip_all, fields(path = %path, endpoint = field::Empty))]
+pub(crate) async fn mcp_call_tool_batch(
+    State(state): State<ApiState>,
+    Path(path): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<Value>, ProxyError> {
+    let batch: ToolCallBatch = parse_json_body(&state, &body)?;
+
+    let results: Vec<Value> = futures::stream::iter(batch.calls)
+        .map(|request| {
+            let (state, path, headers) = (&state, &path, &headers);
+            async move {
+                let name = request.name.clone();
+                let mut result = match call_endpoint_tool(state, path, headers, request).await {
+                    Ok(response) => response.into_json(),
+                    Err(e) => e.to_json(),
+                };
+                result["name"] = json!(name);
+                result
+            }
+        })
+        .buffered(BATCH_CONCURRENCY)
+        .collect()
+        .await;
+
+    Ok(Json(json!({ "results": results })))
+}
+
+/// Write a tool call response as NDJSON: one line per content block, then a
+/// `{"done": true, ...}` line with the remaining response fields
+///
+/// The upstream answers a call with a single JSON-RPC message, so the result is already
+/// fully buffered here; only the framing is incremental, with each block encoded as the
+/// client reads rather than the whole body up front.
+fn ndjson_response(mut call: ForwardedCall) -> Response {
+    let content = std::mem::take(&mut call.response.content);
+    let mut trailer = match call.into_json() {
+        Value::Object(fields) => fields,
+        _ => serde_json::Map::new(),
+    };
+    trailer.remove("content");
+    trailer.insert("done".to_string(), Value::Bool(true));
+    let trailer = Value::Object(trailer);
+
+    let blocks = content.into_iter().map(|block| serde_json::to_vec(&block));
+    let lines = blocks
+        .chain(std::iter::once(serde_json::to_vec(&trailer)))
+        .map(|line| {
+            let mut line = line?;
+            line.push(b'\n');
+            Ok::<_, serde_json::Error>(line)
+        });
+
+    (
+        [(header::CONTENT_TYPE, "application/x-ndjson")],
+        Body::from_stream(futures::stream::iter(lines)),
+    )
+        .into_response()
+}
+
+/// Call a tool by its aggregate name, routing it to the endpoint that offers it
+#[instrument(skip_all, fields(path = field::Empty, endpoint = field::Empty, tool = field::Empty))]
+pub(crate) async fn mcp_call_namespaced_tool(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<impl IntoResponse, ProxyError> {
+    let mut request: ToolCallRequest = parse_json_body(&state, &body)?;
+    let (path, tool) = resolve_namespaced_tool(&state, &headers, &request.name).await?;
+    Span::current().record("path", field::display(&path));
+    Span::current().record("tool", field::display(&tool));
+
+    request.name = tool;
+    call_endpoint_tool(&state, &path, &headers, request)
+        .await
+        .map(|response| Json(response.into_json()))
+}
+
+/// Parse a tool call payload, rejecting pathological nesting before the parser sees it
+fn parse_json_body<T: DeserializeOwned>(state: &ApiState, body: &[u8]) -> Result<T, ProxyError> {
+    if exceeds_json_depth(body, state.max_json_depth) {
+        return Err(ProxyError::invalid_request(format!(
+            "payload nesting exceeds maximum depth of {}",
+            state.max_json_depth
+        )));
+    }
+
+    serde_json::from_slice(body).map_err(ProxyError::invalid_request)
+}
+
+/// A forwarded tool call's response, with any output schema warnings
+pub(crate) struct ForwardedCall {
+    pub(crate) response: ToolCallResponse,
+    pub(crate) warnings: Vec<String>,
+}
+
+impl ForwardedCall {
+    /// Response body: the upstream result, plus `warnings` when there are any
+    fn into_json(self) -> Value {
+        let mut body = json!(self.response);
+        if !self.warnings.is_empty() {
+            body["warnings"] = json!(self.warnings);
+        }
+        body
+    }
+}
+
+/// Forward a tool call to the endpoint at `path`, then write its access log entry
+pub(crate) async fn call_endpoint_tool(
+    state: &ApiState,
+    path: &str,
+    headers: &HeaderMap,
+    request: ToolCallRequest,
+) -> Result<ForwardedCall, ProxyError> {
+    let started = Instant::now();
+    let tool = request.name.clone();
+    let argument_keys: Vec<String> = request
+        .arguments
+        .as_object()
+        .map(|arguments| arguments.keys().cloned().collect())
+        .unwrap_or_default();
+    let arguments = state
+        .log_tool_arguments
+        .then(|| request.arguments.to_string());
+
+    let result = forward_tool_call(state, path, headers, request).await;
+
+    let endpoint = state
+        .manager
+        .get_endpoint_info_by_path(path)
+        .map(|info| info.name)
+        .ok();
+    let status = match &result {
+        Ok(_) => StatusCode::OK,
+        Err(e) => e.status_code(),
+    };
+    info!(
+        endpoint = endpoint.as_deref(),
+        tool = %tool,
+        argument_keys = ?argument_keys,
+        arguments = arguments.as_deref(),
+        duration_ms = started.elapsed().as_millis() as u64,
+        status = status.as_u16(),
+        blocked = matches!(result, Err(ProxyError::ToolNotAllowed(_))),
+        "Tool call"
+    );
+    result
+}
+
+/// Forward a tool call to the endpoint at `path`, enforcing its tool filter
+async fn forward_tool_call(
+    state: &ApiState,
+    path: &str,
+    headers: &HeaderMap,
+    mut request: ToolCallRequest,
+) -> Result<ForwardedCall, ProxyError> {
+    let (client, filter) = state.router.get_client(path).await?;
+    Span::current().record("endpoint", field::display(client.server_name()));
+    let filter = tool_filter::resolve_filter(filter, headers);
+
+    // Check if tool is allowed using the centralized function, resolving aliases
+    let Some(upstream_name) = tool_filter::resolve_tool_name(&request.name, filter.as_ref()) else {
+        return Err(ProxyError::ToolNotAllowed(request.name));
+    };
+    request.name = upstream_name.to_string();
+
+    let tool_name = request.name.clone();
+    if state.validate_arguments {
+        let tools = list_upstream_tools(state, &client).await?;
+        if let Some(tool) = tools.iter().find(|t| t.name == tool_name) {
+            let violations =
+                crate::mcp::schema::schema_violations(&tool.input_schema, &request.arguments);
+            if !violations.is_empty() {
+                return Err(ProxyError::invalid_request(format!(
+                    "arguments do not match the input schema of '{}': {}",
+                    tool_name,
+                    violations.join("; ")
+                )));
+            }
+        }
+    }
+
+    // Call the tool
+    let response = upstream(
+        state,
+        client.server_name(),
+        Operation::Call,
+        client.call_tool(request),
+    )
+    .await;
+    state
+        .metrics
+        .record_tool_call(client.server_name(), &response);
+    let response = response?;
+
+    let mut warnings = Vec::new();
+    if state.validate_output
+        && let Some(structured) = &response.structured_content
+    {
+        // The call already ran, so a failed listing must not turn it into an error
+        match list_upstream_tools(state, &client).await {
+            Ok(tools) => {
+                let schema = tools
+                    .iter()
+                    .find(|t| t.name == tool_name)
+                    .and_then(|t| t.output_schema.as_ref());
+
+                if let Some(schema) = schema {
+                    warnings = output_warnings(schema, structured);
+                    if !warnings.is_empty() {
+                        warn!(tool = %tool_name, "Structured content does not match output schema");
+                    }
+                }
+            }
+            Err(e) => {
+                warn!(tool = %tool_name, "Skipping output validation, cannot list tools: {}", e);
+                warnings.push(format!("Output not validated: cannot list tools ({})", e));
+            }
+        }
+    }
+
+    Ok(ForwardedCall { response, warnings })
+}
+
+/// Check whether JSON nests arrays/objects deeper than `max_depth`
+/// Scans bytes iteratively, skipping string contents, so it can't overflow the stack
+fn exceeds_json_depth(body: &[u8], max_depth: usize) -> bool {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for &byte in body {
+        if in_string {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match byte {
+            b'"' => in_string = true,
+            b'{' | b'[' => {
+                depth += 1;
+                if depth > max_depth {
+                    return true;
+                }
+            }
+            b'}' | b']' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+
+    false
+}
+
+/// Describe how structured tool output violates the tool's output schema
+fn output_warnings(schema: &Value, structured: &Value) -> Vec<String> {
+    crate::mcp::schema::schema_violations(schema, structured)
+        .into_iter()
+        .map(|violation| format!("Output schema violation: {}", violation))
+        .collect()
+}
+
+#[instrument(skip_all, fields(path = %path, endpoint = field::Empty))]
+pub(crate) async fn mcp_list_resources(
+    State(state): State<ApiState>,
+    Path(path): Path<String>,
+) -> Result<impl IntoResponse, ProxyError> {
+    let (client, _filter) = state.router.get_client(&path).await?;
+    Span::current().record("endpoint", field::display(client.server_name()));
+
+    let resources = upstream(
+        &state,
+        client.server_name(),
+        Operation::ListResources,
+        client.list_resources(),
+    )
+    .await?;
+
+    Ok(Json(json!({
+        "server": client.server_name(),
+        "resources": resources,
+    })))
+}
+
+#[derive(Deserialize)]
+pub(crate) struct ReadResourceQuery {
+    uri: Option<String>,
+}
+
+#[instrument(skip_all, fields(path = %path, endpoint = field::Empty, uri = field::Empty))]
+pub(crate) async fn mcp_read_resource(
+    State(state): State<ApiState>,
+    Path(path): Path<String>,
+    Query(query): Query<ReadResourceQuery>,
+) -> Result<impl IntoResponse, ProxyError> {
+    let uri = query
+        .uri
+        .filter(|uri| !uri.is_empty())
+        .ok_or_else(|| ProxyError::InvalidRequest("Missing 'uri' query parameter".to_string()))?;
+    let (client, _filter) = state.router.get_client(&path).await?;
+    Span::current().record("endpoint", field::display(client.server_name()));
+    Span::current().record("uri", field::display(&uri));
+
+    let contents = upstream(
+        &state,
+        client.server_name(),
+        Operation::ReadResource,
+        client.read_resource(uri.clone()),
+    )
+    .await?;
+
+    Ok(Json(json!({
+        "server": client.server_name(),
+        "uri": uri,
+        "contents": contents,
+    })))
+}
+
+/// The upstream's initialize result as captured during the handshake
+pub(crate) async fn mcp_server_info(
+    State(state): State<ApiState>,
+    Path(path): Path<String>,
+) -> Result<impl IntoResponse, ProxyError> {
+    let (client, _filter) = state.router.get_client(&path).await?;
+    let info = client.server_info().await?;
+
+    let mut body = json!(info);
+    body["server"] = json!(client.server_name());
+    Ok(Json(body))
+}
+
+#[instrument(skip_all, fields(path = %path, endpoint = field::Empty))]
+pub(crate) async fn mcp_list_prompts(
+    State(state): State<ApiState>,
+    Path(path): Path<String>,
+) -> Result<impl IntoResponse, ProxyError> {
+    let (client, _filter) = state.router.get_client(&path).await?;
+    Span::current().record("endpoint", field::display(client.server_name()));
+
+    let prompts = upstream(
+        &state,
+        client.server_name(),
+        Operation::ListPrompts,
+        client.list_prompts(),
+    )
+    .await?;
+
+    Ok(Json(json!({
+        "server": client.server_name(),
+        "prompts": prompts,
+    })))
+}
+
+#[instrument(skip_all, fields(path = %path, endpoint = field::Empty, prompt = field::Empty))]
+pub(crate) async fn mcp_get_prompt(
+    State(state): State<ApiState>,
+    Path(path): Path<String>,
+    body: Bytes,
+) -> Result<impl IntoResponse, ProxyError> {
+    let request: PromptGetRequest = parse_json_body(&state, &body)?;
+    Span::current().record("prompt", field::display(&request.name));
+    let (client, _filter) = state.router.get_client(&path).await?;
+    Span::current().record("endpoint", field::display(client.server_name()));
+
+    let response = upstream(
+        &state,
+        client.server_name(),
+        Operation::GetPrompt,
+        client.get_prompt(request),
+    )
+    .await?;
+
+    Ok(Json(response))
+}
+
+#[instrument(skip_all, fields(path = %path, endpoint = field::Empty, task = %task_id))]
+pub(crate) async fn mcp_get_task(
+    State(state): State<ApiState>,
+    Path((path, task_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, ProxyError> {
+    let (client, _filter) = state.router.get_client(&path).await?;
+    Span::current().record("endpoint", field::display(client.server_name()));
+
+    let task = upstream(
+        &state,
+        client.server_name(),
+        Operation::GetTask,
+        client.get_task(task_id),
+    )
+    .await?;
+    Ok(Json(task))
+}
+
+#[instrument(skip_all, fields(path = %path, endpoint = field::Empty, task = %task_id))]
+pub(crate) async fn mcp_get_task_result(
+    State(state): State<ApiState>,
+    Path((path, task_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, ProxyError> {
+    let (client, _filter) = state.router.get_client(&path).await?;
+    Span::current().record("endpoint", field::display(client.server_name()));
+
+    let result = upstream(
+        &state,
+        client.server_name(),
+        Operation::GetTaskResult,
+        client.get_task_result(task_id),
+    )
+    .await?;
+    Ok(Json(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::StatusCode;
+    use axum::response::Response;
+    use serde_json::Value;
+
+    async fn create_test_state() -> ApiState {
+        // Use a simple inline config for unit tests
+        use crate::config::{EndpointConfig, EndpointKindConfig, ToolFilter};
+        use std::collections::HashMap;
+        use std::time::Duration;
+
+        let manager = Arc::new(EndpointManager::new());
+
+        let configs = vec![
+            EndpointConfig {
+                name: "test-local".to_string(),
+                aliases: Vec::new(),
+                endpoint_type: EndpointKindConfig::Local {
+                    command: "echo".to_string(),
+                    args: vec!["hello".to_string()],
+                    env: HashMap::new(),
+                    clean_env: false,
+                    auto_start: true,
+                    health_command: None,
+                    cwd: None,
+                    restart_on_failure: false,
+                    startup_retry: None,
+                    env_file: None,
+                },
+                tools: None,
+                rate_limit: None,
+                max_concurrent_requests: None,
+                enabled: true,
+                metadata: Default::default(),
+            },
+            EndpointConfig {
+                name: "test-remote".to_string(),
+                aliases: Vec::new(),
+                endpoint_type: EndpointKindConfig::Remote {
+                    url: "http://localhost:8080".to_string(),
+                    urls: Vec::new(),
+                    require_tools_on_start: false,
+                    headers: HashMap::new(),
+                    connect_timeout_secs: None,
+                    prewarm: false,
+                },
+                tools: Some(ToolFilter {
+                    include: Some(vec!["search".to_string()]),
+                    ..Default::default()
+                }),
+                rate_limit: None,
+                max_concurrent_requests: None,
+                enabled: true,
+                metadata: Default::default(),
+            },
+        ];
+
+        manager.init_from_config(configs.clone()).await.unwrap();
+
+        let router = Arc::new(PathRouter::new(manager.clone()));
+
+        ApiState {
+            metrics: manager.metrics(),
+            endpoint_routes: Default::default(),
+            manager,
+            router,
+            mcp_request_timeout: Duration::from_secs(30),
+            validate_output: false,
+            validate_arguments: false,
+            max_json_depth: 64,
+            log_tool_arguments: false,
+            aggregate_tool_names: AggregateToolNames::Namespaced,
+        }
+    }
+
+    /// Answers the handshake, then every tools/list and tools/call
+    const STUB_MCP_SERVER: &str = r#"
+while read -r line; do
+  id=$(echo "$line" | sed -n 's/.*"id":\([0-9]*\).*/\1/p')
+  case "$line" in
+    *notifications/*)
+      ;;
+    *initialize*)
+      echo "{\"jsonrpc\":\"2.0\",\"id\":$id,\"result\":{\"protocolVersion\":\"2025-06-18\",\"capabilities\":{\"tools\":{},\"resources\":{},\"prompts\":{}},\"serverInfo\":{\"name\":\"stub\",\"version\":\"0.0.0\"},\"instructions\":\"Call echo first\"}}"
+      ;;
+    *tools/list*)
+      echo "{\"jsonrpc\":\"2.0\",\"id\":$id,\"result\":{\"tools\":[{\"name\":\"echo\",\"inputSchema\":{\"type\":\"object\"}}]}}"
+      ;;
+    *tools/call*\"name\":\"chapters\"*)
+      echo "{\"jsonrpc\":\"2.0\",\"id\":$id,\"result\":{\"content\":[{\"type\":\"text\",\"text\":\"one\"},{\"type\":\"text\",\"text\":\"two\"},{\"type\":\"text\",\"text\":\"three\"}]}}"
+      ;;
+    *tools/call*\"name\":\"forecast\"*)
+      echo "{\"jsonrpc\":\"2.0\",\"id\":$id,\"result\":{\"content\":[{\"type\":\"text\",\"text\":\"sunny, 21C\"}],\"structuredContent\":{\"sky\":\"sunny\",\"celsius\":21}}}"
+      ;;
+    *tools/call*\"name\":\"nope\"*)
+      echo "{\"jsonrpc\":\"2.0\",\"id\":$id,\"error\":{\"code\":-32602,\"message\":\"tool not found\"}}"
+      ;;
+    *tools/call*\"name\":\"fail\"*)
+      echo "{\"jsonrpc\":\"2.0\",\"id\":$id,\"error\":{\"code\":-32602,\"message\":\"bad city\",\"data\":{\"field\":\"city\"}}}"
+      ;;
+    *tools/call*)
+      echo "{\"jsonrpc\":\"2.0\",\"id\":$id,\"result\":{\"content\":[{\"type\":\"text\",\"text\":\"ok\"}]}}"
+      ;;
+    *prompts/list*)
+      echo "{\"jsonrpc\":\"2.0\",\"id\":$id,\"result\":{\"prompts\":[{\"name\":\"greet\",\"arguments\":[{\"name\":\"who\",\"required\":true}]}]}}"
+      ;;
+    *prompts/get*)
+      echo "{\"jsonrpc\":\"2.0\",\"id\":$id,\"result\":{\"description\":\"Greeting\",\"messages\":[{\"role\":\"user\",\"content\":{\"type\":\"text\",\"text\":\"hello\"}}]}}"
+      ;;
+    *resources/list*)
+      echo "{\"jsonrpc\":\"2.0\",\"id\":$id,\"result\":{\"resources\":[{\"uri\":\"file:///notes.txt\",\"name\":\"notes\",\"mimeType\":\"text/plain\"}]}}"
+      ;;
+    *resources/read*)
+      echo "{\"jsonrpc\":\"2.0\",\"id\":$id,\"result\":{\"contents\":[{\"uri\":\"file:///notes.txt\",\"mimeType\":\"text/plain\",\"text\":\"hello\"},{\"uri\":\"file:///notes.txt\",\"blob\":\"aGVsbG8=\"}]}}"
+      ;;
+  esac
+done
+"#;
+
+    /// State with a running local endpoint named `stub` and a stopped one named `idle`
+    async fn create_stub_state() -> ApiState {
+        create_stub_state_with(EndpointManager::new()).await
+    }
+
+    async fn create_stub_state_with(manager: EndpointManager) -> ApiState {
+        let endpoints = vec![
+            stub_endpoint("stub", STUB_MCP_SERVER, true),
+            stub_endpoint("idle", STUB_MCP_SERVER, false),
+        ];
+        create_state_with_endpoints(manager, endpoints).await
+    }
+
+    /// Local endpoint running a shell stub server
+    fn stub_endpoint(name: &str, script: &str, auto_start: bool) -> EndpointConfig {
+        use crate::config::EndpointKindConfig;
+
+        EndpointConfig {
+            name: name.to_string(),
+            aliases: Vec::new(),
+            endpoint_type: EndpointKindConfig::Local {
+                command: "sh".to_string(),
+                args: vec!["-c".to_string(), script.to_string()],
+                env: HashMap::new(),
+                clean_env: false,
+                auto_start,
+                health_command: None,
+                cwd: None,
+                restart_on_failure: false,
+                startup_retry: None,
+                env_file: None,
+            },
+            tools: None,
+            rate_limit: None,
+            max_concurrent_requests: None,
+            enabled: true,
+            metadata: Default::default(),
+        }
+    }
+
+    async fn create_state_with_endpoints(
+        manager: EndpointManager,
+        endpoints: Vec<EndpointConfig>,
+    ) -> ApiState {
+        let manager = Arc::new(manager);
+        manager.init_from_config(endpoints).await.unwrap();
+
+        let router = Arc::new(PathRouter::new(manager.clone()));
+
+        ApiState {
+            metrics: manager.metrics(),
+            endpoint_routes: Default::default(),
+            manager,
+            router,
+            mcp_request_timeout: Duration::from_secs(30),
+            validate_output: false,
+            validate_arguments: false,
+            max_json_depth: 64,
+            log_tool_arguments: false,
+            aggregate_tool_names: AggregateToolNames::Namespaced,
+        }
+    }
+
+    /// Stub listing tools `a`, `b`, `c` on a first page and `d` on a second
+    const PAGED_STUB_MCP_SERVER: &str = r#"
+tool() { echo "{\"name\":\"$1\",\"inputSchema\":{\"type\":\"object\"}}"; }
+while read -r line; do
+  id=$(echo "$line" | sed -n 's/.*"id":\([0-9]*\).*/\1/p')
+  case "$line" in
+    *notifications/*)
+      ;;
+    *initialize*)
+      echo "{\"jsonrpc\":\"2.0\",\"id\":$id,\"result\":{\"protocolVersion\":\"2025-06-18\",\"capabilities\":{\"tools\":{}},\"serverInfo\":{\"name\":\"paged\",\"version\":\"0.0.0\"}}}"
+      ;;
+    *tools/list*\"cursor\":\"page-2\"*)
+      echo "{\"jsonrpc\":\"2.0\",\"id\":$id,\"result\":{\"tools\":[$(tool d)]}}"
+      ;;
+    *tools/list*)
+      echo "{\"jsonrpc\":\"2.0\",\"id\":$id,\"result\":{\"tools\":[$(tool a),$(tool b),$(tool c)],\"nextCursor\":\"page-2\"}}"
+      ;;
+  esac
+done
+"#;
+
+    async fn list_paged_tools(state: &ApiState, query: ListToolsQuery) -> Value {
+        let response = mcp_list_tools(
+            State(state.clone()),
+            Path("paged".to_string()),
+            Query(query),
+            HeaderMap::new(),
+        )
+        .await
+        .unwrap()
+        .into_response();
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    fn tool_names(json: &Value) -> Vec<&str> {
+        json["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["name"].as_str().unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_list_tools_pages_follow_upstream_cursor() {
+        let endpoints = vec![stub_endpoint("paged", PAGED_STUB_MCP_SERVER, true)];
+        let state = create_state_with_endpoints(EndpointManager::new(), endpoints).await;
+
+        // Without paging parameters every page is collected
+        let all = list_paged_tools(&state, ListToolsQuery::default()).await;
+        assert_eq!(tool_names(&all), ["a", "b", "c", "d"]);
+        assert!(all.get("next_cursor").is_none());
+
+        let first = list_paged_tools(
+            &state,
+            ListToolsQuery {
+                cursor: None,
+                limit: Some(10),
+            },
+        )
+        .await;
+        assert_eq!(tool_names(&first), ["a", "b", "c"]);
+
+        let second = list_paged_tools(
+            &state,
+            ListToolsQuery {
+                cursor: first["next_cursor"].as_str().map(str::to_string),
+                limit: Some(10),
+            },
+        )
+        .await;
+        assert_eq!(tool_names(&second), ["d"]);
+        assert!(second["next_cursor"].is_null());
+    }
+
+    #[tokio::test]
+    async fn test_list_tools_limit_resumes_mid_page() {
+        let mut endpoint = stub_endpoint("paged", PAGED_STUB_MCP_SERVER, true);
+        endpoint.tools = Some(crate::config::ToolFilter {
+            include: None,
+            exclude: Some(vec!["b".to_string()]),
+            ..Default::default()
+        });
+        let state = create_state_with_endpoints(EndpointManager::new(), vec![endpoint]).await;
+
+        let mut names = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = list_paged_tools(
+                &state,
+                ListToolsQuery {
+                    cursor,
+                    limit: Some(1),
+                },
+            )
+            .await;
+            assert!(page["tools"].as_array().unwrap().len() <= 1);
+            names.extend(tool_names(&page).into_iter().map(str::to_string));
+            cursor = page["next_cursor"].as_str().map(str::to_string);
+            if cursor.is_none() {
+                break;
+            }
+        }
+
+        // The filter applies to each page and nothing is skipped or repeated
+        assert_eq!(names, ["a", "c", "d"]);
+    }
+
+    #[tokio::test]
+    async fn test_list_tools_rejects_malformed_cursor() {
+        let state = create_stub_state().await;
+        let result = mcp_list_tools(
+            State(state),
+            Path("stub".to_string()),
+            Query(ListToolsQuery {
+                cursor: Some("not-a-cursor".to_string()),
+                limit: None,
+            }),
+            HeaderMap::new(),
+        )
+        .await;
+
+        assert!(matches!(result, Err(ProxyError::InvalidRequest(_))));
+    }
+
+    async fn call_stub_tool(state: &ApiState) {
+        mcp_call_tool(
+            State(state.clone()),
+            Path("stub".to_string()),
+            Query(Default::default()),
+            HeaderMap::new(),
+            Bytes::from(json!({"name": "echo", "arguments": {}}).to_string()),
+        )
+        .await
+        .unwrap();
+    }
+
+    async fn metrics_text(state: &ApiState) -> String {
+        let response = metrics(State(state.clone())).await.unwrap().into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(
+            response.headers()[header::CONTENT_TYPE]
+                .to_str()
+                .unwrap()
+                .starts_with("text/plain")
+        );
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        String::from_utf8(body.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_metrics_count_tool_calls() {
+        let state = create_stub_state().await;
+        call_stub_tool(&state).await;
+
+        let text = metrics_text(&state).await;
+        assert!(text.contains("# TYPE rusted_tools_tool_calls_total counter"));
+        assert!(
+            text.contains(r#"rusted_tools_tool_calls_total{endpoint="stub",result="success"} 1"#)
+        );
+        assert!(
+            text.contains(
+                r#"rusted_tools_endpoint_events_total{endpoint="stub",event="started"} 1"#
+            )
+        );
+        assert!(text.contains(r#"rusted_tools_endpoints{status="running"} 1"#));
+    }
+
+    #[tokio::test]
+    async fn test_list_all_tools_namespaces_running_endpoints() {
+        let state = create_stub_state().await;
+        let response = mcp_list_all_tools(State(state), HeaderMap::new())
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+
+        let tools = json["tools"].as_array().unwrap();
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0]["name"], "stub:echo");
+        assert_eq!(tools[0]["endpoint"], "stub");
+        assert_eq!(tools[0]["original_name"], "echo");
+
+        // The stopped endpoint is reported, not fatal
+        let warnings = json["warnings"].as_array().unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].as_str().unwrap().contains("idle"));
+    }
+
+    #[tokio::test]
+    async fn test_list_all_tools_reports_upstream_name_of_renamed_tool() {
+        let mut endpoint = stub_endpoint("stub", STUB_MCP_SERVER, true);
+        endpoint.tools = Some(crate::config::ToolFilter {
+            rename: HashMap::from([("echo".to_string(), "shout".to_string())]),
+            ..Default::default()
+        });
+        let state = create_state_with_endpoints(EndpointManager::new(), vec![endpoint]).await;
+
+        let response = mcp_list_all_tools(State(state), HeaderMap::new())
+            .await
+            .into_response();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+
+        let tools = json["tools"].as_array().unwrap();
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0]["name"], "stub:shout");
+        assert_eq!(tools[0]["original_name"], "echo");
+    }
+
+    /// State whose `stub` and `other` endpoints both offer `echo`, and whose `solo`
+    /// endpoint offers it renamed to `shout`
+    async fn create_colliding_state(naming: AggregateToolNames) -> ApiState {
+        let mut solo = stub_endpoint("solo", STUB_MCP_SERVER, true);
+        solo.tools = Some(crate::config::ToolFilter {
+            rename: HashMap::from([("echo".to_string(), "shout".to_string())]),
+            ..Default::default()
+        });
+        let endpoints = vec![
+            stub_endpoint("stub", STUB_MCP_SERVER, true),
+            stub_endpoint("other", STUB_MCP_SERVER, true),
+            solo,
+        ];
+        let mut state = create_state_with_endpoints(EndpointManager::new(), endpoints).await;
+        state.aggregate_tool_names = naming;
+        state
+    }
+
+    #[tokio::test]
+    async fn test_list_all_tools_disambiguates_colliding_names() {
+        let state = create_colliding_state(AggregateToolNames::Disambiguate).await;
+        let response = mcp_list_all_tools(State(state.clone()), HeaderMap::new())
+            .await
+            .unwrap()
+            .into_response();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+
+        let names: Vec<&str> = json["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|tool| tool["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["other:echo", "shout", "stub:echo"]);
+        let warnings = json["warnings"].as_array().unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert!(
+            warnings[0].as_str().unwrap().contains("other, stub"),
+            "{}",
+            json
+        );
+
+        // Calls use the listed names, and any tool can be called by its namespaced name
+        for name in ["stub:echo", "shout", "solo:shout"] {
+            let response = call_namespaced(&state, name).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK, "{}", name);
+        }
+        let err = call_namespaced(&state, "echo").await.unwrap_err();
+        assert!(matches!(err, ProxyError::ToolNotFound(ref name) if name == "echo"));
+    }
+
+    #[tokio::test]
+    async fn test_list_all_tools_rejects_colliding_names_when_configured() {
+        let state = create_colliding_state(AggregateToolNames::Error).await;
+        let err = mcp_list_all_tools(State(state.clone()), HeaderMap::new())
+            .await
+            .err()
+            .unwrap();
+        assert!(matches!(err, ProxyError::ToolNameConflict(_)));
+        assert_eq!(err.status_code(), StatusCode::CONFLICT);
+        assert!(err.to_string().contains("'echo'"), "{}", err);
+
+        // Only the colliding name fails; other tools and namespaced names still resolve
+        let err = call_namespaced(&state, "echo").await.unwrap_err();
+        assert!(matches!(err, ProxyError::ToolNameConflict(_)));
+        for name in ["shout", "stub:echo"] {
+            let response = call_namespaced(&state, name).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK, "{}", name);
+        }
+    }
+
+    async fn get_server_tool(state: &ApiState, tool: &str) -> Result<Response, ProxyError> {
+        server_tool(
+            State(state.clone()),
+            Path(("stub".to_string(), tool.to_string())),
+            HeaderMap::new(),
+        )
+        .await
+        .map(IntoResponse::into_response)
+    }
+
+    #[tokio::test]
+    async fn test_server_tool_returns_schema() {
+        let state = create_stub_state().await;
+        let response = get_server_tool(&state, "echo").await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["name"], "echo");
+        assert_eq!(json["input_schema"], json!({"type": "object"}));
+    }
+
+    #[tokio::test]
+    async fn test_server_tool_unknown_tool_is_not_found() {
+        let state = create_stub_state().await;
+        let err = get_server_tool(&state, "missing").await.unwrap_err();
+
+        assert_eq!(err.status_code(), StatusCode::NOT_FOUND);
+        assert!(matches!(err, ProxyError::ToolNotFound(ref name) if name == "missing"));
+    }
+
+    #[tokio::test]
+    async fn test_server_tool_hides_filtered_out_tool() {
+        let mut endpoint = stub_endpoint("stub", STUB_MCP_SERVER, true);
+        endpoint.tools = Some(crate::config::ToolFilter {
+            exclude: Some(vec!["echo".to_string()]),
+            ..Default::default()
+        });
+        let state = create_state_with_endpoints(EndpointManager::new(), vec![endpoint]).await;
+
+        let err = get_server_tool(&state, "echo").await.unwrap_err();
+        assert_eq!(err.status_code(), StatusCode::NOT_FOUND);
+    }
+
+    async fn call_namespaced(state: &ApiState, name: &str) -> Result<Response, ProxyError> {
+        mcp_call_namespaced_tool(
+            State(state.clone()),
+            HeaderMap::new(),
+            Bytes::from(json!({"name": name, "arguments": {}}).to_string()),
+        )
+        .await
+        .map(IntoResponse::into_response)
+    }
+
+    #[tokio::test]
+    async fn test_call_namespaced_tool_routes_to_endpoint() {
+        let state = create_stub_state().await;
+        let response = call_namespaced(&state, "stub:echo").await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["content"][0]["text"], "ok");
+    }
+
+    #[tokio::test]
+    async fn test_call_namespaced_tool_unknown_endpoint() {
+        let state = create_stub_state().await;
+        let err = call_namespaced(&state, "missing:echo").await.unwrap_err();
+
+        assert_eq!(err.status_code(), StatusCode::NOT_FOUND);
+        assert!(err.to_string().contains("missing"));
+    }
+
+    #[tokio::test]
+    async fn test_call_namespaced_tool_requires_prefix() {
+        let state = create_stub_state().await;
+        let err = call_namespaced(&state, "echo").await.unwrap_err();
+
+        assert_eq!(err.status_code(), StatusCode::BAD_REQUEST);
+        assert!(err.to_string().contains("<endpoint>:<tool>"));
+    }
+
+    #[tokio::test]
+    async fn test_mcp_list_resources_returns_upstream_resources() {
+        let state = create_stub_state().await;
+        let response = mcp_list_resources(State(state), Path("stub".to_string()))
+            .await
+            .unwrap()
+            .into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            json["resources"],
+            json!([{"uri": "file:///notes.txt", "name": "notes", "mimeType": "text/plain"}])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mcp_read_resource_returns_text_and_blob_contents() {
+        let state = create_stub_state().await;
+        let response = mcp_read_resource(
+            State(state),
+            Path("stub".to_string()),
+            Query(ReadResourceQuery {
+                uri: Some("file:///notes.txt".to_string()),
+            }),
+        )
+        .await
+        .unwrap()
+        .into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["uri"], "file:///notes.txt");
+        assert_eq!(
+            json["contents"],
+            json!([
+                {"uri": "file:///notes.txt", "mimeType": "text/plain", "text": "hello"},
+                {"uri": "file:///notes.txt", "mimeType": null, "blob": "aGVsbG8="},
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mcp_read_resource_requires_uri() {
+        let state = create_stub_state().await;
+        let result = mcp_read_resource(
+            State(state),
+            Path("stub".to_string()),
+            Query(ReadResourceQuery { uri: None }),
+        )
+        .await;
+
+        assert!(matches!(result, Err(ProxyError::InvalidRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_mcp_list_prompts_returns_upstream_prompts() {
+        let state = create_stub_state().await;
+        let response = mcp_list_prompts(State(state), Path("stub".to_string()))
+            .await
+            .unwrap()
+            .into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            json["prompts"],
+            json!([{"name": "greet", "arguments": [{"name": "who", "required": true}]}])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mcp_get_prompt_returns_rendered_messages() {
+        let state = create_stub_state().await;
+        let response = mcp_get_prompt(
+            State(state),
+            Path("stub".to_string()),
+            Bytes::from(json!({"name": "greet", "arguments": {"who": "world"}}).to_string()),
+        )
+        .await
+        .unwrap()
+        .into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["description"], "Greeting");
+        assert_eq!(
+            json["messages"],
+            json!([{"role": "user", "content": {"type": "text", "text": "hello"}}])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mcp_get_prompt_rejects_missing_name() {
+        let state = create_stub_state().await;
+        let result = mcp_get_prompt(
+            State(state),
+            Path("stub".to_string()),
+            Bytes::from(json!({"arguments": {}}).to_string()),
+        )
+        .await;
+
+        assert!(matches!(result, Err(ProxyError::InvalidRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_calls_over_rate_limit_get_429() {
+        use crate::config::{McpConfig, RateLimit};
+
+        let config = McpConfig {
+            rate_limit: Some(RateLimit {
+                requests_per_second: 1.0,
+                burst: 2,
+            }),
+            ..Default::default()
+        };
+        let state = create_stub_state_with(EndpointManager::from_config(&config)).await;
+
+        // The burst is admitted, the next call in the same instant is not
+        call_stub_tool(&state).await;
+        call_stub_tool(&state).await;
+        let response = mcp_call_tool(
+            State(state.clone()),
+            Path("stub".to_string()),
+            Query(Default::default()),
+            HeaderMap::new(),
+            Bytes::from(json!({"name": "echo", "arguments": {}}).to_string()),
+        )
+        .await
+        .into_response();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "1");
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["code"], 429);
+        assert_eq!(json["retry_after_secs"], 1);
+
+        // Every other upstream request draws from the same bucket
+        let response = mcp_list_tools(
+            State(state.clone()),
+            Path("stub".to_string()),
+            Query(Default::default()),
+            HeaderMap::new(),
+        )
+        .await
+        .into_response();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        let response = mcp_list_resources(State(state.clone()), Path("stub".to_string()))
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        let response = mcp_list_prompts(State(state.clone()), Path("stub".to_string()))
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        let err = get_server_tool(&state, "echo").await.unwrap_err();
+        assert!(matches!(err, ProxyError::RateLimited { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_endpoint_rate_limits_are_independent() {
+        use crate::config::{McpConfig, RateLimit};
+
+        let config = McpConfig {
+            rate_limit: Some(RateLimit {
+                requests_per_second: 1.0,
+                burst: 1,
+            }),
+            ..Default::default()
+        };
+        let manager = EndpointManager::from_config(&config);
+        let state = create_stub_state_with(manager).await;
+
+        assert!(state.manager.check_rate_limit("stub").is_ok());
+        assert!(state.manager.check_rate_limit("stub").is_err());
+        assert!(state.manager.check_rate_limit("idle").is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_call_waits_for_a_free_request_slot() {
+        use crate::config::McpConfig;
+
+        let config = McpConfig {
+            max_concurrent_requests: Some(1),
+            ..Default::default()
+        };
+        let state = create_stub_state_with(EndpointManager::from_config(&config)).await;
+
+        // Stand in for a first call still in flight
+        let first = state
+            .manager
+            .acquire_request_slot("stub", Duration::from_secs(1))
+            .await
+            .unwrap();
+        assert!(first.is_some());
+
+        let second = tokio::spawn({
+            let state = state.clone();
+            async move { call_stub_tool(&state).await }
+        });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!second.is_finished());
+
+        drop(first);
+        tokio::time::timeout(Duration::from_secs(5), second)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_call_without_free_request_slot_gets_503() {
+        use crate::config::McpConfig;
+
+        let config = McpConfig {
+            max_concurrent_requests: Some(1),
+            ..Default::default()
+        };
+        let mut state = create_stub_state_with(EndpointManager::from_config(&config)).await;
+        state.mcp_request_timeout = Duration::from_millis(100);
+
+        let _busy = state
+            .manager
+            .acquire_request_slot("stub", Duration::from_secs(1))
+            .await
+            .unwrap();
+        let response = mcp_list_tools(
+            State(state),
+            Path("stub".to_string()),
+            Query(Default::default()),
+            HeaderMap::new(),
+        )
+        .await
+        .into_response();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[tokio::test]
+    async fn test_mcp_server_info_returns_handshake_result() {
+        let state = create_stub_state().await;
+        let response = mcp_server_info(State(state.clone()), Path("stub".to_string()))
+            .await
+            .unwrap()
+            .into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["server"], "stub");
+        assert_eq!(json["protocolVersion"], "2025-06-18");
+        assert!(json["capabilities"]["tools"].is_object());
+        assert_eq!(json["serverInfo"]["name"], "stub");
+        assert_eq!(json["instructions"], "Call echo first");
+
+        let result = mcp_server_info(State(state.clone()), Path("idle".to_string())).await;
+        assert!(result.is_err());
+
+        // Cleared along with the connection
+        let client = state.manager.get_client("stub").await.unwrap();
+        state.manager.stop_endpoint("stub").await.unwrap();
+        assert!(matches!(
+            client.server_info().await,
+            Err(ProxyError::ServerNotRunning(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_path_alias_lists_same_tools() {
+        let mut endpoint = stub_endpoint("stub", STUB_MCP_SERVER, true);
+        endpoint.aliases = vec!["legacy-stub".to_string()];
+        let state = create_state_with_endpoints(EndpointManager::new(), vec![endpoint]).await;
+
+        let list = |path: &str| {
+            let state = state.clone();
+            let path = path.to_string();
+            async move {
+                let response = mcp_list_tools(
+                    State(state),
+                    Path(path),
+                    Query(Default::default()),
+                    HeaderMap::new(),
+                )
+                .await
+                .unwrap()
+                .into_response();
+                let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                serde_json::from_slice::<Value>(&body).unwrap()
+            }
+        };
+
+        let primary = list("stub").await;
+        let alias = list("legacy-stub").await;
+        assert_eq!(tool_names(&primary), ["echo"]);
+        assert_eq!(primary, alias);
+    }
+
+    #[tokio::test]
+    async fn test_renamed_tool_is_listed_and_called_by_alias() {
+        let mut endpoint = stub_endpoint("stub", STUB_MCP_SERVER, true);
+        endpoint.tools = Some(crate::config::ToolFilter {
+            rename: [("echo".to_string(), "shout".to_string())].into(),
+            ..Default::default()
+        });
+        let state = create_state_with_endpoints(EndpointManager::new(), vec![endpoint]).await;
+
+        let response = mcp_list_tools(
+            State(state.clone()),
+            Path("stub".to_string()),
+            Query(Default::default()),
+            HeaderMap::new(),
+        )
+        .await
+        .unwrap()
+        .into_response();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(tool_names(&json), ["shout"]);
+
+        let call = |name: &str| {
+            mcp_call_tool(
+                State(state.clone()),
+                Path("stub".to_string()),
+                Query(Default::default()),
+                HeaderMap::new(),
+                Bytes::from(json!({"name": name, "arguments": {}}).to_string()),
+            )
+        };
+        assert_eq!(call("shout").await.into_response().status(), StatusCode::OK);
+        assert_eq!(
+            call("echo").await.into_response().status(),
+            StatusCode::FORBIDDEN
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_tools_applies_description_override() {
+        let mut endpoint = stub_endpoint("stub", STUB_MCP_SERVER, true);
+        endpoint.tools = Some(crate::config::ToolFilter {
+            descriptions: [("echo".to_string(), "Repeat the input".to_string())].into(),
+            ..Default::default()
+        });
+        let state = create_state_with_endpoints(EndpointManager::new(), vec![endpoint]).await;
+
+        let response = mcp_list_tools(
+            State(state),
+            Path("stub".to_string()),
+            Query(Default::default()),
+            HeaderMap::new(),
+        )
+        .await
+        .unwrap()
+        .into_response();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["tools"][0]["description"], "Repeat the input");
+        assert_eq!(json["tools"][0]["input_schema"], json!({"type": "object"}));
+    }
+
+    #[tokio::test]
+    async fn test_list_tools_cached_until_restart() {
+        use crate::config::McpConfig;
+
+        let log = tempfile::NamedTempFile::new().unwrap();
+        let script = format!(
+            "LIST_LOG={}\n{}",
+            log.path().display(),
+            STUB_MCP_SERVER.replace(
+                "*tools/list*)",
+                "*tools/list*)\n      echo list >> \"$LIST_LOG\""
+            )
+        );
+        let manager = EndpointManager::from_config(&McpConfig {
+            tools_cache_secs: 60,
+            ..Default::default()
+        });
+        let state =
+            create_state_with_endpoints(manager, vec![stub_endpoint("stub", &script, true)]).await;
+
+        let list = || async {
+            mcp_list_tools(
+                State(state.clone()),
+                Path("stub".to_string()),
+                Query(Default::default()),
+                HeaderMap::new(),
+            )
+            .await
+            .unwrap();
+        };
+        let upstream_lists = || std::fs::read_to_string(log.path()).unwrap().lines().count();
+
+        list().await;
+        list().await;
+        assert_eq!(upstream_lists(), 1);
+
+        // The fingerprint hashes the same cached listing
+        let response = mcp_tools_fingerprint(State(state.clone()), Path("stub".to_string()))
+            .await
+            .unwrap()
+            .into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(upstream_lists(), 1);
+
+        state.manager.restart_endpoint("stub").await.unwrap();
+        list().await;
+        assert_eq!(upstream_lists(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_call_tool_streams_content_blocks_as_ndjson() {
+        let state = create_stub_state().await;
+        let response = mcp_call_tool(
+            State(state),
+            Path("stub".to_string()),
+            Query(CallToolQuery { stream: true }),
+            HeaderMap::new(),
+            Bytes::from(json!({"name": "chapters", "arguments": {}}).to_string()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "application/x-ndjson"
+        );
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let lines: Vec<Value> = std::str::from_utf8(&body)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                json!({"type": "text", "text": "one"}),
+                json!({"type": "text", "text": "two"}),
+                json!({"type": "text", "text": "three"}),
+                json!({"done": true}),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_call_tool_returns_structured_content() {
+        let state = create_stub_state().await;
+        let call = |name: &str| {
+            mcp_call_tool(
+                State(state.clone()),
+                Path("stub".to_string()),
+                Query(Default::default()),
+                HeaderMap::new(),
+                Bytes::from(json!({"name": name, "arguments": {}}).to_string()),
+            )
+        };
+
+        let response = call("forecast").await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            body["structured_content"],
+            json!({"sky": "sunny", "celsius": 21})
+        );
+        assert_eq!(body["content"][0]["text"], "sunny, 21C");
+
+        // Omitted when the upstream returns none
+        let response = call("echo").await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert!(body.get("structured_content").is_none(), "{}", body);
+    }
+
+    #[tokio::test]
+    async fn test_call_tool_succeeds_when_output_validation_cannot_list_tools() {
+        let script = STUB_MCP_SERVER.replace(
+            r#"\"result\":{\"tools\":[{\"name\":\"echo\",\"inputSchema\":{\"type\":\"object\"}}]}"#,
+            r#"\"error\":{\"code\":-32603,\"message\":\"listing broke\"}"#,
+        );
+        assert_ne!(script, STUB_MCP_SERVER);
+        let mut state = create_state_with_endpoints(
+            EndpointManager::new(),
+            vec![stub_endpoint("stub", &script, true)],
+        )
+        .await;
+        state.validate_output = true;
+
+        let response = mcp_call_tool(
+            State(state),
+            Path("stub".to_string()),
+            Query(Default::default()),
+            HeaderMap::new(),
+            Bytes::from(json!({"name": "forecast", "arguments": {}}).to_string()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["content"][0]["text"], "sunny, 21C");
+        let warning = body["warnings"][0].as_str().unwrap();
+        assert!(warning.starts_with("Output not validated"), "{}", warning);
+    }
+
+    /// Stub listing a single tool named after its first argument
+    const NAMED_TOOL_STUB_MCP_SERVER: &str = r#"
+while read -r line; do
+  id=$(echo "$line" | sed -n 's/.*"id":\([0-9]*\).*/\1/p')
+  case "$line" in
+    *notifications/*)
+      ;;
+    *initialize*)
+      echo "{\"jsonrpc\":\"2.0\",\"id\":$id,\"result\":{\"protocolVersion\":\"2025-06-18\",\"capabilities\":{\"tools\":{}},\"serverInfo\":{\"name\":\"stub\",\"version\":\"0.0.0\"}}}"
+      ;;
+    *tools/list*)
+      echo "{\"jsonrpc\":\"2.0\",\"id\":$id,\"result\":{\"tools\":[{\"name\":\"$1\",\"inputSchema\":{\"type\":\"object\"}}]}}"
+      ;;
+  esac
+done
+"#;
+
+    /// Config file with a `greeter` endpoint serving the tool `tool`
+    fn write_greeter_config(dir: &std::path::Path, tool: &str) -> std::path::PathBuf {
+        let script = dir.join("stub.sh");
+        std::fs::write(&script, NAMED_TOOL_STUB_MCP_SERVER).unwrap();
+        let path = dir.join("config.toml");
+        std::fs::write(
+            &path,
+            format!(
+                r#"
+[http]
+
+[[endpoints]]
+name = "greeter"
+type = "local"
+command = "sh"
+args = ["{}", "{}"]
+"#,
+                script.display(),
+                tool
+            ),
+        )
+        .unwrap();
+        path
+    }
+
+    async fn listed_tool_names(state: &ApiState, name: &str) -> Vec<String> {
+        let client = state.manager.get_client(name).await.unwrap();
+        let tools = client.list_tools().await.unwrap();
+        tools.into_iter().map(|tool| tool.name).collect()
+    }
+
+    #[tokio::test]
+    async fn test_reload_server_applies_changed_args() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_greeter_config(dir.path(), "hello");
+        let config = crate::config::load_config(&path).unwrap();
+        let state = create_state_with_endpoints(
+            EndpointManager::new().with_config_path(&path),
+            config.endpoints,
+        )
+        .await;
+        assert_eq!(listed_tool_names(&state, "greeter").await, ["hello"]);
+
+        let reload = |state: ApiState| async move {
+            let response = reload_server(State(state), Path("greeter".to_string()))
+                .await
+                .unwrap()
+                .into_response();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            serde_json::from_slice::<Value>(&body).unwrap()
+        };
+
+        // Nothing changed, so the endpoint is left alone
+        assert_eq!(reload(state.clone()).await["changed"], false);
+
+        write_greeter_config(dir.path(), "goodbye");
+        assert_eq!(reload(state.clone()).await["changed"], true);
+
+        let info = state.manager.get_endpoint_info("greeter").unwrap();
+        assert_eq!(info.status, EndpointStatus::Running);
+        assert_eq!(listed_tool_names(&state, "greeter").await, ["goodbye"]);
+
+        state.manager.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_reload_server_keeps_endpoint_dropped_from_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_greeter_config(dir.path(), "hello");
+        let config = crate::config::load_config(&path).unwrap();
+        let state = create_state_with_endpoints(
+            EndpointManager::new().with_config_path(&path),
+            config.endpoints,
+        )
+        .await;
+
+        std::fs::write(&path, "[http]\n").unwrap();
+        let err = reload_server(State(state.clone()), Path("greeter".to_string()))
+            .await
+            .err()
+            .unwrap();
+        assert!(matches!(err, ProxyError::ServerNotFound(_)));
+
+        let info = state.manager.get_endpoint_info("greeter").unwrap();
+        assert_eq!(info.status, EndpointStatus::Running);
+        assert_eq!(listed_tool_names(&state, "greeter").await, ["hello"]);
+
+        state.manager.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_reload_server_restores_endpoint_when_new_settings_fail() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_greeter_config(dir.path(), "hello");
+        let config = crate::config::load_config(&path).unwrap();
+        let state = create_state_with_endpoints(
+            EndpointManager::new().with_config_path(&path),
+            config.endpoints,
+        )
+        .await;
+        state
+            .manager
+            .add_endpoint(stub_endpoint("other", STUB_MCP_SERVER, false))
+            .await
+            .unwrap();
+
+        // The new alias collides with the endpoint added at runtime
+        write_greeter_config(dir.path(), "goodbye");
+        let mut file = std::fs::read_to_string(&path).unwrap();
+        file.push_str("aliases = [\"other\"]\n");
+        std::fs::write(&path, file).unwrap();
+
+        let err = reload_server(State(state.clone()), Path("greeter".to_string()))
+            .await
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("already used"), "{}", err);
+        assert_eq!(err.status_code(), StatusCode::CONFLICT);
+
+        let info = state.manager.get_endpoint_info("greeter").unwrap();
+        assert_eq!(info.status, EndpointStatus::Running);
+        assert!(info.aliases.is_empty());
+        assert_eq!(listed_tool_names(&state, "greeter").await, ["hello"]);
+
+        state.manager.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_reload_server_leaves_stopped_endpoint_stopped() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_greeter_config(dir.path(), "hello");
+        let config = crate::config::load_config(&path).unwrap();
+        let state = create_state_with_endpoints(
+            EndpointManager::new().with_config_path(&path),
+            config.endpoints,
+        )
+        .await;
+        state.manager.stop_endpoint("greeter").await.unwrap();
+
+        write_greeter_config(dir.path(), "goodbye");
+        reload_server(State(state.clone()), Path("greeter".to_string()))
+            .await
+            .unwrap();
+
+        let info = state.manager.get_endpoint_info("greeter").unwrap();
+        assert_eq!(info.status, EndpointStatus::Stopped);
+        let current = state.manager.endpoint_config("greeter").unwrap();
+        assert!(format!("{:?}", current.endpoint_type).contains("goodbye"));
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_adds_removes_and_changes_endpoints() {
+        let state = create_state_with_endpoints(
+            EndpointManager::new(),
+            vec![
+                stub_endpoint("kept", "cat", false),
+                stub_endpoint("changed", "cat", false),
+                stub_endpoint("dropped", "cat", false),
+            ],
+        )
+        .await;
+
+        let config = crate::config::AppConfig {
+            endpoints: vec![
+                stub_endpoint("kept", "cat", false),
+                stub_endpoint("changed", "cat -u", false),
+                stub_endpoint("added", "cat", false),
+                EndpointConfig {
+                    enabled: false,
+                    ..stub_endpoint("disabled", "cat", false)
+                },
+            ],
+            ..Default::default()
+        };
+        let summary = super::super::reload::reconcile(&state, &config).await;
+
+        assert_eq!(summary.added, ["added"]);
+        assert_eq!(summary.removed, ["dropped"]);
+        assert_eq!(summary.changed, ["changed"]);
+
+        let mut names: Vec<String> = state
+            .manager
+            .list_endpoints()
+            .into_iter()
+            .map(|info| info.name)
+            .collect();
+        names.sort();
+        assert_eq!(names, ["added", "changed", "kept"]);
+        assert_eq!(
+            state.manager.endpoint_config("changed"),
+            Some(stub_endpoint("changed", "cat -u", false))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_call_tool_batch_reports_each_result_in_order() {
+        let mut endpoint = stub_endpoint("stub", STUB_MCP_SERVER, true);
+        endpoint.tools = Some(crate::config::ToolFilter {
+            exclude: Some(vec!["fail".to_string()]),
+            ..Default::default()
+        });
+        let state = create_state_with_endpoints(EndpointManager::new(), vec![endpoint]).await;
+
+        let calls = json!({"calls": [
+            {"name": "echo", "arguments": {}},
+            {"name": "fail", "arguments": {}},
+            {"name": "nope", "arguments": {}},
+        ]});
+        let Json(body) = mcp_call_tool_batch(
+            State(state),
+            Path("stub".to_string()),
+            HeaderMap::new(),
+            Bytes::from(calls.to_string()),
+        )
+        .await
+        .unwrap();
+
+        let results = body["results"].as_array().unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0]["name"], "echo");
+        assert_eq!(
+            results[0]["content"],
+            json!([{"type": "text", "text": "ok"}])
+        );
+        assert!(results[0].get("error").is_none());
+
+        assert_eq!(results[1]["name"], "fail");
+        assert_eq!(results[1]["code"], 403);
+        assert!(results[1]["error"].is_string());
+        assert!(results[1].get("content").is_none());
+
+        assert_eq!(results[2]["name"], "nope");
+        assert_eq!(results[2]["code"], 404);
+        assert_eq!(results[2]["mcp"]["message"], "tool not found");
+    }
+
+    #[tokio::test]
+    async fn test_call_tool_returns_upstream_mcp_error() {
+        let state = create_stub_state().await;
+        let response = mcp_call_tool(
+            State(state),
+            Path("stub".to_string()),
+            Query(Default::default()),
+            HeaderMap::new(),
+            Bytes::from(json!({"name": "fail", "arguments": {}}).to_string()),
+        )
+        .await
+        .into_response();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["code"], 400);
+        assert_eq!(json["mcp"]["code"], -32602);
+        assert_eq!(json["mcp"]["message"], "bad city");
+        assert_eq!(json["mcp"]["data"], json!({"field": "city"}));
+    }
+
+    #[tokio::test]
+    async fn test_call_tool_arguments_checked_against_input_schema() {
+        let script = STUB_MCP_SERVER.replace(
+            r#"\"inputSchema\":{\"type\":\"object\"}"#,
+            r#"\"inputSchema\":{\"type\":\"object\",\"properties\":{\"city\":{\"type\":\"string\"}},\"required\":[\"city\"]}"#,
+        );
+        let mut state = create_state_with_endpoints(
+            EndpointManager::new(),
+            vec![stub_endpoint("stub", &script, true)],
+        )
+        .await;
+        state.validate_arguments = true;
+
+        let call = |arguments: Value| {
+            mcp_call_tool(
+                State(state.clone()),
+                Path("stub".to_string()),
+                Query(Default::default()),
+                HeaderMap::new(),
+                Bytes::from(json!({"name": "echo", "arguments": arguments}).to_string()),
+            )
+        };
+
+        let response = call(json!({})).await.into_response();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        let error = json["error"].as_str().unwrap();
+        assert!(
+            error.contains("\"city\" is a required property"),
+            "{}",
+            error
+        );
+        // Rejected before reaching the upstream
+        assert!(
+            !metrics_text(&state)
+                .await
+                .contains("rusted_tools_tool_calls_total{")
+        );
+
+        let response = call(json!({"city": "Oslo"})).await.into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_call_tool_logs_carry_request_fields() {
+        let state = create_stub_state().await;
+        call_stub_tool(&state).await;
+
+        // The upstream call runs on the runtime worker, outside the handler's task
+        logs_assert(|lines: &[&str]| {
+            let line = lines
+                .iter()
+                .find(|line| line.contains("Calling tool 'echo'"))
+                .ok_or("no tool call log")?;
+            for field in ["path=stub", "endpoint=stub", "tool=echo"] {
+                if !line.contains(field) {
+                    return Err(format!("missing {} in {}", field, line));
+                }
+            }
+            Ok(())
+        });
+    }
+
+    /// Check for `tool`'s access log line, with all of `present` and none of `absent` in it
+    fn access_log<'a>(
+        tool: &str,
+        present: &'a [&'a str],
+        absent: &'a [&'a str],
+    ) -> impl Fn(&[&str]) -> Result<(), String> + 'a {
+        let tool_field = format!("tool={}", tool);
+        move |lines: &[&str]| {
+            let line = lines
+                .iter()
+                .find(|line| line.contains("Tool call") && line.contains(&tool_field))
+                .ok_or("no access log")?;
+            if let Some(field) = present.iter().find(|field| !line.contains(*field)) {
+                return Err(format!("missing {} in {}", field, line));
+            }
+            if let Some(field) = absent.iter().find(|field| line.contains(*field)) {
+                return Err(format!("unexpected {} in {}", field, line));
+            }
+            Ok(())
+        }
+    }
+
+    async fn call_tool_with(state: &ApiState, name: &str, arguments: Value) {
+        let _ = mcp_call_tool(
+            State(state.clone()),
+            Path("stub".to_string()),
+            Query(Default::default()),
+            HeaderMap::new(),
+            Bytes::from(json!({"name": name, "arguments": arguments}).to_string()),
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_tool_call_access_log_redacts_argument_values() {
+        let mut endpoint = stub_endpoint("stub", STUB_MCP_SERVER, true);
+        endpoint.tools = Some(crate::config::ToolFilter {
+            exclude: Some(vec!["secret".to_string()]),
+            ..Default::default()
+        });
+        let state = create_state_with_endpoints(EndpointManager::new(), vec![endpoint]).await;
+
+        call_tool_with(&state, "echo", json!({"city": "Oslo"})).await;
+        logs_assert(access_log(
+            "echo",
+            &[
+                "endpoint=\"stub\"",
+                "argument_keys=[\"city\"]",
+                "duration_ms=",
+                "status=200",
+                "blocked=false",
+            ],
+            &["Oslo", "arguments="],
+        ));
+
+        call_tool_with(&state, "secret", json!({})).await;
+        logs_assert(access_log("secret", &["status=403", "blocked=true"], &[]));
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_tool_call_access_log_can_include_arguments() {
+        let mut state = create_stub_state().await;
+        state.log_tool_arguments = true;
+
+        call_tool_with(&state, "echo", json!({"city": "Oslo"})).await;
+        logs_assert(access_log("echo", &["arguments=", "Oslo"], &[]));
+    }
+
+    #[tokio::test]
+    async fn test_metrics_time_upstream_calls() {
+        let state = create_stub_state().await;
+        let samples = r#"rusted_tools_upstream_request_duration_seconds_count{endpoint="stub",operation="call"}"#;
+
+        call_stub_tool(&state).await;
+        assert!(
+            metrics_text(&state)
+                .await
+                .contains(&format!("{} 1", samples))
+        );
+
+        call_stub_tool(&state).await;
+        let text = metrics_text(&state).await;
+        assert!(text.contains(&format!("{} 2", samples)));
+        assert!(!text.contains("rusted_tools_upstream_request_errors_total{"));
+    }
+
+    #[tokio::test]
+    async fn test_health_check() {
+        let state = create_test_state().await;
+        let response = health_check(State(state), Query(Default::default())).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(json["status"], "ok");
+        assert_eq!(json["service"], "rusted-tools");
+        assert!(json["version"].is_string());
+        assert!(json.get("endpoints").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_deep_health_check_reports_failed_endpoint() {
+        // test-local is auto-started with a non-MCP command, so its start fails
+        let state = create_test_state().await;
+
+        let shallow = health_check(State(state.clone()), Query(Default::default())).await;
+        assert_eq!(shallow.status(), StatusCode::OK);
+
+        let response = health_check(State(state), Query(HealthQuery { deep: true })).await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["status"], "degraded");
+        assert_eq!(json["endpoints"]["test-local"]["status"], "failed");
+        assert!(json["endpoints"]["test-local"].get("last_error").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_deep_health_check_ok_when_endpoints_running() {
+        let state = create_stub_state().await;
+
+        let response = health_check(State(state), Query(HealthQuery { deep: true })).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["status"], "ok");
+        assert_eq!(json["endpoints"]["stub"]["status"], "running");
+    }
+
+    #[tokio::test]
+    async fn test_version_reports_build_metadata() {
+        let response = version().await.into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(json["version"], env!("CARGO_PKG_VERSION"));
+        assert!(json["git_hash"].as_str().is_some_and(|s| !s.is_empty()));
+        assert!(json["built_at"].as_str().is_some_and(|s| !s.is_empty()));
+    }
+
+    #[tokio::test]
+    async fn test_server_info() {
+        let response = server_info().await.into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(json["name"], "rusted-tools");
+        assert!(json["version"].is_string());
+        assert!(json["description"].is_string());
+    }
+
+    #[tokio::test]
+    async fn test_list_servers() {
+        let state = create_test_state().await;
+        let response = list_servers(State(state), Query(Vec::new()))
+            .await
+            .into_response();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+
+        let servers = json["servers"].as_array().unwrap();
+        assert_eq!(servers.len(), 2);
+
+        // Check local server
+        let local = servers.iter().find(|s| s["name"] == "test-local").unwrap();
+        assert_eq!(local["type"], "local");
+        assert_eq!(local["path"], "test-local");
+        assert!(local["filter"].is_null());
+
+        // Check remote server
+        let remote = servers.iter().find(|s| s["name"] == "test-remote").unwrap();
+        assert_eq!(remote["type"], "remote");
+        assert_eq!(remote["path"], "test-remote");
+        assert_eq!(remote["filter"]["include"], json!(["search"]));
+    }
+
+    #[tokio::test]
+    async fn test_server_status_reports_tool_filter() {
+        let state = create_test_state().await;
+        let response = server_status(State(state), Path("test-remote".to_string()))
+            .await
+            .unwrap()
+            .into_response();
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(json["filter"]["include"], json!(["search"]));
+        assert!(json["filter"]["exclude"].is_null());
+    }
+
+    #[tokio::test]
+    async fn test_server_status_found() {
+        let state = create_test_state().await;
+        let response = server_status(State(state), Path("test-local".to_string()))
+            .await
+            .unwrap()
+            .into_response();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(json["name"], "test-local");
+        assert_eq!(json["type"], "local");
+        assert!(json["status"].is_string());
+    }
+
+    #[tokio::test]
+    async fn test_server_status_reports_last_error_after_failed_start() {
+        // test-local is auto-started with a non-MCP command, so its start fails
+        let state = create_test_state().await;
+        let response = server_status(State(state), Path("test-local".to_string()))
+            .await
+            .unwrap()
+            .into_response();
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(json["status"], "failed");
+        assert!(json["last_error"].is_string());
+        assert!(json["uptime_seconds"].is_null());
+        assert!(json["started_at"].is_null());
+    }
+
+    #[tokio::test]
+    async fn test_server_status_reports_start_time_of_running_endpoint() {
+        let state = create_stub_state().await;
+        let response = server_status(State(state), Path("stub".to_string()))
+            .await
+            .unwrap()
+            .into_response();
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(json["status"], "running");
+        let started_at = humantime::parse_rfc3339(json["started_at"].as_str().unwrap()).unwrap();
+        assert!(started_at <= SystemTime::now());
+        assert!(json["uptime_seconds"].as_u64().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_server_status_not_found() {
+        let state = create_test_state().await;
+        let result = server_status(State(state), Path("nonexistent".to_string())).await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_start_server_not_found() {
+        let state = create_test_state().await;
+        let result = start_server(State(state), Path("nonexistent".to_string())).await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_stop_server_not_found() {
+        let state = create_test_state().await;
+        let result = stop_server(State(state), Path("nonexistent".to_string())).await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_restart_server_not_found() {
+        let state = create_test_state().await;
+        let result = restart_server(State(state), Path("nonexistent".to_string())).await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_mcp_list_tools_server_not_found() {
+        let state = create_test_state().await;
+        let result = mcp_list_tools(
+            State(state),
+            Path("nonexistent".to_string()),
+            Query(Default::default()),
+            HeaderMap::new(),
+        )
+        .await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_mcp_tools_fingerprint_server_not_found() {
+        let state = create_test_state().await;
+        let result = mcp_tools_fingerprint(State(state), Path("nonexistent".to_string())).await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_mcp_get_task_server_not_found() {
+        let state = create_test_state().await;
+        let result = mcp_get_task(
+            State(state),
+            Path(("nonexistent".to_string(), "task-1".to_string())),
+        )
+        .await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_task_polling_counts_against_rate_limit() {
+        use crate::config::{McpConfig, RateLimit};
+
+        let config = McpConfig {
+            rate_limit: Some(RateLimit {
+                requests_per_second: 1.0,
+                burst: 1,
+            }),
+            ..Default::default()
+        };
+        let state = create_stub_state_with(EndpointManager::from_config(&config)).await;
+        assert!(state.manager.check_rate_limit("stub").is_ok());
+
+        let task = Path(("stub".to_string(), "task-1".to_string()));
+        let response = mcp_get_task(State(state.clone()), task)
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        let task = Path(("stub".to_string(), "task-1".to_string()));
+        let response = mcp_get_task_result(State(state), task)
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    /// Answers task-augmented tool calls with a task handle; task `t-busy` never finishes
+    const TASK_STUB_MCP_SERVER: &str = r#"
+task() { echo "{\"taskId\":\"$1\",\"status\":\"$2\",\"createdAt\":\"2026-01-01T00:00:00Z\"}"; }
+while read -r line; do
+  id=$(echo "$line" | sed -n 's/.*"id":\([0-9]*\).*/\1/p')
+  case "$line" in
+    *notifications/*)
+      ;;
+    *initialize*)
+      echo "{\"jsonrpc\":\"2.0\",\"id\":$id,\"result\":{\"protocolVersion\":\"2025-06-18\",\"capabilities\":{\"tools\":{}},\"serverInfo\":{\"name\":\"tasks\",\"version\":\"0.0.0\"}}}"
+      ;;
+    *tools/call*\"task\":*)
+      echo "{\"jsonrpc\":\"2.0\",\"id\":$id,\"result\":{\"task\":$(task t-1 working)}}"
+      ;;
+    *tools/call*)
+      echo "{\"jsonrpc\":\"2.0\",\"id\":$id,\"result\":{\"content\":[{\"type\":\"text\",\"text\":\"ok\"}]}}"
+      ;;
+    *tasks/get*t-busy*)
+      echo "{\"jsonrpc\":\"2.0\",\"id\":$id,\"result\":{\"task\":$(task t-busy working)}}"
+      ;;
+    *tasks/get*)
+      echo "{\"jsonrpc\":\"2.0\",\"id\":$id,\"result\":{\"task\":$(task t-1 completed)}}"
+      ;;
+    *tasks/result*)
+      echo "{\"jsonrpc\":\"2.0\",\"id\":$id,\"result\":{\"content\":[{\"type\":\"text\",\"text\":\"finished\"}]}}"
+      ;;
+  esac
+done
+"#;
+
+    async fn response_json(response: Response) -> Value {
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_task_augmented_call_round_trips_through_task_routes() {
+        let endpoints = vec![stub_endpoint("tasks", TASK_STUB_MCP_SERVER, true)];
+        let state = create_state_with_endpoints(EndpointManager::new(), endpoints).await;
+
+        // The stub only answers with a task when the request carried one
+        let response = mcp_call_tool(
+            State(state.clone()),
+            Path("tasks".to_string()),
+            Query(Default::default()),
+            HeaderMap::new(),
+            Bytes::from(
+                json!({"name": "slow", "arguments": {}, "task": {"ttl": 60000}}).to_string(),
+            ),
+        )
+        .await
+        .unwrap();
+        let json = response_json(response).await;
+        assert_eq!(json["task"]["taskId"], "t-1");
+        assert_eq!(json["task"]["status"], "working");
+
+        let task = Path(("tasks".to_string(), "t-1".to_string()));
+        let response = mcp_get_task(State(state.clone()), task)
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        let json = response_json(response).await;
+        assert_eq!(json["task"]["taskId"], "t-1");
+        assert_eq!(json["task"]["status"], "completed");
+
+        let task = Path(("tasks".to_string(), "t-1".to_string()));
+        let response = mcp_get_task_result(State(state.clone()), task)
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        let json = response_json(response).await;
+        assert_eq!(json["content"][0]["text"], "finished");
+
+        // An unfinished task is refused instead of blocking the endpoint's worker
+        let task = Path(("tasks".to_string(), "t-busy".to_string()));
+        let response = mcp_get_task_result(State(state), task)
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+    }
+
+    #[test]
+    fn test_tool_call_request_accepts_task() {
+        let request: crate::mcp::ToolCallRequest = serde_json::from_value(json!({
+            "name": "slow_tool",
+            "arguments": {},
+            "task": {"ttl": 60000}
+        }))
+        .unwrap();
+
+        assert_eq!(request.task.unwrap()["ttl"], 60000);
+    }
+
+    #[cfg(feature = "arbitrary-precision")]
+    #[test]
+    fn test_tool_call_request_keeps_large_integers_exact() {
+        // 2^53 + 1 is the first integer an f64 can't hold; the rest exceed 64 bits
+        let payload = r#"{"name":"t","arguments":{"id":9007199254740993,"big":123456789012345678901234567890,"neg":-98765432109876543210}}"#;
+        let request: crate::mcp::ToolCallRequest = serde_json::from_str(payload).unwrap();
+
+        let forwarded = serde_json::to_string(&request.arguments).unwrap();
+        assert_eq!(
+            forwarded,
+            r#"{"big":123456789012345678901234567890,"id":9007199254740993,"neg":-98765432109876543210}"#
+        );
+    }
+
+    #[cfg(not(feature = "arbitrary-precision"))]
+    #[test]
+    fn test_tool_call_request_rounds_integers_beyond_64_bits() {
+        // Without the feature, integers outside i64/u64 are parsed as f64 and lose digits
+        let payload = r#"{"name":"t","arguments":{"big":123456789012345678901234567890}}"#;
+        let request: crate::mcp::ToolCallRequest = serde_json::from_str(payload).unwrap();
+
+        let forwarded = serde_json::to_string(&request.arguments).unwrap();
+        assert!(!forwarded.contains("123456789012345678901234567890"));
+        assert_eq!(
+            request.arguments["big"].as_f64(),
+            Some(123456789012345678901234567890.0)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mcp_call_tool_server_not_found() {
+        let state = create_test_state().await;
+        let payload = json!({
             "name": "test_tool",
             "arguments": {}
         });
-        let result =
-            mcp_call_tool(State(state), Path("nonexistent".to_string()), Json(payload)).await;
+        let result = mcp_call_tool(
+            State(state),
+            Path("nonexistent".to_string()),
+            Query(Default::default()),
+            HeaderMap::new(),
+            Bytes::from(payload.to_string()),
+        )
+        .await;
 
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_exceeds_json_depth() {
+        // The call payload itself is one level deep
+        assert!(!exceeds_json_depth(
+            br#"{"name":"t","arguments":{"a":[1]}}"#,
+            3
+        ));
+        assert!(exceeds_json_depth(
+            br#"{"name":"t","arguments":{"a":[[1]]}}"#,
+            3
+        ));
+
+        // Brackets inside strings don't count
+        assert!(!exceeds_json_depth(br#"{"a":"[[[[\"{{{{"}"#, 1));
+    }
+
+    #[tokio::test]
+    async fn test_mcp_call_tool_rejects_deep_nesting() {
+        let state = create_test_state().await;
+        let body = format!(
+            r#"{{"name":"t","arguments":{{"a":{}1{}}}}}"#,
+            "[".repeat(100),
+            "]".repeat(100)
+        );
+
+        let result = mcp_call_tool(
+            State(state),
+            Path("test-local".to_string()),
+            Query(Default::default()),
+            HeaderMap::new(),
+            Bytes::from(body),
+        )
+        .await;
+
+        assert!(matches!(result, Err(ProxyError::InvalidRequest(_))));
+    }
+
+    #[test]
+    fn test_output_warnings() {
+        let schema = json!({
+            "type": "object",
+            "properties": {"temperature": {"type": "number"}},
+            "required": ["temperature"]
+        });
+
+        assert!(output_warnings(&schema, &json!({"temperature": 21.5})).is_empty());
+
+        let warnings = output_warnings(&schema, &json!({"temperature": "warm"}));
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].starts_with("Output schema violation"));
+    }
 }
@@ -1,11 +1,48 @@
 // MCP SSE Service factory for creating HTTP/SSE endpoints for local MCP endpoints
 
-use crate::mcp::StdioBridge;
+use super::handlers::ApiState;
+use crate::config::{McpConfig, ToolFilter};
+use crate::mcp::{AggregateBridge, StdioBridge};
 use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
 use rmcp::transport::streamable_http_server::{StreamableHttpServerConfig, StreamableHttpService};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio_util::sync::CancellationToken;
 
+/// Keep-alive and reconnect timing of the MCP SSE streams served to clients
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SseSettings {
+    pub(crate) keep_alive: Option<Duration>,
+    pub(crate) retry: Option<Duration>,
+}
+
+impl Default for SseSettings {
+    fn default() -> Self {
+        Self::from(&McpConfig::default())
+    }
+}
+
+impl From<&McpConfig> for SseSettings {
+    fn from(config: &McpConfig) -> Self {
+        let secs = |secs| (secs > 0).then(|| Duration::from_secs(secs));
+        Self {
+            keep_alive: secs(config.sse_keep_alive_secs),
+            retry: secs(config.sse_retry_secs),
+        }
+    }
+}
+
+impl SseSettings {
+    fn server_config(&self, cancellation_token: CancellationToken) -> StreamableHttpServerConfig {
+        StreamableHttpServerConfig {
+            stateful_mode: true,
+            sse_keep_alive: self.keep_alive,
+            sse_retry: self.retry,
+            cancellation_token,
+        }
+    }
+}
+
 /// Create a StreamableHttpService for a local MCP endpoint
 /// This service will forward all MCP protocol messages to the stdio-based local MCP client
 ///
@@ -14,10 +51,13 @@ use tokio_util::sync::CancellationToken;
 pub(crate) fn create_local_sse_service(
     client: Arc<crate::mcp::McpClient>,
     server_name: String,
+    tools: Option<ToolFilter>,
+    sse: &SseSettings,
     cancellation_token: CancellationToken,
 ) -> StreamableHttpService<StdioBridge, LocalSessionManager> {
     let client_clone = client.clone();
     let server_name_clone = server_name.clone();
+    let tools = tools.map(Arc::new);
 
     // Create a factory function that creates a new bridge server instance
     // This will be called for each new SSE session
@@ -26,18 +66,54 @@ pub(crate) fn create_local_sse_service(
         Ok(StdioBridge::new(
             client_clone.clone(),
             server_name_clone.clone(),
+            tools.clone(),
         ))
     };
 
-    // Create the SSE service with default config
     StreamableHttpService::new(
         service_factory,
         Arc::new(LocalSessionManager::default()),
-        StreamableHttpServerConfig {
-            stateful_mode: true,
-            sse_keep_alive: Some(std::time::Duration::from_secs(15)),
-            sse_retry: Some(std::time::Duration::from_secs(3)),
-            cancellation_token,
-        },
+        sse.server_config(cancellation_token),
     )
 }
+
+/// Create a StreamableHttpService exposing all endpoints as one MCP server
+pub(crate) fn create_aggregate_sse_service(
+    state: ApiState,
+    sse: &SseSettings,
+    cancellation_token: CancellationToken,
+) -> StreamableHttpService<AggregateBridge, LocalSessionManager> {
+    let service_factory = move || Ok(AggregateBridge::new(state.clone()));
+
+    StreamableHttpService::new(
+        service_factory,
+        Arc::new(LocalSessionManager::default()),
+        sse.server_config(cancellation_token),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sse_settings_default_to_previous_timing() {
+        let sse = SseSettings::default();
+        assert_eq!(sse.keep_alive, Some(Duration::from_secs(15)));
+        assert_eq!(sse.retry, Some(Duration::from_secs(3)));
+    }
+
+    #[test]
+    fn test_sse_settings_reach_server_config() {
+        let sse = SseSettings::from(&McpConfig {
+            sse_keep_alive_secs: 45,
+            sse_retry_secs: 0,
+            ..Default::default()
+        });
+
+        let config = sse.server_config(CancellationToken::new());
+        assert!(config.stateful_mode);
+        assert_eq!(config.sse_keep_alive, Some(Duration::from_secs(45)));
+        assert_eq!(config.sse_retry, None);
+    }
+}
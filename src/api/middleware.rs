@@ -0,0 +1,166 @@
+// HTTP middleware applied to selected route groups
+
+use crate::config::{AuthConfig, PathMatching};
+use crate::endpoint::EndpointManager;
+use crate::endpoint::registry::EndpointStatus;
+use crate::routing::PathRouter;
+use axum::{
+    Json,
+    extract::{Request, State},
+    http::{StatusCode, Uri, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+use std::sync::Arc;
+use subtle::ConstantTimeEq;
+use tracing::{info, warn};
+
+/// Header carrying the admin key for mutating management operations
+pub(crate) const ADMIN_KEY_HEADER: &str = "x-admin-key";
+
+/// Reject requests that don't carry the configured admin key
+pub(crate) async fn require_admin_key(
+    State(admin_key): State<Arc<str>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let provided = request
+        .headers()
+        .get(ADMIN_KEY_HEADER)
+        .and_then(|value| value.to_str().ok());
+
+    // Constant-time, so response timing doesn't reveal how much of a guess was right
+    let authorized = provided
+        .is_some_and(|provided| bool::from(provided.as_bytes().ct_eq(admin_key.as_bytes())));
+    if !authorized {
+        let status = StatusCode::FORBIDDEN;
+        return (
+            status,
+            Json(json!({
+                "error": "Admin key required for this operation",
+                "code": status.as_u16(),
+            })),
+        )
+            .into_response();
+    }
+
+    next.run(request).await
+}
+
+/// Reject requests without an accepted `Authorization: Bearer` token, except on public paths
+pub(crate) async fn require_bearer_token(
+    State(auth): State<Arc<AuthConfig>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if auth.is_public(request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    let token = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    if !token.is_some_and(|token| auth.accepts(token)) {
+        let status = StatusCode::UNAUTHORIZED;
+        return (
+            status,
+            [(header::WWW_AUTHENTICATE, "Bearer")],
+            Json(json!({
+                "error": "Valid bearer token required",
+                "code": status.as_u16(),
+            })),
+        )
+            .into_response();
+    }
+
+    next.run(request).await
+}
+
+/// Start a stopped endpoint on first use, answering 503 if it can't be started
+/// Endpoints an operator stopped stay stopped until started explicitly
+pub(crate) async fn ensure_endpoint_running(
+    State((manager, name)): State<(Arc<EndpointManager>, Arc<str>)>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let is_running = |manager: &EndpointManager| {
+        manager
+            .get_endpoint_info(&name)
+            .is_ok_and(|info| info.status == EndpointStatus::Running)
+    };
+    let unavailable = |reason: String| {
+        let status = StatusCode::SERVICE_UNAVAILABLE;
+        (
+            status,
+            Json(json!({
+                "error": format!("Endpoint {} is unavailable: {}", name, reason),
+                "code": status.as_u16(),
+            })),
+        )
+            .into_response()
+    };
+
+    if !is_running(&manager) {
+        if manager
+            .get_endpoint_info(&name)
+            .is_ok_and(|info| info.stopped_by_operator)
+        {
+            return unavailable("stopped by an operator".to_string());
+        }
+        info!("Starting endpoint {} for incoming MCP connection", name);
+        // A concurrent request may have started it in the meantime
+        if let Err(e) = manager.start_endpoint(&name).await
+            && !is_running(&manager)
+        {
+            warn!("Failed to start endpoint {} on demand: {}", name, e);
+            return unavailable(e.to_string());
+        }
+    }
+
+    next.run(request).await
+}
+
+/// Rewrite `/mcp/{path}/...` to the registered form of `{path}` before routing, as
+/// configured by `mcp.path_matching`
+pub(crate) async fn normalize_mcp_path(
+    State(router): State<Arc<PathRouter>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    if let Some(rewritten) = rewrite_mcp_path(&router, request.uri())
+        && let Ok(uri) = rewritten.parse()
+    {
+        *request.uri_mut() = uri;
+    }
+    next.run(request).await
+}
+
+fn rewrite_mcp_path(router: &PathRouter, uri: &Uri) -> Option<String> {
+    let rest = uri.path().strip_prefix("/mcp/")?;
+    let canonical = |segment: &str| {
+        router
+            .canonical_path(segment)
+            .unwrap_or_else(|| segment.to_string())
+    };
+
+    let mut path = match rest.split_once('/') {
+        Some((segment, tail)) => format!("/mcp/{}/{}", canonical(segment), tail),
+        None => format!("/mcp/{}", canonical(rest)),
+    };
+    if router.matching() == PathMatching::NormalizeTrailingSlash {
+        let trimmed = path.trim_end_matches('/').len().max("/mcp".len());
+        path.truncate(trimmed);
+    }
+    if path == uri.path() {
+        return None;
+    }
+    if let Some(query) = uri.query() {
+        path.push('?');
+        path.push_str(query);
+    }
+    Some(path)
+}
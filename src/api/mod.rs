@@ -1,117 +1,262 @@
+pub(crate) mod drain;
+pub(crate) mod endpoint_routes;
 pub mod handlers;
 pub(crate) mod mcp_sse_service;
+pub(crate) mod middleware;
+pub(crate) mod reload;
+pub(crate) mod request_id;
 pub mod routes;
+pub(crate) mod tls;
 
-use crate::config::AppConfig;
-use crate::endpoint::{EndpointManager, HttpTransportAdapter};
+use crate::config::{AppConfig, PathMatching};
+use crate::endpoint::EndpointManager;
 use crate::routing::PathRouter;
 use anyhow::Result;
 use axum::Router;
+use axum::extract::Request;
+use drain::InFlight;
 use handlers::ApiState;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio_util::sync::CancellationToken;
-use tower_http::{cors::CorsLayer, trace::TraceLayer};
-use tracing::info;
+use tower_http::{
+    cors::CorsLayer,
+    decompression::RequestDecompressionLayer,
+    trace::{DefaultOnRequest, DefaultOnResponse, TraceLayer},
+};
+use tracing::{Level, info};
 
 pub async fn start_server(config: AppConfig) -> Result<()> {
     let addr = format!("{}:{}", config.http.host, config.http.port);
 
-    // Initialize endpoint manager
-    let manager = Arc::new(EndpointManager::new_with_restart_delay(
-        Duration::from_millis(config.mcp.restart_delay_ms),
-    ));
-    manager.init_from_config(config.endpoints.clone()).await?;
-
-    // Initialize router
-    let router = Arc::new(PathRouter::new(manager.clone()));
+    // Load certificates before starting any endpoints, so a bad path fails fast
+    let tls_config = config.tls.as_ref().map(tls::server_config).transpose()?;
+    let scheme = if tls_config.is_some() {
+        "https"
+    } else {
+        "http"
+    };
 
-    // Get routes before moving router into state
-    let routes = router.list_routes();
+    let drain_timeout = Duration::from_secs(config.mcp.shutdown_drain_secs);
 
-    // Create app state
-    let state = ApiState {
-        manager: manager.clone(),
-        router,
-        mcp_request_timeout: Duration::from_secs(config.mcp.request_timeout_secs),
-    };
+    #[cfg(unix)]
+    let reload_from = config.source.clone();
 
-    // Build the application
-    let app = build_router(state).await?;
+    // Build the application, counting every request so shutdown can wait for them
+    // and turn new ones away
+    let (app, state) = build_app_with_state(config).await?;
+    let manager = state.manager.clone();
+    #[cfg(unix)]
+    if let Some(path) = reload_from {
+        tokio::spawn(reload::reload_on_sighup(state, path));
+    }
+    let in_flight = InFlight::default();
+    let app = app.layer(axum::middleware::from_fn_with_state(
+        in_flight.clone(),
+        drain::track_in_flight,
+    ));
+    let routes = PathRouter::new(manager.clone()).list_mounts();
 
     // Create TCP listener
     let listener = tokio::net::TcpListener::bind(&addr).await?;
 
-    info!("HTTP server listening on {}", addr);
-    info!("Health check: http://{}/health", addr);
-    info!("Server info: http://{}/info", addr);
-    info!("Server list: http://{}/servers", addr);
+    info!("{} server listening on {}", scheme.to_uppercase(), addr);
+    info!("Health check: {}://{}/health", scheme, addr);
+    info!("Server info: {}://{}/info", scheme, addr);
+    info!("Server list: {}://{}/servers", scheme, addr);
     info!("");
     info!("MCP endpoints available at:");
     for (path, endpoint_name) in routes {
         info!(
-            "  → http://{}/mcp/{} (endpoint: {})",
-            addr, path, endpoint_name
+            "  → {}://{}/mcp/{} (endpoint: {})",
+            scheme, addr, path, endpoint_name
         );
     }
 
     // Start the server
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal(manager))
-        .await?;
+    let shutdown = shutdown_signal(manager, in_flight, drain_timeout);
+    match tls_config {
+        Some(tls_config) => {
+            let listener = tls::TlsListener::new(listener, tls_config)?;
+            axum::serve(listener, tls::WithClientCertificate(app))
+                .with_graceful_shutdown(shutdown)
+                .await?
+        }
+        None => {
+            axum::serve(listener, app)
+                .with_graceful_shutdown(shutdown)
+                .await?
+        }
+    }
 
     Ok(())
 }
 
-async fn build_router(state: ApiState) -> Result<Router> {
-    let ct = CancellationToken::new();
+/// Register the configured endpoints and build the fully layered app, without binding a socket
+///
+/// Auto-start endpoints are started and prewarm endpoints connected in the background.
+/// [`EndpointManager::is_ready`] turns true once each has been tried.
+///
+/// Use this to embed the proxy in another axum app. Call [`EndpointManager::shutdown`] on
+/// the returned manager when done, so local endpoints are stopped.
+pub async fn build_app(config: AppConfig) -> Result<(Router, Arc<EndpointManager>)> {
+    let (app, state) = build_app_with_state(config).await?;
+    Ok((app, state.manager))
+}
+
+async fn build_app_with_state(config: AppConfig) -> Result<(Router, ApiState)> {
+    if config.mcp.validate_arguments && config.mcp.tools_cache_secs == 0 {
+        tracing::warn!(
+            "validate_arguments is on without tools_cache_secs: every tool call lists the upstream's tools first"
+        );
+    }
+
+    let mut manager = EndpointManager::from_config(&config.mcp).with_webhooks(&config.webhooks);
+    if let Some(path) = &config.source {
+        manager = manager.with_config_path(path);
+    }
+    let manager = Arc::new(manager);
+    let initial = manager.register_from_config(config.endpoints.clone())?;
+
+    let state = ApiState {
+        manager: manager.clone(),
+        router: Arc::new(PathRouter::new(manager.clone()).with_matching(config.mcp.path_matching)),
+        metrics: manager.metrics(),
+        endpoint_routes: Default::default(),
+        mcp_request_timeout: Duration::from_secs(config.mcp.request_timeout_secs),
+        validate_output: config.mcp.validate_output,
+        validate_arguments: config.mcp.validate_arguments,
+        max_json_depth: config.mcp.max_json_depth,
+        log_tool_arguments: config.logging.log_tool_arguments,
+        aggregate_tool_names: config.mcp.aggregate_tool_names,
+    };
+
+    let app = build_router(state.clone(), &config).await?;
 
+    // Serve while endpoints come up; `/ready` reports when they have
+    tokio::spawn(async move { manager.start_initial(initial).await });
+    Ok((app, state))
+}
+
+async fn build_router(state: ApiState, config: &AppConfig) -> Result<Router> {
     // Start with base routes
     let mut app = Router::new()
         .merge(routes::health_routes())
-        .merge(routes::management_routes())
+        .merge(routes::management_routes(&config.management))
         .merge(routes::mcp_routes());
 
-    // Add MCP endpoints via transport adapters
-    let routes = state.router.list_routes();
-    for (path, endpoint_name) in routes {
-        // Get endpoint instance
-        let endpoint = match state.manager.get_endpoint(&endpoint_name) {
-            Ok(endpoint) => endpoint,
-            Err(e) => {
-                tracing::warn!("Skipping endpoint for {}: {}", endpoint_name, e);
-                continue;
-            }
-        };
-
-        let endpoint_guard = endpoint.read().await;
-
-        // attach_http_route takes ownership of the router
-        let result = endpoint_guard.attach_http_route(app, &path, ct.child_token());
+    // Unified MCP server over all endpoints; an exact route so it doesn't shadow /mcp/{path}
+    app = app.route_service(
+        "/mcp",
+        mcp_sse_service::create_aggregate_sse_service(
+            state.clone(),
+            &mcp_sse_service::SseSettings::from(&config.mcp),
+            state.manager.session_token(),
+        ),
+    );
+
+    // Add MCP endpoints via transport adapters, dispatched from the fallback so the
+    // management API can attach and detach them at runtime
+    for (path, endpoint_name) in state.router.list_mounts() {
+        if let Err(e) = state
+            .endpoint_routes
+            .attach(&state.manager, &path, &endpoint_name)
+            .await
+        {
+            tracing::error!(
+                "Failed to attach route for endpoint {}: {}. This is a fatal error.",
+                endpoint_name,
+                e
+            );
+            return Err(e.into());
+        }
+    }
+    app = app.fallback(endpoint_routes::dispatch);
+
+    // Inside the CORS layer, so preflight requests are answered without a token
+    if config.auth.is_enabled() {
+        app = app.layer(axum::middleware::from_fn_with_state(
+            Arc::new(config.auth.clone()),
+            middleware::require_bearer_token,
+        ));
+    }
 
-        app = match result {
-            Ok(router) => router,
-            Err(e) => {
-                tracing::error!(
-                    "Failed to attach route for endpoint {}: {}. This is a fatal error.",
-                    endpoint_name,
-                    e
-                );
-                return Err(e.into());
-            }
-        };
+    let mut cors = CorsLayer::permissive();
+    if let Some(max_age_secs) = config.cors.max_age_secs {
+        cors = cors.max_age(Duration::from_secs(max_age_secs));
     }
 
     // Add layers
+    // Gzip request bodies are decompressed before extraction, so body limits apply to
+    // the decompressed size
+    let mut app = app.layer(RequestDecompressionLayer::new()).layer(cors);
+    if config.logging.http_requests {
+        let level = config
+            .logging
+            .http_request_level
+            .parse()
+            .unwrap_or(Level::DEBUG);
+        app = app.layer(
+            TraceLayer::new_for_http()
+                .make_span_with(move |request: &Request| request_span(request, level))
+                .on_request(DefaultOnRequest::new().level(level))
+                .on_response(DefaultOnResponse::new().level(level)),
+        );
+    }
+    let matching = state.router.matching();
+    let path_router = state.router.clone();
     let app = app
-        .layer(CorsLayer::permissive())
-        .layer(TraceLayer::new_for_http())
+        .layer(axum::middleware::from_fn(request_id::propagate_request_id))
         .with_state(state);
 
-    Ok(app)
+    // Layers on a router run after route matching, so the path is rewritten in a
+    // service wrapping the whole app instead
+    if matching == PathMatching::Exact {
+        return Ok(app);
+    }
+    let normalize =
+        axum::middleware::from_fn_with_state(path_router, middleware::normalize_mcp_path);
+    Ok(Router::new().fallback_service(tower::Layer::layer(&normalize, app)))
 }
 
-async fn shutdown_signal(manager: Arc<EndpointManager>) {
+/// Like tower-http's default request span, plus the request ID and, under mTLS, the
+/// client certificate subject
+fn request_span(request: &Request, level: Level) -> tracing::Span {
+    let request_id = request
+        .extensions()
+        .get::<request_id::RequestId>()
+        .and_then(|id| id.0.to_str().ok());
+    let client = request
+        .extensions()
+        .get::<tls::ClientCertificate>()
+        .map(|certificate| certificate.subject.as_str());
+    // Span levels must be known at compile time
+    macro_rules! span {
+        ($level:expr) => {
+            tracing::span!(
+                $level,
+                "request",
+                method = %request.method(),
+                uri = %request.uri(),
+                version = ?request.version(),
+                request_id,
+                client,
+            )
+        };
+    }
+    match level {
+        Level::TRACE => span!(Level::TRACE),
+        Level::DEBUG => span!(Level::DEBUG),
+        Level::INFO => span!(Level::INFO),
+        Level::WARN => span!(Level::WARN),
+        _ => span!(Level::ERROR),
+    }
+}
+
+async fn shutdown_signal(
+    manager: Arc<EndpointManager>,
+    in_flight: InFlight,
+    drain_timeout: Duration,
+) {
     let ctrl_c = async {
         tokio::signal::ctrl_c()
             .await
@@ -138,6 +283,15 @@ async fn shutdown_signal(manager: Arc<EndpointManager>) {
         },
     }
 
+    // Let running requests finish before their endpoints go away
+    if !in_flight.drain(drain_timeout).await {
+        tracing::warn!(
+            "{} request(s) still running after {:?}, shutting down anyway",
+            in_flight.active(),
+            drain_timeout
+        );
+    }
+
     // Gracefully shutdown all endpoints
     if let Err(e) = manager.shutdown().await {
         tracing::error!("Error during shutdown: {}", e);
@@ -147,7 +301,8 @@ async fn shutdown_signal(manager: Arc<EndpointManager>) {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::{EndpointConfig, EndpointKindConfig, HttpConfig, LoggingConfig, McpConfig};
+    use crate::config::{EndpointConfig, EndpointKindConfig};
+    use crate::endpoint::EndpointStatus;
     use axum::body::Body;
     use axum::http::{Request, StatusCode};
     use tower::ServiceExt;
@@ -155,45 +310,444 @@ mod tests {
     #[tokio::test]
     async fn test_sse_route_attached_for_remote_endpoint() {
         let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
-        let manager = Arc::new(EndpointManager::new());
+        let mut endpoint = remote_endpoint("remote-stub", "http://127.0.0.1:19876");
+        endpoint.aliases = vec!["old-remote-stub".to_string()];
+        let app = create_app(&AppConfig {
+            endpoints: vec![endpoint],
+            ..Default::default()
+        })
+        .await;
+
+        for uri in ["/mcp/remote-stub", "/mcp/old-remote-stub"] {
+            let response = app
+                .clone()
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+
+            assert_ne!(response.status(), StatusCode::NOT_FOUND, "{}", uri);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_request_id_is_echoed_and_forwarded_to_remote() {
+        let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+        // Upstream answering with the request ID it received
+        let upstream = Router::new().fallback(|headers: axum::http::HeaderMap| async move {
+            headers["x-request-id"].to_str().unwrap().to_string()
+        });
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, upstream).await });
+
+        let app = create_app(&AppConfig {
+            endpoints: vec![remote_endpoint("remote", &format!("http://{}", addr))],
+            ..Default::default()
+        })
+        .await;
+
+        let send = |request_id: Option<&'static str>| {
+            let mut request = Request::builder().uri("/mcp/remote/ping");
+            if let Some(id) = request_id {
+                request = request.header("x-request-id", id);
+            }
+            app.clone().oneshot(request.body(Body::empty()).unwrap())
+        };
+
+        let response = send(Some("client-supplied-1")).await.unwrap();
+        assert_eq!(response.headers()["x-request-id"], "client-supplied-1");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"client-supplied-1");
+
+        let response = send(None).await.unwrap();
+        let generated = response.headers()["x-request-id"].clone();
+        assert!(!generated.is_empty());
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], generated.as_bytes());
+    }
+
+    #[tokio::test]
+    async fn test_stopped_local_endpoint_route_returns_503_when_start_fails() {
+        // Building the router must not require the endpoint to be running
+        let app = create_app(&AppConfig {
+            endpoints: vec![local_stub_endpoint()],
+            ..Default::default()
+        })
+        .await;
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/mcp/local-stub")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["code"], 503);
+    }
+
+    #[tokio::test]
+    async fn test_operator_stopped_endpoint_route_is_not_started_on_demand() {
         let config = AppConfig {
-            http: HttpConfig::default(),
-            logging: LoggingConfig::default(),
-            mcp: McpConfig::default(),
-            endpoints: vec![EndpointConfig {
-                name: "remote-stub".to_string(),
-                endpoint_type: EndpointKindConfig::Remote {
-                    url: "http://127.0.0.1:19876".to_string(),
-                },
-                tools: None,
-            }],
+            endpoints: vec![local_stub_endpoint()],
+            ..Default::default()
         };
+        let (app, manager) = build_app(config).await.unwrap();
+        // Already stopped, but the operator's intent still sticks
+        let _ = manager.stop_endpoint_by_operator("local-stub").await;
 
-        manager
-            .init_from_config(config.endpoints.clone())
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/mcp/local-stub")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
             .await
             .unwrap();
 
-        let router = Arc::new(PathRouter::new(manager.clone()));
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert!(
+            json["error"]
+                .as_str()
+                .unwrap()
+                .contains("stopped by an operator"),
+            "{}",
+            json
+        );
+        // No start was attempted, so the endpoint didn't fail either
+        let info = manager.get_endpoint_info("local-stub").unwrap();
+        assert_eq!(info.status, EndpointStatus::Stopped);
+    }
 
-        let state = ApiState {
-            manager,
-            router,
-            mcp_request_timeout: Duration::from_secs(config.mcp.request_timeout_secs),
+    #[tokio::test]
+    async fn test_path_matching_normalizes_mcp_paths() {
+        let send = |matching: PathMatching, uri: &'static str| async move {
+            let mut config = AppConfig {
+                endpoints: vec![local_stub_endpoint()],
+                ..Default::default()
+            };
+            config.mcp.path_matching = matching;
+            let (app, _manager) = build_app(config).await.unwrap();
+            let response = app
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            let status = response.status();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            let json: serde_json::Value = serde_json::from_slice(&body).unwrap_or_default();
+            (
+                status,
+                json["error"].as_str().unwrap_or_default().to_string(),
+            )
         };
+        let not_running = "Server is not running: local-stub";
+
+        // The endpoint isn't running, so the tools handler answers 503 once the path matches
+        let (status, _) = send(PathMatching::Exact, "/mcp/Local-Stub/tools").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, error) = send(PathMatching::CaseInsensitive, "/mcp/Local-Stub/tools").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(error, not_running);
+
+        // The endpoint's transport route is reached under any casing too
+        let (status, _) = send(PathMatching::Exact, "/mcp/LOCAL-STUB").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, error) = send(PathMatching::CaseInsensitive, "/mcp/LOCAL-STUB").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert!(error.starts_with("Endpoint local-stub is unavailable"));
+
+        // Without normalization a trailing slash falls through to the transport route
+        let (_, error) = send(PathMatching::Exact, "/mcp/local-stub/tools/").await;
+        assert_ne!(error, not_running);
+        let (_, error) = send(
+            PathMatching::NormalizeTrailingSlash,
+            "/mcp/local-stub/tools/",
+        )
+        .await;
+        assert_eq!(error, not_running);
+    }
 
-        let app = build_router(state).await.unwrap();
+    #[tokio::test]
+    async fn test_aggregate_mcp_route_attached() {
+        let app = create_empty_app().await;
 
         let response = app
             .oneshot(
                 Request::builder()
-                    .uri("/mcp/remote-stub")
+                    .method("POST")
+                    .uri("/mcp")
+                    .header("content-type", "application/json")
+                    .header("accept", "application/json, text/event-stream")
+                    .body(Body::from(
+                        r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-06-18","capabilities":{},"clientInfo":{"name":"test","version":"1"}}}"#,
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_cors_preflight_max_age() {
+        let mut config = AppConfig::default();
+        config.cors.max_age_secs = Some(600);
+        let app = create_app(&config).await;
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("OPTIONS")
+                    .uri("/health")
+                    .header("origin", "http://localhost:5173")
+                    .header("access-control-request-method", "GET")
                     .body(Body::empty())
                     .unwrap(),
             )
             .await
             .unwrap();
 
+        assert_eq!(response.headers()["access-control-max-age"], "600");
+    }
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        use flate2::{Compression, write::GzEncoder};
+        use std::io::Write;
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    async fn create_empty_app() -> Router {
+        create_app(&AppConfig::default()).await
+    }
+
+    async fn create_app(config: &AppConfig) -> Router {
+        let (app, _manager) = build_app(config.clone()).await.unwrap();
+        app
+    }
+
+    /// Stopped local endpoint `local-stub` whose command exits at once, so starts fail
+    fn local_stub_endpoint() -> EndpointConfig {
+        stub_endpoint(
+            "local-stub",
+            EndpointKindConfig::Local {
+                command: "true".to_string(),
+                args: vec![],
+                env: Default::default(),
+                clean_env: false,
+                auto_start: false,
+                health_command: None,
+                cwd: None,
+                restart_on_failure: false,
+                startup_retry: None,
+                env_file: None,
+            },
+        )
+    }
+
+    /// Remote endpoint at `url`, connected on first use
+    fn remote_endpoint(name: &str, url: &str) -> EndpointConfig {
+        stub_endpoint(
+            name,
+            EndpointKindConfig::Remote {
+                url: url.to_string(),
+                urls: Vec::new(),
+                require_tools_on_start: false,
+                headers: Default::default(),
+                connect_timeout_secs: None,
+                prewarm: false,
+            },
+        )
+    }
+
+    fn stub_endpoint(name: &str, endpoint_type: EndpointKindConfig) -> EndpointConfig {
+        EndpointConfig {
+            name: name.to_string(),
+            aliases: Vec::new(),
+            endpoint_type,
+            tools: None,
+            rate_limit: None,
+            max_concurrent_requests: None,
+            enabled: true,
+            metadata: Default::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_runtime_added_endpoint_route_attaches_and_detaches() {
+        let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+        let app = create_empty_app().await;
+        let send = |method: &str, uri: &str, body: &str| {
+            app.clone().oneshot(
+                Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .header("content-type", "application/json")
+                    .body(Body::from(body.to_string()))
+                    .unwrap(),
+            )
+        };
+
+        let config = r#"{"name":"added","type":"remote","url":"http://127.0.0.1:19876"}"#;
+        let response = send("POST", "/servers", config).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        // Proxied to the (unreachable) upstream rather than unrouted
+        let response = send("GET", "/mcp/added", "").await.unwrap();
         assert_ne!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = send("DELETE", "/servers/added", "").await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = send("GET", "/mcp/added", "").await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_gzip_tool_call_body_is_decompressed() {
+        let app = create_empty_app().await;
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/mcp/missing/tools/call")
+                    .header("content-type", "application/json")
+                    .header("content-encoding", "gzip")
+                    .body(Body::from(gzip(br#"{"name":"t","arguments":{}}"#)))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // The payload parsed, so the request got as far as endpoint lookup
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_gzip_body_limit_applies_to_decompressed_size() {
+        let app = create_empty_app().await;
+        let payload = format!(
+            r#"{{"name":"t","arguments":{{"pad":"{}"}}}}"#,
+            "a".repeat(4 * 1024 * 1024)
+        );
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/mcp/missing/tools/call")
+                    .header("content-type", "application/json")
+                    .header("content-encoding", "gzip")
+                    .body(Body::from(gzip(payload.as_bytes())))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    async fn create_auth_app() -> Router {
+        let mut config = AppConfig::default();
+        config.auth.tokens = vec!["secret".to_string()];
+        create_app(&config).await
+    }
+
+    async fn get_servers(app: Router, authorization: Option<&str>) -> StatusCode {
+        let mut request = Request::builder().uri("/servers");
+        if let Some(value) = authorization {
+            request = request.header("authorization", value);
+        }
+
+        app.oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn test_auth_rejects_missing_token() {
+        let app = create_auth_app().await;
+        assert_eq!(get_servers(app, None).await, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_auth_rejects_wrong_token() {
+        let app = create_auth_app().await;
+        assert_eq!(
+            get_servers(app, Some("Bearer nope")).await,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+
+    #[tokio::test]
+    async fn test_auth_accepts_configured_token() {
+        let app = create_auth_app().await;
+        assert_eq!(
+            get_servers(app, Some("Bearer secret")).await,
+            StatusCode::OK
+        );
+    }
+
+    #[tokio::test]
+    async fn test_health_served_with_http_tracing_disabled() {
+        for (http_requests, level) in [(false, "debug"), (true, "info")] {
+            let mut config = AppConfig::default();
+            config.logging.http_requests = http_requests;
+            config.logging.http_request_level = level.to_string();
+            let app = create_app(&config).await;
+
+            let response = app
+                .oneshot(
+                    Request::builder()
+                        .uri("/health")
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+
+            assert_eq!(response.status(), StatusCode::OK);
+            assert!(response.headers().contains_key("x-request-id"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_auth_leaves_health_public() {
+        let app = create_auth_app().await;
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/health")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
     }
 }
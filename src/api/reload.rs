@@ -0,0 +1,185 @@
+// Re-applying endpoint settings from the config file while the proxy keeps serving
+
+use super::handlers::ApiState;
+use crate::config::{AppConfig, EndpointConfig};
+use crate::endpoint::registry::EndpointStatus;
+use crate::error::{ProxyError, Result};
+use std::collections::HashSet;
+use tracing::{error, info, warn};
+
+/// Endpoints a configuration reload added, removed, or recreated with new settings
+#[derive(Debug, Default)]
+pub(crate) struct ReconcileSummary {
+    pub(crate) added: Vec<String>,
+    pub(crate) removed: Vec<String>,
+    pub(crate) changed: Vec<String>,
+}
+
+/// Bring the live endpoints in line with a freshly loaded configuration
+///
+/// Endpoints missing from it are removed, including ones added through the management API.
+/// Only endpoints are reconciled; other settings take effect on the next restart. Failures
+/// are logged per endpoint and leave the others to be applied.
+pub(crate) async fn reconcile(state: &ApiState, config: &AppConfig) -> ReconcileSummary {
+    let wanted: Vec<&EndpointConfig> = config.endpoints.iter().filter(|e| e.enabled).collect();
+    let names: HashSet<&str> = wanted.iter().map(|e| e.name.as_str()).collect();
+    let mut summary = ReconcileSummary::default();
+
+    // Removals first, so their paths are free for endpoints added below
+    for info in state.manager.list_endpoints() {
+        if names.contains(info.name.as_str()) {
+            continue;
+        }
+        match remove_endpoint(state, &info.name).await {
+            Ok(()) => summary.removed.push(info.name),
+            Err(e) => error!("Failed to remove endpoint {} on reload: {}", info.name, e),
+        }
+    }
+
+    for endpoint in wanted {
+        let name = endpoint.name.clone();
+        match state.manager.endpoint_config(&name) {
+            None => match add_endpoint(state, endpoint.clone()).await {
+                Ok(()) => summary.added.push(name),
+                Err(e) => error!("Failed to add endpoint {} on reload: {}", name, e),
+            },
+            Some(current) if current != *endpoint => {
+                let running = state
+                    .manager
+                    .get_endpoint_info(&name)
+                    .is_ok_and(|info| info.status == EndpointStatus::Running);
+                match replace_endpoint(state, endpoint.clone(), running).await {
+                    Ok(()) => summary.changed.push(name),
+                    Err(e) => error!("Failed to apply new settings to endpoint {}: {}", name, e),
+                }
+            }
+            Some(_) => {}
+        }
+    }
+
+    summary
+}
+
+/// Replace a registered endpoint with one built from `config` and remount its routes,
+/// starting it if `start` is set
+///
+/// If the new endpoint can't be registered, mounted or started, the previous one is put back.
+pub(crate) async fn replace_endpoint(
+    state: &ApiState,
+    config: EndpointConfig,
+    start: bool,
+) -> Result<()> {
+    let name = config.name.clone();
+    let previous = state
+        .manager
+        .endpoint_config(&name)
+        .ok_or_else(|| ProxyError::server_not_found(name.clone()))?;
+    info!("Recreating endpoint {} with changed settings", name);
+
+    remove_endpoint(state, &name).await?;
+    let Err(e) = install_endpoint(state, config, start).await else {
+        return Ok(());
+    };
+
+    warn!(
+        "Restoring endpoint {} after its new settings failed: {}",
+        name, e
+    );
+    if let Err(restore) = install_endpoint(state, previous, start).await {
+        error!("Failed to restore endpoint {}: {}", name, restore);
+    }
+    Err(e)
+}
+
+/// Register an endpoint, auto-starting it as configured, and mount its routes
+async fn add_endpoint(state: &ApiState, config: EndpointConfig) -> Result<()> {
+    let name = config.name.clone();
+    state.manager.add_endpoint(config).await?;
+    discard_on_error(state, &name, attach_routes(state, &name).await).await
+}
+
+/// Register an endpoint and mount its routes, starting it only if `start` is set
+async fn install_endpoint(state: &ApiState, config: EndpointConfig, start: bool) -> Result<()> {
+    let name = config.name.clone();
+    state.manager.register_endpoint(config)?;
+
+    let mut result = attach_routes(state, &name).await;
+    if result.is_ok() && start {
+        result = state.manager.start_endpoint(&name).await;
+    }
+    discard_on_error(state, &name, result).await
+}
+
+async fn attach_routes(state: &ApiState, name: &str) -> Result<()> {
+    let info = state.manager.get_endpoint_info(name)?;
+    for path in info.paths() {
+        state
+            .endpoint_routes
+            .attach(&state.manager, path, name)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Remove an endpoint that failed to come up, so nothing half-installed is left behind
+async fn discard_on_error(state: &ApiState, name: &str, result: Result<()>) -> Result<()> {
+    if result.is_err() {
+        let _ = remove_endpoint(state, name).await;
+    }
+    result
+}
+
+/// Unmount an endpoint's routes, then stop and forget it
+async fn remove_endpoint(state: &ApiState, name: &str) -> Result<()> {
+    let info = state.manager.get_endpoint_info(name)?;
+    for path in info.paths() {
+        state.endpoint_routes.detach(path);
+    }
+    state.manager.remove_endpoint(name).await
+}
+
+/// Reload the configuration from `path` on every SIGHUP, keeping the current one if it's invalid
+#[cfg(unix)]
+pub(crate) async fn reload_on_sighup(state: ApiState, path: std::path::PathBuf) {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    let mut hangups = match signal(SignalKind::hangup()) {
+        Ok(hangups) => hangups,
+        Err(e) => {
+            error!("Failed to install SIGHUP handler: {}", e);
+            return;
+        }
+    };
+
+    while hangups.recv().await.is_some() {
+        info!(
+            "Received SIGHUP, reloading configuration from {}",
+            path.display()
+        );
+        // Reading and parsing the file blocks, so keep it off the async workers
+        let load_path = path.clone();
+        let loaded = tokio::task::spawn_blocking(move || crate::config::load_config(load_path));
+        let config = match loaded.await {
+            Ok(Ok(config)) => config,
+            Ok(Err(e)) => {
+                error!("Keeping the current configuration, reload failed: {:#}", e);
+                continue;
+            }
+            Err(e) => {
+                error!(
+                    "Keeping the current configuration, reload task failed: {}",
+                    e
+                );
+                continue;
+            }
+        };
+
+        let summary = reconcile(&state, &config).await;
+        info!(
+            "Configuration reloaded: added [{}], removed [{}], changed [{}]",
+            summary.added.join(", "),
+            summary.removed.join(", "),
+            summary.changed.join(", ")
+        );
+    }
+}
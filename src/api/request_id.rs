@@ -0,0 +1,101 @@
+// `X-Request-Id` handling: reuse the client's ID or generate one, and carry it to upstreams
+
+use axum::{
+    extract::Request,
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+
+pub(crate) const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// The request's ID, as echoed in the response; available to handlers as an extension
+#[derive(Debug, Clone)]
+pub(crate) struct RequestId(pub(crate) HeaderValue);
+
+tokio::task_local! {
+    static CURRENT: HeaderValue;
+}
+
+/// ID of the request being handled by the current task, if any
+pub(crate) fn current() -> Option<HeaderValue> {
+    CURRENT.try_with(HeaderValue::clone).ok()
+}
+
+/// Run `f` with `id` as the current request ID
+pub(crate) async fn scope<F: Future>(id: HeaderValue, f: F) -> F::Output {
+    CURRENT.scope(id, f).await
+}
+
+/// Reuse the client's `X-Request-Id` or generate one, then expose it to the rest of the
+/// stack (proxied requests, handlers, upstream MCP calls) and echo it in the response
+pub(crate) async fn propagate_request_id(mut request: Request, next: Next) -> Response {
+    let id = match request.headers().get(&REQUEST_ID_HEADER) {
+        Some(id) if !id.is_empty() => id.clone(),
+        _ => {
+            let id = HeaderValue::from_str(&uuid::Uuid::new_v4().to_string())
+                .expect("UUIDs are valid header values");
+            request.headers_mut().insert(REQUEST_ID_HEADER, id.clone());
+            id
+        }
+    };
+    request.extensions_mut().insert(RequestId(id.clone()));
+
+    let mut response = scope(id.clone(), next.run(request)).await;
+    response.headers_mut().insert(REQUEST_ID_HEADER, id);
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, body::Body, routing::get};
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        Router::new()
+            .route(
+                "/id",
+                get(|| async { current().unwrap().to_str().unwrap().to_string() }),
+            )
+            .layer(axum::middleware::from_fn(propagate_request_id))
+    }
+
+    #[tokio::test]
+    async fn test_client_request_id_is_reused_and_echoed() {
+        let response = app()
+            .oneshot(
+                Request::builder()
+                    .uri("/id")
+                    .header("x-request-id", "abc-123")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.headers()["x-request-id"], "abc-123");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"abc-123");
+    }
+
+    #[tokio::test]
+    async fn test_request_id_is_generated_when_absent() {
+        let response = app()
+            .oneshot(Request::builder().uri("/id").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        let id = response.headers()["x-request-id"]
+            .to_str()
+            .unwrap()
+            .to_string();
+        assert!(uuid::Uuid::parse_str(&id).is_ok(), "{}", id);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(body, id.as_bytes());
+    }
+}
@@ -1,37 +1,134 @@
 use crate::api::handlers::ApiState;
+use crate::config::{ManagementConfig, ManagementOperation};
 use axum::{
-    Router,
-    routing::{get, post},
+    Router, middleware,
+    routing::{delete, get, post},
 };
+use std::sync::Arc;
 
 pub fn health_routes() -> Router<ApiState> {
     Router::new()
         .route("/health", get(super::handlers::health_check))
+        .route("/ready", get(super::handlers::readiness_check))
         .route("/info", get(super::handlers::server_info))
+        .route("/version", get(super::handlers::version))
+        .route("/metrics", get(super::handlers::metrics))
 }
 
-pub fn management_routes() -> Router<ApiState> {
-    Router::new()
+pub fn management_routes(config: &ManagementConfig) -> Router<ApiState> {
+    let read_routes = Router::new()
         .route("/servers", get(super::handlers::list_servers))
         .route(
             "/servers/{name}/status",
             get(super::handlers::server_status),
         )
-        .route("/servers/{name}/start", post(super::handlers::start_server))
-        .route("/servers/{name}/stop", post(super::handlers::stop_server))
         .route(
+            "/servers/{name}/capabilities",
+            get(super::handlers::server_capabilities),
+        )
+        .route(
+            "/servers/{name}/tools/{tool}",
+            get(super::handlers::server_tool),
+        );
+
+    // Mutating routes are only registered when enabled, so disabled ones return 404
+    let mut write_routes = Router::new();
+    let mut has_write_routes = false;
+    if config.is_enabled(ManagementOperation::Start) {
+        write_routes =
+            write_routes.route("/servers/{name}/start", post(super::handlers::start_server));
+        has_write_routes = true;
+    }
+    if config.is_enabled(ManagementOperation::Stop) {
+        write_routes =
+            write_routes.route("/servers/{name}/stop", post(super::handlers::stop_server));
+        has_write_routes = true;
+    }
+    if config.is_enabled(ManagementOperation::Restart) {
+        write_routes = write_routes.route(
             "/servers/{name}/restart",
             post(super::handlers::restart_server),
-        )
+        );
+        has_write_routes = true;
+    }
+    if config.is_enabled(ManagementOperation::Reload) {
+        write_routes = write_routes.route(
+            "/servers/{name}/reload",
+            post(super::handlers::reload_server),
+        );
+        has_write_routes = true;
+    }
+
+    if config.is_enabled(ManagementOperation::Add) {
+        write_routes = write_routes.route("/servers", post(super::handlers::add_server));
+        has_write_routes = true;
+    }
+    if config.is_enabled(ManagementOperation::Remove) {
+        write_routes =
+            write_routes.route("/servers/{name}", delete(super::handlers::remove_server));
+        has_write_routes = true;
+    }
+
+    if config.is_enabled(ManagementOperation::TestRemote) {
+        write_routes = write_routes.route("/admin/test-remote", post(super::handlers::test_remote));
+        has_write_routes = true;
+    }
+
+    if has_write_routes && let Some(admin_key) = &config.admin_api_key {
+        write_routes = write_routes.route_layer(middleware::from_fn_with_state(
+            Arc::<str>::from(admin_key.as_str()),
+            super::middleware::require_admin_key,
+        ));
+    }
+
+    read_routes.merge(write_routes)
 }
 
 pub fn mcp_routes() -> Router<ApiState> {
     Router::new()
-        // Note: /mcp/{path} is handled by nest_service in api/mod.rs for SSE support
+        // Note: /mcp/{path} is dispatched from the fallback (see api/endpoint_routes.rs) for SSE support
         // These REST API endpoints remain for backward compatibility
+        .route("/mcp/tools", get(super::handlers::mcp_list_all_tools))
+        .route(
+            "/mcp/tools/call",
+            post(super::handlers::mcp_call_namespaced_tool),
+        )
+        .route("/mcp/{path}/info", get(super::handlers::mcp_server_info))
         .route("/mcp/{path}/tools", get(super::handlers::mcp_list_tools))
+        .route(
+            "/mcp/{path}/tools/fingerprint",
+            get(super::handlers::mcp_tools_fingerprint),
+        )
         .route(
             "/mcp/{path}/tools/call",
             post(super::handlers::mcp_call_tool),
         )
+        .route(
+            "/mcp/{path}/tools/call-batch",
+            post(super::handlers::mcp_call_tool_batch),
+        )
+        .route(
+            "/mcp/{path}/resources",
+            get(super::handlers::mcp_list_resources),
+        )
+        .route(
+            "/mcp/{path}/resources/read",
+            get(super::handlers::mcp_read_resource),
+        )
+        .route(
+            "/mcp/{path}/prompts",
+            get(super::handlers::mcp_list_prompts),
+        )
+        .route(
+            "/mcp/{path}/prompts/get",
+            post(super::handlers::mcp_get_prompt),
+        )
+        .route(
+            "/mcp/{path}/tasks/{task_id}",
+            get(super::handlers::mcp_get_task),
+        )
+        .route(
+            "/mcp/{path}/tasks/{task_id}/result",
+            get(super::handlers::mcp_get_task_result),
+        )
 }
@@ -0,0 +1,349 @@
+// HTTPS for the server listener, terminating TLS with rustls
+
+use crate::config::TlsConfig;
+use anyhow::{Context, Result};
+use axum::Router;
+use axum::extract::Request;
+use axum::response::Response;
+use axum::serve::IncomingStream;
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::server::WebPkiClientVerifier;
+use rustls::{RootCertStore, ServerConfig};
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::task::{Context as TaskContext, Poll};
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio_rustls::TlsAcceptor;
+use tokio_rustls::server::TlsStream;
+use tracing::debug;
+
+/// How long a client gets to complete the TLS handshake
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Handshaken connections waiting to be served
+const ACCEPT_BACKLOG: usize = 64;
+
+/// Subject of the certificate a client authenticated with, e.g. `CN=client, O=Example`
+/// Added to the extensions of every request on an mTLS connection
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ClientCertificate {
+    pub(crate) subject: String,
+}
+
+/// Build the rustls server config from the configured certificate chain and key
+/// With `client_ca_path` set, clients must present a certificate issued by that CA
+pub(crate) fn server_config(tls: &TlsConfig) -> Result<ServerConfig> {
+    let certs = load_certs(&tls.cert_path, "certificate")?;
+    let key = PrivateKeyDer::from_pem_file(&tls.key_path)
+        .with_context(|| format!("Failed to load TLS private key from {}", tls.key_path))?;
+
+    let builder = ServerConfig::builder();
+    let builder = match &tls.client_ca_path {
+        Some(client_ca_path) => {
+            let mut roots = RootCertStore::empty();
+            for cert in load_certs(client_ca_path, "client CA")? {
+                roots
+                    .add(cert)
+                    .with_context(|| format!("Invalid client CA in {}", client_ca_path))?;
+            }
+            let verifier = WebPkiClientVerifier::builder(Arc::new(roots))
+                .build()
+                .with_context(|| format!("Invalid client CA in {}", client_ca_path))?;
+            builder.with_client_cert_verifier(verifier)
+        }
+        None => builder.with_no_client_auth(),
+    };
+
+    let mut config = builder
+        .with_single_cert(certs, key)
+        .context("TLS certificate and private key don't match")?;
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    Ok(config)
+}
+
+fn load_certs(path: &str, what: &str) -> Result<Vec<CertificateDer<'static>>> {
+    let certs = CertificateDer::pem_file_iter(path)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+        .with_context(|| format!("Failed to load TLS {} from {}", what, path))?;
+    if certs.is_empty() {
+        anyhow::bail!("No certificates found in {}", path);
+    }
+    Ok(certs)
+}
+
+/// Subject of the client's verified certificate, if it presented one
+fn client_certificate(stream: &TlsStream<TcpStream>) -> Option<ClientCertificate> {
+    let der = stream.get_ref().1.peer_certificates()?.first()?;
+    let (_, cert) = x509_parser::parse_x509_certificate(der).ok()?;
+    Some(ClientCertificate {
+        subject: cert.subject().to_string(),
+    })
+}
+
+/// TCP listener yielding connections once their TLS handshake has completed
+/// Handshakes run in their own tasks, so a slow client doesn't hold up the others
+pub(crate) struct TlsListener {
+    connections: mpsc::Receiver<(TlsStream<TcpStream>, SocketAddr)>,
+    local_addr: SocketAddr,
+}
+
+impl TlsListener {
+    pub(crate) fn new(listener: TcpListener, config: ServerConfig) -> std::io::Result<Self> {
+        let local_addr = listener.local_addr()?;
+        let acceptor = TlsAcceptor::from(Arc::new(config));
+        let (tx, connections) = mpsc::channel(ACCEPT_BACKLOG);
+
+        let mut listener = listener;
+        tokio::spawn(async move {
+            loop {
+                let (stream, addr) = axum::serve::Listener::accept(&mut listener).await;
+                if tx.is_closed() {
+                    break;
+                }
+
+                let acceptor = acceptor.clone();
+                let handshaken = tx.clone();
+                tokio::spawn(async move {
+                    match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                        Ok(Ok(stream)) => {
+                            let _ = handshaken.send((stream, addr)).await;
+                        }
+                        Ok(Err(e)) => debug!("TLS handshake with {} failed: {}", addr, e),
+                        Err(_) => debug!("TLS handshake with {} timed out", addr),
+                    }
+                });
+            }
+        });
+
+        Ok(Self {
+            connections,
+            local_addr,
+        })
+    }
+}
+
+impl axum::serve::Listener for TlsListener {
+    type Io = TlsStream<TcpStream>;
+    type Addr = SocketAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        match self.connections.recv().await {
+            Some(connection) => connection,
+            // The accept task only ends once this listener is gone
+            None => std::future::pending().await,
+        }
+    }
+
+    fn local_addr(&self) -> std::io::Result<Self::Addr> {
+        Ok(self.local_addr)
+    }
+}
+
+/// Make-service for `axum::serve` that tags requests with the connection's client certificate
+#[derive(Clone)]
+pub(crate) struct WithClientCertificate(pub(crate) Router);
+
+impl tower::Service<IncomingStream<'_, TlsListener>> for WithClientCertificate {
+    type Response = AddClientCertificate;
+    type Error = Infallible;
+    type Future = std::future::Ready<Result<Self::Response, Infallible>>;
+
+    fn poll_ready(&mut self, _cx: &mut TaskContext<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, stream: IncomingStream<'_, TlsListener>) -> Self::Future {
+        std::future::ready(Ok(AddClientCertificate {
+            app: self.0.clone(),
+            certificate: client_certificate(stream.io()),
+        }))
+    }
+}
+
+/// The app for one TLS connection
+#[derive(Clone)]
+pub(crate) struct AddClientCertificate {
+    app: Router,
+    certificate: Option<ClientCertificate>,
+}
+
+impl tower::Service<Request> for AddClientCertificate {
+    type Response = Response;
+    type Error = Infallible;
+    type Future = <Router as tower::Service<Request>>::Future;
+
+    fn poll_ready(&mut self, cx: &mut TaskContext<'_>) -> Poll<Result<(), Self::Error>> {
+        tower::Service::<Request>::poll_ready(&mut self.app, cx)
+    }
+
+    fn call(&mut self, mut request: Request) -> Self::Future {
+        if let Some(certificate) = &self.certificate {
+            request.extensions_mut().insert(certificate.clone());
+        }
+        self.app.call(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, routing::get};
+
+    /// Test CA and a localhost certificate it issued; see the README there
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/tls");
+
+    fn fixture_config() -> TlsConfig {
+        TlsConfig {
+            cert_path: format!("{}/server.pem", FIXTURES),
+            key_path: format!("{}/server.key", FIXTURES),
+            client_ca_path: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_serves_https_with_configured_certificate() {
+        let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+        let config = server_config(&fixture_config()).unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let listener = TlsListener::new(listener, config).unwrap();
+        let addr = axum::serve::Listener::local_addr(&listener).unwrap();
+        let app = Router::new().route("/health", get(|| async { "ok" }));
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let ca = std::fs::read(format!("{}/ca.pem", FIXTURES)).unwrap();
+        let client = reqwest::Client::builder()
+            .add_root_certificate(reqwest::Certificate::from_pem(&ca).unwrap())
+            .build()
+            .unwrap();
+        let response = client
+            .get(format!("https://localhost:{}/health", addr.port()))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.text().await.unwrap(), "ok");
+
+        // Plain HTTP never completes a handshake
+        let plain = reqwest::get(format!("http://127.0.0.1:{}/health", addr.port())).await;
+        assert!(plain.is_err());
+    }
+
+    /// Serve an app echoing the client certificate subject, requiring certificates from the test CA
+    async fn spawn_mtls_server() -> SocketAddr {
+        let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+        let config = server_config(&TlsConfig {
+            client_ca_path: Some(format!("{}/ca.pem", FIXTURES)),
+            ..fixture_config()
+        })
+        .unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let listener = TlsListener::new(listener, config).unwrap();
+        let addr = axum::serve::Listener::local_addr(&listener).unwrap();
+        let app = Router::new().route(
+            "/whoami",
+            get(|request: Request| async move {
+                request
+                    .extensions()
+                    .get::<ClientCertificate>()
+                    .map(|certificate| certificate.subject.clone())
+                    .unwrap_or_default()
+            }),
+        );
+        tokio::spawn(async move { axum::serve(listener, WithClientCertificate(app)).await });
+        addr
+    }
+
+    async fn whoami(addr: SocketAddr, identity: Option<&str>) -> reqwest::Result<String> {
+        let ca = std::fs::read(format!("{}/ca.pem", FIXTURES)).unwrap();
+        let mut builder = reqwest::Client::builder()
+            .add_root_certificate(reqwest::Certificate::from_pem(&ca).unwrap());
+        if let Some(name) = identity {
+            let mut pem = std::fs::read(format!("{}/{}.pem", FIXTURES, name)).unwrap();
+            pem.extend(std::fs::read(format!("{}/{}.key", FIXTURES, name)).unwrap());
+            builder = builder.identity(reqwest::Identity::from_pem(&pem).unwrap());
+        }
+
+        builder
+            .build()
+            .unwrap()
+            .get(format!("https://localhost:{}/whoami", addr.port()))
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_trusted_client_certificate_reaches_handlers() {
+        let addr = spawn_mtls_server().await;
+
+        let subject = whoami(addr, Some("client")).await.unwrap();
+        assert_eq!(subject, "CN=client, O=rusted-tools tests");
+    }
+
+    #[tokio::test]
+    async fn test_untrusted_or_missing_client_certificate_is_rejected() {
+        let addr = spawn_mtls_server().await;
+
+        assert!(whoami(addr, Some("untrusted-client")).await.is_err());
+        assert!(whoami(addr, None).await.is_err());
+    }
+
+    #[test]
+    fn test_missing_client_ca_is_reported() {
+        let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+        let tls = TlsConfig {
+            client_ca_path: Some(format!("{}/missing.pem", FIXTURES)),
+            ..fixture_config()
+        };
+
+        let err = server_config(&tls).unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("Failed to load TLS client CA from"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_missing_certificate_is_reported() {
+        let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+        let tls = TlsConfig {
+            cert_path: format!("{}/missing.pem", FIXTURES),
+            ..fixture_config()
+        };
+
+        let err = server_config(&tls).unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("Failed to load TLS certificate from"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_certificate_file_used_as_key_is_reported() {
+        let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+        let tls = TlsConfig {
+            key_path: format!("{}/server.pem", FIXTURES),
+            ..fixture_config()
+        };
+
+        let err = server_config(&tls).unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("Failed to load TLS private key from"),
+            "{}",
+            err
+        );
+    }
+}
@@ -1,28 +1,350 @@
 pub mod types;
 
 use anyhow::{Context, Result};
-use config::{Config, File};
+use config::{Config, File, FileFormat};
 use std::path::Path;
 pub use types::*;
 
-/// Load configuration from a TOML file
+/// Load configuration from a TOML, YAML, or JSON file
+/// The format follows the file extension, defaulting to TOML
 pub fn load_config<P: AsRef<Path>>(path: P) -> Result<AppConfig> {
     let path = path.as_ref();
 
     let config = Config::builder()
-        .add_source(File::from(path))
+        .add_source(File::from(path).format(file_format(path)))
         .build()
         .with_context(|| format!("Failed to load config from: {}", path.display()))?;
 
-    let app_config: AppConfig = config
+    let mut app_config: AppConfig = config
         .try_deserialize()
         .context("Failed to deserialize configuration")?;
 
+    app_config.logging.level = normalize_log_level(&app_config.logging.level);
+    app_config.logging.http_request_level =
+        normalize_log_level(&app_config.logging.http_request_level);
+    if let Some(dir) = path.parent() {
+        resolve_env_files(&mut app_config, dir);
+    }
+
     validate_config(&app_config)?;
+    app_config.source = Some(path.to_path_buf());
 
     Ok(app_config)
 }
 
+/// Make relative `env_file` paths relative to the config file's directory
+fn resolve_env_files(config: &mut AppConfig, dir: &Path) {
+    for endpoint in &mut config.endpoints {
+        if let EndpointKindConfig::Local {
+            env_file: Some(env_file),
+            ..
+        } = &mut endpoint.endpoint_type
+            && Path::new(env_file).is_relative()
+        {
+            *env_file = dir.join(&*env_file).display().to_string();
+        }
+    }
+}
+
+/// Commented starter configuration written by `rusted-tools init`
+pub const STARTER_CONFIG: &str = include_str!("starter.toml");
+
+/// Write the starter configuration to `path`, refusing to replace an existing file unless `force`
+pub fn write_starter_config<P: AsRef<Path>>(path: P, force: bool) -> Result<()> {
+    let path = path.as_ref();
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true);
+    if force {
+        options.create(true).truncate(true);
+    } else {
+        options.create_new(true);
+    }
+
+    let mut file = options.open(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::AlreadyExists => anyhow::anyhow!(
+            "{} already exists; pass --force to overwrite it",
+            path.display()
+        ),
+        _ => anyhow::Error::new(e).context(format!("Failed to create {}", path.display())),
+    })?;
+    std::io::Write::write_all(&mut file, STARTER_CONFIG.as_bytes())
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
+
+/// Pick the config file format from the path's extension
+fn file_format(path: &Path) -> FileFormat {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase);
+    match extension.as_deref() {
+        Some("yaml" | "yml") => FileFormat::Yaml,
+        Some("json") => FileFormat::Json,
+        _ => FileFormat::Toml,
+    }
+}
+
+/// Normalize a log level to the lowercase form expected by `EnvFilter`,
+/// mapping common aliases (e.g. `warning` -> `warn`)
+pub fn normalize_log_level(level: &str) -> String {
+    let level = level.trim().to_ascii_lowercase();
+    match level.as_str() {
+        "warning" => "warn".to_string(),
+        _ => level,
+    }
+}
+
+/// Replace `${NAME}` references with the value of the environment variable `NAME`
+pub(crate) fn expand_env_vars(value: &str) -> crate::Result<String> {
+    let mut expanded = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(start) = rest.find("${") {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after.find('}').ok_or_else(|| {
+            crate::ProxyError::config(format!("Unterminated ${{...}} in '{}'", value))
+        })?;
+        let name = &after[..end];
+        let resolved = std::env::var(name).map_err(|_| {
+            crate::ProxyError::config(format!("Environment variable '{}' is not set", name))
+        })?;
+        expanded.push_str(&resolved);
+        rest = &after[end + 1..];
+    }
+
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+/// Problems that don't make the configuration invalid but will likely bite at runtime
+pub fn config_warnings(config: &AppConfig) -> Vec<String> {
+    let mut warnings = Vec::new();
+    if config.endpoints.is_empty() {
+        warnings.push("No endpoints configured".to_string());
+    }
+
+    for endpoint in &config.endpoints {
+        match &endpoint.endpoint_type {
+            EndpointKindConfig::Local { command, .. } => {
+                if !command_exists(command) {
+                    warnings.push(format!(
+                        "Endpoint '{}': command '{}' not found",
+                        endpoint.name, command
+                    ));
+                }
+            }
+            EndpointKindConfig::Remote { headers, .. } => {
+                for (name, value) in headers {
+                    if let Err(e) = expand_env_vars(value) {
+                        warnings.push(format!(
+                            "Endpoint '{}': header '{}': {}",
+                            endpoint.name, name, e
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    warnings
+}
+
+/// Whether a command can be spawned as given, either as a path or through `PATH`
+fn command_exists(command: &str) -> bool {
+    if command.contains(std::path::MAIN_SEPARATOR) {
+        return Path::new(command).is_file();
+    }
+
+    std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).any(|dir| dir.join(command).is_file()))
+        .unwrap_or(false)
+}
+
+/// Endpoint path taken by the aggregate routes under `/mcp`
+const RESERVED_ENDPOINT_PATH: &str = "tools";
+
+/// Slowest accepted rate limit refill, one request every ~17 minutes
+const MIN_REQUESTS_PER_SECOND: f64 = 0.001;
+
+/// Check that an endpoint name can be mounted at `/mcp/{name}`
+fn validate_endpoint_path(name: &str) -> crate::Result<()> {
+    if name.is_empty() {
+        return Err(crate::ProxyError::InvalidRequest(
+            "Endpoint name must not be empty".to_string(),
+        ));
+    }
+
+    validate_mount_path(name, name)
+}
+
+/// Check that an endpoint's aliases can be mounted like its primary path
+fn validate_endpoint_aliases(name: &str, aliases: &[String]) -> crate::Result<()> {
+    for alias in aliases {
+        if alias.is_empty() {
+            return Err(crate::ProxyError::InvalidRequest(format!(
+                "Endpoint '{}' has an empty alias",
+                name
+            )));
+        }
+        validate_mount_path(name, alias)?;
+    }
+    Ok(())
+}
+
+/// Upstream URLs of a remote endpoint, which sets exactly one of `url` and `urls`
+pub(crate) fn remote_urls(name: &str, url: &str, urls: &[String]) -> crate::Result<Vec<String>> {
+    match (url.is_empty(), urls.is_empty()) {
+        (false, true) => Ok(vec![url.to_string()]),
+        (true, false) if urls.iter().all(|url| !url.is_empty()) => Ok(urls.to_vec()),
+        (true, false) => Err(crate::ProxyError::InvalidRequest(format!(
+            "Endpoint '{}' has an empty URL in urls",
+            name
+        ))),
+        (false, false) => Err(crate::ProxyError::InvalidRequest(format!(
+            "Endpoint '{}' sets both url and urls",
+            name
+        ))),
+        (true, true) => Err(crate::ProxyError::InvalidRequest(format!(
+            "Remote endpoint '{}' needs a url or urls",
+            name
+        ))),
+    }
+}
+
+fn validate_mount_path(name: &str, path: &str) -> crate::Result<()> {
+    if let Some(invalid) = path.chars().find(|c| matches!(c, '/' | '\\' | '.')) {
+        return Err(crate::ProxyError::InvalidRequest(format!(
+            "Endpoint '{}' has invalid path '{}': contains '{}' (/, \\, and . are not allowed)",
+            name, path, invalid
+        )));
+    }
+
+    if let Some(conflict) = route_conflict(name, path) {
+        return Err(crate::ProxyError::InvalidRequest(conflict));
+    }
+
+    Ok(())
+}
+
+/// Describe the fixed route an endpoint path would shadow, if any
+///
+/// `/mcp/tools` lists the tools of all endpoints, so no endpoint may claim that path.
+/// Case is ignored, since `path_matching = "case_insensitive"` would route `/mcp/tools`
+/// to an endpoint at `Tools`. Management routes live outside `/mcp` and can't be shadowed.
+fn route_conflict(name: &str, path: &str) -> Option<String> {
+    path.eq_ignore_ascii_case(RESERVED_ENDPOINT_PATH)
+        .then(|| {
+            format!(
+                "Endpoint '{}' has path '{}', which is reserved for the aggregate tool listing at /mcp/{}",
+                name, path, RESERVED_ENDPOINT_PATH
+            )
+        })
+}
+
+/// Check that a rate limit refills at a finite rate of at least `MIN_REQUESTS_PER_SECOND`
+/// and admits at least one request at a time
+fn validate_rate_limit(field: &str, limit: &RateLimit) -> crate::Result<()> {
+    if !(limit.requests_per_second.is_finite()
+        && limit.requests_per_second >= MIN_REQUESTS_PER_SECOND)
+    {
+        return Err(crate::ProxyError::InvalidRequest(format!(
+            "Invalid {}.requests_per_second: {}. Must be at least {}",
+            field, limit.requests_per_second, MIN_REQUESTS_PER_SECOND
+        )));
+    }
+
+    if limit.burst == 0 {
+        return Err(crate::ProxyError::InvalidRequest(format!(
+            "Invalid {}.burst: 0. Must be at least 1",
+            field
+        )));
+    }
+
+    Ok(())
+}
+
+/// Check that no two tools are renamed to the same alias
+fn validate_tool_renames(field: &str, filter: &ToolFilter) -> crate::Result<()> {
+    let mut aliases = std::collections::HashSet::new();
+    for alias in filter.rename.values() {
+        if !aliases.insert(alias) {
+            return Err(crate::ProxyError::InvalidRequest(format!(
+                "Invalid {}.rename: more than one tool is renamed to '{}'",
+                field, alias
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Check the settings of a single endpoint, both when loading the config file and when
+/// adding an endpoint at runtime
+///
+/// Clashes with the paths of other endpoints are left to the caller.
+pub(crate) fn validate_endpoint(
+    endpoint: &EndpointConfig,
+    path_matching: &PathMatching,
+) -> crate::Result<()> {
+    let name = &endpoint.name;
+    validate_endpoint_path(name)?;
+    validate_endpoint_aliases(name, &endpoint.aliases)?;
+    for (i, alias) in endpoint.aliases.iter().enumerate() {
+        if let Some(other) = std::iter::once(name)
+            .chain(&endpoint.aliases[..i])
+            .find(|other| path_matching.same_path(other, alias))
+        {
+            return Err(crate::ProxyError::InvalidRequest(format!(
+                "Alias '{}' of endpoint '{}' clashes with its own path '{}'",
+                alias, name, other
+            )));
+        }
+    }
+
+    match &endpoint.endpoint_type {
+        EndpointKindConfig::Remote {
+            url,
+            urls,
+            connect_timeout_secs,
+            ..
+        } => {
+            remote_urls(name, url, urls)?;
+            if *connect_timeout_secs == Some(0) {
+                return Err(crate::ProxyError::InvalidRequest(format!(
+                    "Invalid endpoints.{}.connect_timeout_secs: 0. Minimum value is 1",
+                    name
+                )));
+            }
+        }
+        EndpointKindConfig::Local {
+            health_command: Some(health),
+            ..
+        } if health.interval_secs == 0 => {
+            return Err(crate::ProxyError::InvalidRequest(format!(
+                "Invalid endpoints.{}.health_command.interval_secs: 0. Minimum value is 1",
+                name
+            )));
+        }
+        EndpointKindConfig::Local { .. } => {}
+    }
+    if let Some(limit) = &endpoint.rate_limit {
+        validate_rate_limit(&format!("endpoints.{}.rate_limit", name), limit)?;
+    }
+    if let Some(tools) = &endpoint.tools {
+        validate_tool_renames(&format!("endpoints.{}.tools", name), tools)?;
+    }
+    if endpoint.max_concurrent_requests == Some(0) {
+        return Err(crate::ProxyError::InvalidRequest(format!(
+            "Invalid endpoints.{}.max_concurrent_requests: 0. Minimum value is 1",
+            name
+        )));
+    }
+
+    Ok(())
+}
+
 /// Validate the loaded configuration
 fn validate_config(config: &AppConfig) -> Result<()> {
     // Validate that endpoint names/paths are unique
@@ -36,17 +358,48 @@ fn validate_config(config: &AppConfig) -> Result<()> {
         }
     }
 
-    // Validate endpoint paths don't contain special characters
+    // Paths are derived from endpoint names, so only aliases can clash with another path
+    let mut paths = std::collections::HashMap::new();
     for endpoint in &config.endpoints {
-        let path = endpoint.name.clone();
-        if path.contains('/') || path.contains('\\') || path.contains('.') {
-            anyhow::bail!(
-                "Endpoint path '{}' contains invalid characters (/, \\, or .)",
-                path
-            );
+        paths.insert(endpoint.name.as_str(), endpoint.name.as_str());
+    }
+    for endpoint in &config.endpoints {
+        for alias in &endpoint.aliases {
+            if let Some(other) = paths.insert(alias.as_str(), endpoint.name.as_str()) {
+                anyhow::bail!(
+                    "Alias '{}' of endpoint '{}' clashes with the path of endpoint '{}'",
+                    alias,
+                    endpoint.name,
+                    other
+                );
+            }
+        }
+    }
+
+    // Case-insensitive matching can't tell apart paths that differ only in case
+    if config.mcp.path_matching == PathMatching::CaseInsensitive {
+        let mut folded = std::collections::HashMap::new();
+        for (path, name) in paths {
+            if let Some(other) = folded.insert(path.to_ascii_lowercase(), path) {
+                anyhow::bail!(
+                    "Path '{}' of endpoint '{}' differs from path '{}' only in case, \
+                     which mcp.path_matching = \"case_insensitive\" can't distinguish",
+                    path,
+                    name,
+                    other
+                );
+            }
         }
     }
 
+    // The checks answer runtime adds with 400; in the config file they're configuration errors
+    for endpoint in &config.endpoints {
+        validate_endpoint(endpoint, &config.mcp.path_matching).map_err(|e| match e {
+            crate::ProxyError::InvalidRequest(message) => crate::ProxyError::config(message),
+            e => e,
+        })?;
+    }
+
     // Validate log level
     let valid_levels = ["trace", "debug", "info", "warn", "error"];
     if !valid_levels.contains(&config.logging.level.as_str()) {
@@ -56,6 +409,13 @@ fn validate_config(config: &AppConfig) -> Result<()> {
             valid_levels.join(", ")
         );
     }
+    if !valid_levels.contains(&config.logging.http_request_level.as_str()) {
+        anyhow::bail!(
+            "Invalid logging.http_request_level '{}'. Valid levels: {}",
+            config.logging.http_request_level,
+            valid_levels.join(", ")
+        );
+    }
 
     // Validate log format
     let valid_formats = ["pretty", "json"];
@@ -75,6 +435,34 @@ fn validate_config(config: &AppConfig) -> Result<()> {
         );
     }
 
+    if config.mcp.max_list_pages == 0 {
+        anyhow::bail!("Invalid mcp.max_list_pages: 0. Minimum value is 1");
+    }
+
+    if config.mcp.connect_timeout_secs == 0 {
+        anyhow::bail!("Invalid mcp.connect_timeout_secs: 0. Minimum value is 1");
+    }
+
+    if config.mcp.client_info.name.is_empty() {
+        anyhow::bail!("Invalid mcp.client_info.name: must not be empty");
+    }
+    let capabilities = config.mcp.client_info.capabilities.clone().into();
+    if let Err(e) = serde_json::from_value::<rmcp::model::ClientCapabilities>(capabilities) {
+        anyhow::bail!("Invalid mcp.client_info.capabilities: {}", e);
+    }
+
+    if config.mcp.max_json_depth == 0 {
+        anyhow::bail!("Invalid mcp.max_json_depth: 0. Minimum value is 1");
+    }
+
+    if let Some(limit) = &config.mcp.rate_limit {
+        validate_rate_limit("mcp.rate_limit", limit)?;
+    }
+
+    if config.mcp.max_concurrent_requests == Some(0) {
+        anyhow::bail!("Invalid mcp.max_concurrent_requests: 0. Minimum value is 1");
+    }
+
     Ok(())
 }
 
@@ -136,6 +524,595 @@ args = ["hello"]
         assert_eq!(config.http.port, 3000);
         assert_eq!(config.logging.level, "info");
         assert_eq!(config.logging.format, "pretty");
+        assert_eq!(config.mcp.remote_pool_max_idle, 8);
+        assert_eq!(config.mcp.remote_pool_idle_timeout_secs, 90);
+        assert_eq!(config.mcp.connect_timeout_secs, 10);
+        assert!(!config.auth.is_enabled());
+        assert_eq!(config.auth.public_paths, vec!["/health", "/ready"]);
+    }
+
+    #[test]
+    fn test_load_auth_with_single_token() {
+        let config = load_from(
+            ".toml",
+            r#"
+[http]
+
+[auth]
+token = "secret"
+public_paths = ["/health", "/info"]
+"#,
+        );
+
+        assert!(config.auth.is_enabled());
+        assert!(config.auth.accepts("secret"));
+        assert!(!config.auth.accepts("other"));
+        assert!(config.auth.is_public("/info"));
+    }
+
+    fn assert_equivalent_config(config: &AppConfig) {
+        assert_eq!(config.http.port, 8080);
+        assert_eq!(config.mcp.request_timeout_secs, 10);
+        assert_eq!(config.endpoints.len(), 1);
+        assert_eq!(config.endpoints[0].name, "time");
+        assert!(config.endpoints[0].enabled);
+        match &config.endpoints[0].endpoint_type {
+            EndpointKindConfig::Local { command, args, .. } => {
+                assert_eq!(command, "docker");
+                assert_eq!(args, &["run", "--rm", "-i", "mcp/time"]);
+            }
+            _ => panic!("Expected local endpoint"),
+        }
+    }
+
+    fn load_from(suffix: &str, content: &str) -> AppConfig {
+        let mut temp_file = NamedTempFile::with_suffix(suffix).unwrap();
+        temp_file.write_all(content.as_bytes()).unwrap();
+        load_config(temp_file.path()).unwrap()
+    }
+
+    const TOML_CONFIG: &str = r#"
+[http]
+port = 8080
+
+[mcp]
+request_timeout_secs = 10
+
+[[endpoints]]
+name = "time"
+type = "local"
+command = "docker"
+args = ["run", "--rm", "-i", "mcp/time"]
+"#;
+
+    #[test]
+    fn test_load_config_from_toml_yaml_and_json() {
+        assert_equivalent_config(&load_from(".toml", TOML_CONFIG));
+
+        let yaml = r#"
+http:
+  port: 8080
+mcp:
+  request_timeout_secs: 10
+endpoints:
+  - name: time
+    type: local
+    command: docker
+    args: ["run", "--rm", "-i", "mcp/time"]
+"#;
+        assert_equivalent_config(&load_from(".yaml", yaml));
+        assert_equivalent_config(&load_from(".yml", yaml));
+
+        let json = r#"{
+  "http": {"port": 8080},
+  "mcp": {"request_timeout_secs": 10},
+  "endpoints": [
+    {"name": "time", "type": "local", "command": "docker", "args": ["run", "--rm", "-i", "mcp/time"]}
+  ]
+}"#;
+        assert_equivalent_config(&load_from(".json", json));
+    }
+
+    #[test]
+    fn test_load_config_with_tag_list() {
+        let config = load_from(
+            ".toml",
+            r#"
+[http]
+
+[[endpoints]]
+name = "docs"
+type = "remote"
+url = "https://example.com/mcp"
+tags = ["team=docs", "internal"]
+"#,
+        );
+
+        let metadata = &config.endpoints[0].metadata;
+        assert_eq!(metadata.tags["team"], "docs");
+        assert_eq!(metadata.tags["internal"], "");
+        assert!(metadata.has_tags(["team=docs", "internal"]));
+        assert!(!metadata.has_tags(["team=search"]));
+        assert!(!metadata.has_tags(["team"]));
+    }
+
+    #[test]
+    fn test_load_config_with_endpoint_metadata() {
+        let config = load_from(
+            ".toml",
+            r#"
+[http]
+
+[[endpoints]]
+name = "docs"
+type = "remote"
+url = "https://example.com/mcp"
+display_name = "Docs Search"
+description = "Search product documentation"
+tags = { team = "docs", env = "prod" }
+"#,
+        );
+
+        let metadata = &config.endpoints[0].metadata;
+        assert_eq!(metadata.display_name.as_deref(), Some("Docs Search"));
+        assert_eq!(
+            metadata.description.as_deref(),
+            Some("Search product documentation")
+        );
+        assert_eq!(metadata.tags["team"], "docs");
+        assert_eq!(metadata.tags["env"], "prod");
+        assert!(matches!(
+            config.endpoints[0].endpoint_type,
+            EndpointKindConfig::Remote { .. }
+        ));
+    }
+
+    #[test]
+    fn test_load_config_with_health_command() {
+        let config = load_from(
+            ".toml",
+            r#"
+[http]
+
+[[endpoints]]
+name = "db-tools"
+type = "local"
+command = "db-mcp"
+args = []
+health_command = { command = "pg_isready", args = ["-h", "localhost"] }
+"#,
+        );
+
+        match &config.endpoints[0].endpoint_type {
+            EndpointKindConfig::Local {
+                health_command: Some(health),
+                ..
+            } => {
+                assert_eq!(health.command, "pg_isready");
+                assert_eq!(health.args, vec!["-h", "localhost"]);
+                assert_eq!(health.interval_secs, 30);
+            }
+            _ => panic!("Expected local endpoint with health command"),
+        }
+
+        let mut zero = config.clone();
+        if let EndpointKindConfig::Local {
+            health_command: Some(health),
+            ..
+        } = &mut zero.endpoints[0].endpoint_type
+        {
+            health.interval_secs = 0;
+        }
+        let err = validate_config(&zero).unwrap_err().to_string();
+        assert!(err.contains("health_command.interval_secs"), "{}", err);
+    }
+
+    #[test]
+    fn test_load_config_with_startup_retry_defaults() {
+        let config = load_from(
+            ".toml",
+            r#"
+[http]
+
+[[endpoints]]
+name = "fetch"
+type = "local"
+command = "docker"
+args = ["run", "--rm", "-i", "mcp/fetch"]
+startup_retry = { max_attempts = 5 }
+"#,
+        );
+
+        match &config.endpoints[0].endpoint_type {
+            EndpointKindConfig::Local {
+                startup_retry: Some(retry),
+                ..
+            } => {
+                assert_eq!(retry.max_attempts, 5);
+                assert_eq!(retry.delay_secs, 10);
+                assert!(retry.on_errors.iter().any(|e| e == "Unable to find image"));
+            }
+            _ => panic!("Expected local endpoint with startup retry"),
+        }
+    }
+
+    #[test]
+    fn test_load_config_with_rate_limits() {
+        let config = load_from(
+            ".toml",
+            r#"
+[http]
+
+[mcp]
+rate_limit = { requests_per_second = 5, burst = 10 }
+
+[[endpoints]]
+name = "fetch"
+type = "remote"
+url = "http://localhost:8080/mcp"
+rate_limit = { requests_per_second = 0.5 }
+"#,
+        );
+
+        let global = config.mcp.rate_limit.unwrap();
+        assert_eq!(global.requests_per_second, 5.0);
+        assert_eq!(global.burst, 10);
+
+        let endpoint = config.endpoints[0].rate_limit.unwrap();
+        assert_eq!(endpoint.requests_per_second, 0.5);
+        assert_eq!(endpoint.burst, 1);
+    }
+
+    #[test]
+    fn test_load_config_resolves_env_file_against_config_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(
+            &path,
+            r#"
+[http]
+
+[[endpoints]]
+name = "relative"
+type = "local"
+command = "echo"
+args = []
+env_file = "secrets.env"
+
+[[endpoints]]
+name = "absolute"
+type = "local"
+command = "echo"
+args = []
+env_file = "/run/secrets/app.env"
+"#,
+        )
+        .unwrap();
+
+        let config = load_config(&path).unwrap();
+        assert_eq!(config.source.as_deref(), Some(path.as_path()));
+        let env_files: Vec<_> = config
+            .endpoints
+            .iter()
+            .map(|endpoint| endpoint.to_local_settings().unwrap().env_file.unwrap())
+            .collect();
+        assert_eq!(
+            env_files,
+            vec![
+                dir.path().join("secrets.env").display().to_string(),
+                "/run/secrets/app.env".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_load_config_with_client_info() {
+        let config = load_from(
+            ".toml",
+            r#"
+[http]
+
+[mcp.client_info]
+name = "acme-gateway"
+capabilities = { roots = { listChanged = true } }
+"#,
+        );
+        let identity = &config.mcp.client_info;
+        assert_eq!(identity.name, "acme-gateway");
+        assert_eq!(identity.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(identity.capabilities["roots"]["listChanged"], true);
+
+        let defaults = ClientIdentity::default();
+        assert_eq!(defaults.name, "rusted-tools");
+        assert!(defaults.capabilities.is_empty());
+    }
+
+    #[test]
+    fn test_validate_rejects_invalid_client_capabilities() {
+        let mut config = AppConfig::default();
+        config
+            .mcp
+            .client_info
+            .capabilities
+            .insert("roots".to_string(), serde_json::json!("yes"));
+
+        let err = validate_config(&config).unwrap_err().to_string();
+        assert!(err.contains("mcp.client_info.capabilities"), "{}", err);
+    }
+
+    #[test]
+    fn test_load_config_with_path_matching() {
+        let config = load_from(
+            ".toml",
+            r#"
+[http]
+
+[mcp]
+path_matching = "normalize_trailing_slash"
+"#,
+        );
+        assert_eq!(
+            config.mcp.path_matching,
+            PathMatching::NormalizeTrailingSlash
+        );
+        assert_eq!(McpConfig::default().path_matching, PathMatching::Exact);
+    }
+
+    #[test]
+    fn test_load_config_with_aggregate_tool_names() {
+        let config = load_from(
+            ".toml",
+            r#"
+[http]
+
+[mcp]
+aggregate_tool_names = "disambiguate"
+"#,
+        );
+        assert_eq!(
+            config.mcp.aggregate_tool_names,
+            AggregateToolNames::Disambiguate
+        );
+        assert_eq!(
+            McpConfig::default().aggregate_tool_names,
+            AggregateToolNames::Namespaced
+        );
+    }
+
+    #[test]
+    fn test_validate_rejects_zero_rate_limit() {
+        let mut endpoint = create_local_endpoint("time");
+        endpoint.rate_limit = Some(RateLimit {
+            requests_per_second: 0.0,
+            burst: 1,
+        });
+        let config = AppConfig {
+            endpoints: vec![endpoint],
+            ..Default::default()
+        };
+
+        let err = validate_config(&config).unwrap_err().to_string();
+        assert!(err.contains("endpoints.time.rate_limit.requests_per_second"));
+    }
+
+    #[test]
+    fn test_validate_rejects_degenerate_rate_limits() {
+        let config_with = |limit: RateLimit| AppConfig {
+            mcp: McpConfig {
+                rate_limit: Some(limit),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let err = validate_config(&config_with(RateLimit {
+            requests_per_second: 1e-20,
+            burst: 1,
+        }))
+        .unwrap_err()
+        .to_string();
+        assert!(
+            err.contains("mcp.rate_limit.requests_per_second"),
+            "{}",
+            err
+        );
+
+        let err = validate_config(&config_with(RateLimit {
+            requests_per_second: 1.0,
+            burst: 0,
+        }))
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("mcp.rate_limit.burst"), "{}", err);
+    }
+
+    #[test]
+    fn test_starter_config_loads() {
+        let config = load_from(".toml", STARTER_CONFIG);
+        assert_eq!(config.endpoints.len(), 2);
+        assert!(config.endpoints[0].tools.is_some());
+    }
+
+    #[test]
+    fn test_write_starter_config_refuses_to_overwrite() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "[http]\n").unwrap();
+
+        let err = write_starter_config(&path, false).unwrap_err().to_string();
+        assert!(err.contains("--force"));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "[http]\n");
+
+        write_starter_config(&path, true).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), STARTER_CONFIG);
+    }
+
+    #[test]
+    fn test_load_config_without_extension_uses_toml() {
+        assert_equivalent_config(&load_from("", TOML_CONFIG));
+    }
+
+    #[test]
+    fn test_load_config_normalizes_log_level() {
+        let config_content = r#"
+[http]
+
+[logging]
+level = "WARNING"
+"#;
+
+        let mut temp_file = NamedTempFile::with_suffix(".toml").unwrap();
+        temp_file.write_all(config_content.as_bytes()).unwrap();
+
+        let config = load_config(temp_file.path()).unwrap();
+        assert_eq!(config.logging.level, "warn");
+    }
+
+    #[test]
+    fn test_load_config_with_http_request_logging() {
+        let config = load_from(
+            ".toml",
+            r#"
+[http]
+
+[logging]
+http_requests = false
+http_request_level = "INFO"
+"#,
+        );
+        assert!(!config.logging.http_requests);
+        assert_eq!(config.logging.http_request_level, "info");
+
+        let mut invalid = config.clone();
+        invalid.logging.http_request_level = "loud".to_string();
+        let err = validate_config(&invalid).unwrap_err().to_string();
+        assert!(err.contains("logging.http_request_level"), "{}", err);
+    }
+
+    #[test]
+    fn test_normalize_log_level() {
+        assert_eq!(normalize_log_level("INFO"), "info");
+        assert_eq!(normalize_log_level("Debug"), "debug");
+        assert_eq!(normalize_log_level("warning"), "warn");
+        assert_eq!(normalize_log_level(" Trace "), "trace");
+        assert_eq!(normalize_log_level("verbose"), "verbose");
+    }
+
+    #[test]
+    fn test_load_config_rejects_unknown_log_level() {
+        let config_content = r#"
+[http]
+
+[logging]
+level = "Verbose"
+"#;
+
+        let mut temp_file = NamedTempFile::with_suffix(".toml").unwrap();
+        temp_file.write_all(config_content.as_bytes()).unwrap();
+
+        assert!(load_config(temp_file.path()).is_err());
+    }
+
+    #[test]
+    fn test_load_config_with_header_tool_filters() {
+        let config_content = r#"
+[http]
+
+[[endpoints]]
+name = "tiered"
+type = "local"
+command = "echo"
+args = []
+
+[endpoints.tools]
+include = ["basic_tool"]
+
+[[endpoints.tools.when_header]]
+header = "x-tier"
+value = "pro"
+include = ["basic_tool", "pro_tool"]
+"#;
+
+        let mut temp_file = NamedTempFile::with_suffix(".toml").unwrap();
+        temp_file.write_all(config_content.as_bytes()).unwrap();
+
+        let config = load_config(temp_file.path()).unwrap();
+        let filter = config.endpoints[0].tools.as_ref().unwrap();
+        assert_eq!(filter.when_header.len(), 1);
+        assert_eq!(filter.when_header[0].header, "x-tier");
+        assert_eq!(filter.when_header[0].value, "pro");
+        assert!(filter.when_header[0].exclude.is_none());
+    }
+
+    #[test]
+    fn test_load_config_with_tool_filter_regexes() {
+        let config = load_from(
+            ".toml",
+            r#"
+[http]
+
+[[endpoints]]
+name = "github"
+type = "remote"
+url = "https://example.com/mcp"
+
+[endpoints.tools]
+include_regex = "^github_"
+exclude_regex = "_delete_"
+"#,
+        );
+
+        let filter = config.endpoints[0].tools.as_ref().unwrap();
+        assert!(filter.allows("github_list_repos"));
+        assert!(!filter.allows("github_delete_repo"));
+        assert!(!filter.allows("search"));
+    }
+
+    #[test]
+    fn test_load_config_rejects_invalid_tool_filter_regex() {
+        let mut temp_file = NamedTempFile::with_suffix(".toml").unwrap();
+        temp_file
+            .write_all(
+                br#"
+[http]
+
+[[endpoints]]
+name = "github"
+type = "remote"
+url = "https://example.com/mcp"
+
+[endpoints.tools]
+include_regex = "github_("
+"#,
+            )
+            .unwrap();
+
+        let err = load_config(temp_file.path()).unwrap_err();
+        assert!(
+            format!("{:#}", err).contains("Invalid tool filter regex 'github_('"),
+            "{:#}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_validate_rejects_duplicate_tool_alias() {
+        let mut endpoint = create_local_endpoint("docs");
+        endpoint.tools = Some(ToolFilter {
+            rename: [
+                ("search".to_string(), "find".to_string()),
+                ("lookup".to_string(), "find".to_string()),
+            ]
+            .into(),
+            ..Default::default()
+        });
+        let config = AppConfig {
+            endpoints: vec![endpoint],
+            ..Default::default()
+        };
+
+        let err = validate_config(&config).unwrap_err();
+        assert!(err.to_string().contains("renamed to 'find'"), "{}", err);
     }
 
     #[test]
@@ -147,25 +1124,48 @@ args = ["hello"]
             endpoints: vec![
                 EndpointConfig {
                     name: "server".to_string(),
+                    aliases: Vec::new(),
                     endpoint_type: EndpointKindConfig::Local {
                         command: "echo".to_string(),
                         args: vec![],
                         env: Default::default(),
+                        clean_env: false,
                         auto_start: true,
+                        health_command: None,
+                        cwd: None,
+                        restart_on_failure: false,
+                        startup_retry: None,
+                        env_file: None,
                     },
                     tools: None,
+                    rate_limit: None,
+                    max_concurrent_requests: None,
+                    enabled: true,
+                    metadata: Default::default(),
                 },
                 EndpointConfig {
                     name: "server".to_string(),
+                    aliases: Vec::new(),
                     endpoint_type: EndpointKindConfig::Local {
                         command: "echo".to_string(),
                         args: vec![],
                         env: Default::default(),
+                        clean_env: false,
                         auto_start: true,
+                        health_command: None,
+                        cwd: None,
+                        restart_on_failure: false,
+                        startup_retry: None,
+                        env_file: None,
                     },
                     tools: None,
+                    rate_limit: None,
+                    max_concurrent_requests: None,
+                    enabled: true,
+                    metadata: Default::default(),
                 },
             ],
+            ..Default::default()
         };
 
         assert!(validate_config(&config).is_err());
@@ -179,16 +1179,305 @@ args = ["hello"]
             mcp: Default::default(),
             endpoints: vec![EndpointConfig {
                 name: "server/path".to_string(),
+                aliases: Vec::new(),
                 endpoint_type: EndpointKindConfig::Local {
                     command: "echo".to_string(),
                     args: vec![],
                     env: Default::default(),
+                    clean_env: false,
                     auto_start: true,
+                    health_command: None,
+                    cwd: None,
+                    restart_on_failure: false,
+                    startup_retry: None,
+                    env_file: None,
                 },
                 tools: None,
+                rate_limit: None,
+                max_concurrent_requests: None,
+                enabled: true,
+                metadata: Default::default(),
             }],
+            ..Default::default()
         };
 
         assert!(validate_config(&config).is_err());
     }
+
+    fn create_local_endpoint(name: &str) -> EndpointConfig {
+        EndpointConfig {
+            name: name.to_string(),
+            aliases: Vec::new(),
+            endpoint_type: EndpointKindConfig::Local {
+                command: "echo".to_string(),
+                args: vec![],
+                env: Default::default(),
+                clean_env: false,
+                auto_start: true,
+                health_command: None,
+                cwd: None,
+                restart_on_failure: false,
+                startup_retry: None,
+                env_file: None,
+            },
+            tools: None,
+            rate_limit: None,
+            max_concurrent_requests: None,
+            enabled: true,
+            metadata: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_validate_duplicate_name_error_names_endpoint() {
+        let config = AppConfig {
+            endpoints: vec![create_local_endpoint("time"), create_local_endpoint("time")],
+            ..Default::default()
+        };
+
+        let err = validate_config(&config).unwrap_err().to_string();
+        assert!(err.contains("'time'"));
+    }
+
+    #[test]
+    fn test_validate_aliases() {
+        let with_aliases = |name: &str, aliases: &[&str]| EndpointConfig {
+            aliases: aliases.iter().map(|a| a.to_string()).collect(),
+            ..create_local_endpoint(name)
+        };
+        let validate = |endpoints| {
+            validate_config(&AppConfig {
+                endpoints,
+                ..Default::default()
+            })
+        };
+
+        assert!(validate(vec![with_aliases("docs", &["old-docs"])]).is_ok());
+
+        let err = validate(vec![
+            with_aliases("docs", &["search"]),
+            create_local_endpoint("search"),
+        ])
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("'search'"), "{}", err);
+
+        let err = validate(vec![
+            with_aliases("docs", &["legacy"]),
+            with_aliases("search", &["legacy"]),
+        ])
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("'legacy'"), "{}", err);
+
+        for alias in ["old/docs", "tools", ""] {
+            assert!(validate(vec![with_aliases("docs", &[alias])]).is_err());
+        }
+    }
+
+    #[test]
+    fn test_validate_case_insensitive_path_clash() {
+        let mut config = AppConfig {
+            endpoints: vec![
+                EndpointConfig {
+                    aliases: vec!["Docs".to_string()],
+                    ..create_local_endpoint("search")
+                },
+                create_local_endpoint("docs"),
+            ],
+            ..Default::default()
+        };
+        assert!(validate_config(&config).is_ok());
+
+        config.mcp.path_matching = PathMatching::CaseInsensitive;
+        let err = validate_config(&config).unwrap_err().to_string();
+        assert!(err.contains("only in case"), "{}", err);
+    }
+
+    #[test]
+    fn test_validate_endpoint_alias_clashing_with_own_path() {
+        let endpoint = EndpointConfig {
+            aliases: vec!["Docs".to_string()],
+            ..create_local_endpoint("docs")
+        };
+        assert!(validate_endpoint(&endpoint, &PathMatching::Exact).is_ok());
+
+        let err = validate_endpoint(&endpoint, &PathMatching::CaseInsensitive).unwrap_err();
+        assert!(err.to_string().contains("own path 'docs'"), "{}", err);
+    }
+
+    #[test]
+    fn test_expand_env_vars() {
+        // SAFETY: the variable name is unique to this test
+        unsafe { std::env::set_var("RUSTED_TOOLS_TEST_API_KEY", "s3cret") };
+
+        assert_eq!(
+            expand_env_vars("Bearer ${RUSTED_TOOLS_TEST_API_KEY}").unwrap(),
+            "Bearer s3cret"
+        );
+        assert_eq!(expand_env_vars("plain").unwrap(), "plain");
+        assert!(expand_env_vars("${RUSTED_TOOLS_TEST_UNSET_VAR}").is_err());
+        assert!(expand_env_vars("${UNTERMINATED").is_err());
+    }
+
+    #[test]
+    fn test_config_warnings() {
+        let mut remote = create_local_endpoint("remote");
+        remote.endpoint_type = EndpointKindConfig::Remote {
+            url: "http://localhost:8080/mcp".to_string(),
+            urls: Vec::new(),
+            require_tools_on_start: false,
+            headers: std::collections::HashMap::from([(
+                "Authorization".to_string(),
+                "Bearer ${RUSTED_TOOLS_TEST_UNSET_VAR}".to_string(),
+            )]),
+            connect_timeout_secs: None,
+            prewarm: false,
+        };
+        let mut missing = create_local_endpoint("missing");
+        if let EndpointKindConfig::Local { command, .. } = &mut missing.endpoint_type {
+            *command = "rusted-tools-no-such-command".to_string();
+        }
+        let config = AppConfig {
+            endpoints: vec![create_local_endpoint("sh"), remote, missing],
+            ..Default::default()
+        };
+
+        let warnings = config_warnings(&config);
+        assert_eq!(warnings.len(), 2, "{:?}", warnings);
+        assert!(warnings[0].contains("RUSTED_TOOLS_TEST_UNSET_VAR"));
+        assert!(warnings[1].contains("rusted-tools-no-such-command"));
+
+        let empty = config_warnings(&AppConfig::default());
+        assert_eq!(empty, vec!["No endpoints configured".to_string()]);
+    }
+
+    #[test]
+    fn test_validate_rejects_reserved_endpoint_name() {
+        let config = AppConfig {
+            endpoints: vec![create_local_endpoint("tools")],
+            ..Default::default()
+        };
+
+        let err = validate_config(&config).unwrap_err().to_string();
+        assert!(err.contains("reserved"));
+    }
+
+    #[test]
+    fn test_load_config_rejects_endpoint_shadowing_tool_listing() {
+        for (name, aliases) in [("tools", "[]"), ("Tools", "[]"), ("docs", r#"["tools"]"#)] {
+            let mut temp_file = NamedTempFile::with_suffix(".toml").unwrap();
+            write!(
+                temp_file,
+                r#"
+[http]
+
+[[endpoints]]
+name = "{}"
+aliases = {}
+type = "remote"
+url = "http://localhost:8080/mcp"
+"#,
+                name, aliases
+            )
+            .unwrap();
+
+            let err = load_config(temp_file.path()).unwrap_err();
+            assert!(
+                matches!(
+                    err.downcast_ref::<crate::ProxyError>(),
+                    Some(crate::ProxyError::Config(message)) if message.contains("/mcp/tools")
+                ),
+                "{:#}",
+                err
+            );
+        }
+    }
+
+    #[test]
+    fn test_load_config_with_remote_urls() {
+        let config = load_from(
+            ".toml",
+            r#"
+[http]
+
+[[endpoints]]
+name = "docs"
+type = "remote"
+urls = ["https://eu.example.com/mcp", "https://us.example.com/mcp"]
+"#,
+        );
+
+        let EndpointKindConfig::Remote { url, urls, .. } = &config.endpoints[0].endpoint_type
+        else {
+            panic!("expected a remote endpoint");
+        };
+        assert_eq!(
+            remote_urls("docs", url, urls).unwrap(),
+            ["https://eu.example.com/mcp", "https://us.example.com/mcp"]
+        );
+    }
+
+    #[test]
+    fn test_load_config_with_connect_timeouts() {
+        let config = load_from(
+            ".toml",
+            r#"
+[http]
+
+[mcp]
+connect_timeout_secs = 3
+
+[[endpoints]]
+name = "docs"
+type = "remote"
+url = "https://example.com/mcp"
+connect_timeout_secs = 1
+"#,
+        );
+
+        assert_eq!(config.mcp.connect_timeout_secs, 3);
+        let EndpointKindConfig::Remote {
+            connect_timeout_secs,
+            ..
+        } = &config.endpoints[0].endpoint_type
+        else {
+            panic!("expected a remote endpoint");
+        };
+        assert_eq!(*connect_timeout_secs, Some(1));
+
+        let mut zero = config.clone();
+        zero.mcp.connect_timeout_secs = 0;
+        let err = validate_config(&zero).unwrap_err().to_string();
+        assert!(err.contains("mcp.connect_timeout_secs"), "{}", err);
+    }
+
+    #[test]
+    fn test_remote_urls_requires_exactly_one_of_url_and_urls() {
+        let urls = vec!["https://us.example.com/mcp".to_string()];
+        assert_eq!(
+            remote_urls("docs", "https://eu.example.com/mcp", &[]).unwrap(),
+            ["https://eu.example.com/mcp"]
+        );
+
+        let both = remote_urls("docs", "https://eu.example.com/mcp", &urls).unwrap_err();
+        assert!(both.to_string().contains("both url and urls"), "{}", both);
+        let neither = remote_urls("docs", "", &[]).unwrap_err();
+        assert!(neither.to_string().contains("needs a url"), "{}", neither);
+        let empty = remote_urls("docs", "", &[String::new()]).unwrap_err();
+        assert!(empty.to_string().contains("empty URL"), "{}", empty);
+    }
+
+    #[test]
+    fn test_validate_each_invalid_path_character() {
+        for name in ["a/b", "a\\b", "a.b"] {
+            let config = AppConfig {
+                endpoints: vec![create_local_endpoint(name)],
+                ..Default::default()
+            };
+
+            let err = validate_config(&config).unwrap_err().to_string();
+            assert!(err.contains(name), "error should name '{}': {}", name, err);
+        }
+    }
 }
@@ -1,8 +1,10 @@
 use crate::error::{ProxyError, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::PathBuf;
+use subtle::{Choice, ConstantTimeEq};
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize)]
 pub struct AppConfig {
     pub http: HttpConfig,
     #[serde(default)]
@@ -10,7 +12,21 @@ pub struct AppConfig {
     #[serde(default)]
     pub mcp: McpConfig,
     #[serde(default)]
+    pub management: ManagementConfig,
+    #[serde(default)]
+    pub webhooks: WebhooksConfig,
+    #[serde(default)]
+    pub cors: CorsConfig,
+    #[serde(default)]
+    pub auth: AuthConfig,
+    /// Serve HTTPS instead of plain HTTP when set
+    #[serde(default)]
+    pub tls: Option<TlsConfig>,
+    #[serde(default)]
     pub endpoints: Vec<EndpointConfig>,
+    /// File the configuration was loaded from, re-read when endpoints are reloaded
+    #[serde(skip)]
+    pub source: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -30,12 +46,92 @@ impl Default for HttpConfig {
     }
 }
 
+/// Cross-origin settings for browser-based clients
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CorsConfig {
+    /// How long browsers may cache preflight results (`Access-Control-Max-Age`)
+    #[serde(default)]
+    pub max_age_secs: Option<u64>,
+}
+
+/// Certificate and key the server listener terminates TLS with
+#[derive(Debug, Clone, Deserialize)]
+pub struct TlsConfig {
+    /// PEM file with the certificate chain, leaf first
+    pub cert_path: String,
+    /// PEM file with the private key
+    pub key_path: String,
+    /// PEM file with the CA certificates client certificates must chain to
+    /// When set, clients without a valid certificate are rejected during the handshake
+    #[serde(default)]
+    pub client_ca_path: Option<String>,
+}
+
+/// Bearer tokens required on every request, except on public paths
+#[derive(Debug, Clone, Deserialize)]
+pub struct AuthConfig {
+    /// Accepted bearer tokens; authentication is off when none are configured
+    #[serde(default)]
+    pub tokens: Vec<String>,
+    /// A single accepted token, for configs that only need one
+    #[serde(default)]
+    pub token: Option<String>,
+    /// Exact request paths served without a token
+    #[serde(default = "default_public_paths")]
+    pub public_paths: Vec<String>,
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        Self {
+            tokens: Vec::new(),
+            token: None,
+            public_paths: default_public_paths(),
+        }
+    }
+}
+
+impl AuthConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.token.is_some() || !self.tokens.is_empty()
+    }
+
+    /// Compares against every configured token in constant time, so neither the response
+    /// time nor which token matched reveals anything about the accepted tokens
+    pub fn accepts(&self, token: &str) -> bool {
+        self.token
+            .iter()
+            .chain(&self.tokens)
+            .fold(Choice::from(0), |accepted, candidate| {
+                accepted | candidate.as_bytes().ct_eq(token.as_bytes())
+            })
+            .into()
+    }
+
+    pub fn is_public(&self, path: &str) -> bool {
+        self.public_paths.iter().any(|p| p == path)
+    }
+}
+
+fn default_public_paths() -> Vec<String> {
+    vec!["/health".to_string(), "/ready".to_string()]
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct LoggingConfig {
     #[serde(default = "default_log_level")]
     pub level: String,
     #[serde(default = "default_log_format")]
     pub format: String,
+    /// Include argument values, not just their keys, in the per-tool-call access log
+    #[serde(default)]
+    pub log_tool_arguments: bool,
+    /// Trace every HTTP request and response
+    #[serde(default = "default_http_requests")]
+    pub http_requests: bool,
+    /// Level of the HTTP request spans and their request/response events
+    #[serde(default = "default_http_request_level")]
+    pub http_request_level: String,
 }
 
 impl Default for LoggingConfig {
@@ -43,6 +139,9 @@ impl Default for LoggingConfig {
         Self {
             level: "info".to_string(),
             format: "pretty".to_string(),
+            log_tool_arguments: false,
+            http_requests: default_http_requests(),
+            http_request_level: default_http_request_level(),
         }
     }
 }
@@ -53,6 +152,145 @@ pub struct McpConfig {
     pub request_timeout_secs: u64,
     #[serde(default = "default_restart_delay_ms")]
     pub restart_delay_ms: u64,
+    /// Cap on the exponentially growing delay between consecutive restarts
+    #[serde(default = "default_restart_max_delay_ms")]
+    pub restart_max_delay_ms: u64,
+    /// Seconds an endpoint must stay running before its restart delay resets
+    #[serde(default = "default_restart_backoff_reset_secs")]
+    pub restart_backoff_reset_secs: u64,
+    /// Consecutive automatic restarts before an endpoint is left failed; unlimited when unset
+    #[serde(default)]
+    pub max_restart_attempts: Option<u32>,
+    /// Upper bound on pages fetched when walking a paginated upstream list
+    #[serde(default = "default_max_list_pages")]
+    pub max_list_pages: usize,
+    /// Check structured tool output against the tool's declared output schema
+    #[serde(default)]
+    pub validate_output: bool,
+    /// Reject tool calls whose arguments don't match the tool's input schema
+    /// Schemas come from the tools cache, or a fresh listing per call when it is disabled
+    #[serde(default)]
+    pub validate_arguments: bool,
+    /// Idle connections kept alive per remote upstream
+    #[serde(default = "default_remote_pool_max_idle")]
+    pub remote_pool_max_idle: usize,
+    /// Seconds an idle remote connection is kept before being closed
+    #[serde(default = "default_remote_pool_idle_timeout_secs")]
+    pub remote_pool_idle_timeout_secs: u64,
+    /// Seconds a remote endpoint may take to open a TCP connection, unless the endpoint sets its own
+    #[serde(default = "default_connect_timeout_secs")]
+    pub connect_timeout_secs: u64,
+    /// Extra attempts at a remote handshake or tool listing that failed to connect or timed out
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for each further one
+    #[serde(default = "default_retry_backoff_ms")]
+    pub retry_backoff_ms: u64,
+    /// Seconds a graceful endpoint stop may take before the upstream is dropped
+    #[serde(default = "default_stop_timeout_secs")]
+    pub stop_timeout_secs: u64,
+    /// Seconds a local child gets to exit after SIGTERM before it is killed
+    #[serde(default = "default_shutdown_grace_secs")]
+    pub shutdown_grace_secs: u64,
+    /// Seconds between keep-alive comments on MCP SSE streams; 0 disables them
+    #[serde(default = "default_sse_keep_alive_secs")]
+    pub sse_keep_alive_secs: u64,
+    /// Reconnect delay suggested to MCP SSE clients; 0 leaves it to the client
+    #[serde(default = "default_sse_retry_secs")]
+    pub sse_retry_secs: u64,
+    /// Seconds shutdown waits for in-flight HTTP requests before stopping endpoints
+    #[serde(default = "default_shutdown_drain_secs")]
+    pub shutdown_drain_secs: u64,
+    /// Upper bound on registered endpoints; unlimited when unset
+    #[serde(default)]
+    pub max_endpoints: Option<usize>,
+    /// Maximum nesting depth accepted in tool call payloads
+    #[serde(default = "default_max_json_depth")]
+    pub max_json_depth: usize,
+    /// Seconds between `list_tools` probes of running endpoints; 0 disables probing
+    #[serde(default)]
+    pub health_check_interval_secs: u64,
+    /// Consecutive failed probes before an endpoint is marked failed
+    #[serde(default = "default_health_check_failure_threshold")]
+    pub health_check_failure_threshold: u32,
+    /// Seconds a tool listing is reused before asking the upstream again; 0 disables caching
+    #[serde(default)]
+    pub tools_cache_secs: u64,
+    /// Upstream requests allowed per endpoint, unless the endpoint sets its own
+    #[serde(default)]
+    pub rate_limit: Option<RateLimit>,
+    /// Upstream requests in flight per endpoint, unless the endpoint sets its own; unlimited when unset
+    #[serde(default)]
+    pub max_concurrent_requests: Option<usize>,
+    /// How the `{path}` of `/mcp/{path}` requests is matched against endpoint paths
+    #[serde(default)]
+    pub path_matching: PathMatching,
+    /// How tools of different endpoints are named on `/mcp/tools` and the `/mcp` server
+    /// Modes other than `namespaced` resolve plain tool names from a listing of every
+    /// endpoint, so they should be paired with `tools_cache_secs`
+    #[serde(default)]
+    pub aggregate_tool_names: AggregateToolNames,
+    /// Identity the proxy presents to upstream servers when connecting
+    #[serde(default)]
+    pub client_info: ClientIdentity,
+}
+
+/// Client name, version, and capabilities sent in the MCP `initialize` request
+#[derive(Debug, Clone, Deserialize)]
+pub struct ClientIdentity {
+    #[serde(default = "default_client_name")]
+    pub name: String,
+    #[serde(default = "default_client_version")]
+    pub version: String,
+    /// Client capabilities as in the `initialize` request, e.g. `{ roots = { listChanged = true } }`
+    #[serde(default)]
+    pub capabilities: serde_json::Map<String, serde_json::Value>,
+}
+
+impl Default for ClientIdentity {
+    fn default() -> Self {
+        Self {
+            name: default_client_name(),
+            version: default_client_version(),
+            capabilities: Default::default(),
+        }
+    }
+}
+
+/// Matching of requested MCP paths against registered endpoint paths
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PathMatching {
+    #[default]
+    Exact,
+    /// `/mcp/Time` reaches the endpoint at `time`
+    CaseInsensitive,
+    /// `/mcp/time/tools/` is served as `/mcp/time/tools`
+    NormalizeTrailingSlash,
+}
+
+/// Naming of tools merged from all endpoints by the aggregate routes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AggregateToolNames {
+    /// Every tool is named `{path}:{tool}`
+    #[default]
+    Namespaced,
+    /// Tools keep their own names; a name offered by several endpoints is
+    /// prefixed as `{path}:{tool}` for each of them, with a warning
+    Disambiguate,
+    /// Tools keep their own names; a name offered by several endpoints is an error
+    Error,
+}
+
+impl PathMatching {
+    /// Whether two endpoint paths would be routed as the same path
+    pub(crate) fn same_path(self, a: &str, b: &str) -> bool {
+        match self {
+            PathMatching::CaseInsensitive => a.eq_ignore_ascii_case(b),
+            _ => a == b,
+        }
+    }
 }
 
 impl Default for McpConfig {
@@ -60,17 +298,195 @@ impl Default for McpConfig {
         Self {
             request_timeout_secs: default_request_timeout_secs(),
             restart_delay_ms: default_restart_delay_ms(),
+            restart_max_delay_ms: default_restart_max_delay_ms(),
+            restart_backoff_reset_secs: default_restart_backoff_reset_secs(),
+            max_restart_attempts: None,
+            max_list_pages: default_max_list_pages(),
+            validate_output: false,
+            validate_arguments: false,
+            remote_pool_max_idle: default_remote_pool_max_idle(),
+            remote_pool_idle_timeout_secs: default_remote_pool_idle_timeout_secs(),
+            connect_timeout_secs: default_connect_timeout_secs(),
+            max_retries: default_max_retries(),
+            retry_backoff_ms: default_retry_backoff_ms(),
+            stop_timeout_secs: default_stop_timeout_secs(),
+            shutdown_grace_secs: default_shutdown_grace_secs(),
+            sse_keep_alive_secs: default_sse_keep_alive_secs(),
+            sse_retry_secs: default_sse_retry_secs(),
+            shutdown_drain_secs: default_shutdown_drain_secs(),
+            max_endpoints: None,
+            max_json_depth: default_max_json_depth(),
+            health_check_interval_secs: 0,
+            health_check_failure_threshold: default_health_check_failure_threshold(),
+            tools_cache_secs: 0,
+            rate_limit: None,
+            max_concurrent_requests: None,
+            path_matching: PathMatching::Exact,
+            aggregate_tool_names: AggregateToolNames::Namespaced,
+            client_info: ClientIdentity::default(),
+        }
+    }
+}
+
+/// Controls which management operations are exposed and who may call them
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ManagementConfig {
+    /// Mutating operations that are not registered at all (requests get 404)
+    #[serde(default)]
+    pub disabled_operations: Vec<ManagementOperation>,
+    /// When set, mutating operations require this key in the `X-Admin-Key` header
+    #[serde(default)]
+    pub admin_api_key: Option<String>,
+    /// Register `POST /admin/test-remote`, which makes the proxy connect to any URL a caller
+    /// names; off by default
+    #[serde(default)]
+    pub enable_test_remote: bool,
+}
+
+impl ManagementConfig {
+    pub fn is_enabled(&self, operation: ManagementOperation) -> bool {
+        if operation == ManagementOperation::TestRemote && !self.enable_test_remote {
+            return false;
         }
+        !self.disabled_operations.contains(&operation)
     }
 }
 
+/// Mutating management operations that can be disabled or gated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ManagementOperation {
+    Start,
+    Stop,
+    Restart,
+    TestRemote,
+    /// Register endpoints at runtime with `POST /servers`
+    Add,
+    /// Deregister endpoints at runtime with `DELETE /servers/{name}`
+    Remove,
+    /// Re-apply an endpoint's settings from the config file with `POST /servers/{name}/reload`
+    Reload,
+}
+
+/// Where to POST endpoint lifecycle events, and which ones to send
 #[derive(Debug, Clone, Deserialize)]
+pub struct WebhooksConfig {
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default = "default_webhook_events")]
+    pub events: Vec<LifecycleEvent>,
+    /// Quiet period before an endpoint's event is sent; later selected events in it replace
+    /// the pending one, unless it is a failure
+    #[serde(default = "default_webhook_debounce_ms")]
+    pub debounce_ms: u64,
+}
+
+impl Default for WebhooksConfig {
+    fn default() -> Self {
+        Self {
+            url: None,
+            events: default_webhook_events(),
+            debounce_ms: default_webhook_debounce_ms(),
+        }
+    }
+}
+
+/// Endpoint state changes that can be reported to a webhook
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LifecycleEvent {
+    Started,
+    Stopped,
+    Failed,
+    Restarted,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct EndpointConfig {
     pub name: String,
+    /// Extra `/mcp/{alias}` paths serving the same endpoint, e.g. its old name after a rename
+    #[serde(default)]
+    pub aliases: Vec<String>,
     #[serde(flatten)]
     pub endpoint_type: EndpointKindConfig,
     #[serde(default)]
     pub tools: Option<ToolFilter>,
+    /// Overrides `mcp.rate_limit` for this endpoint
+    #[serde(default)]
+    pub rate_limit: Option<RateLimit>,
+    /// Overrides `mcp.max_concurrent_requests` for this endpoint
+    #[serde(default)]
+    pub max_concurrent_requests: Option<usize>,
+    /// Disabled endpoints stay in the config but are neither registered nor routed
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(flatten)]
+    pub metadata: EndpointMetadata,
+}
+
+/// Token bucket refilled at `requests_per_second`, holding at most `burst` requests
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct RateLimit {
+    pub requests_per_second: f64,
+    #[serde(default = "default_rate_limit_burst")]
+    pub burst: u32,
+}
+
+fn default_rate_limit_burst() -> u32 {
+    1
+}
+
+/// Descriptive endpoint details returned to clients alongside tool listings
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct EndpointMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Labels for grouping endpoints, e.g. `team = "docs"`
+    #[serde(
+        default,
+        deserialize_with = "deserialize_tags",
+        skip_serializing_if = "HashMap::is_empty"
+    )]
+    pub tags: HashMap<String, String>,
+}
+
+impl EndpointMetadata {
+    /// Whether the endpoint carries every `key=value` pair; a bare `key` matches an empty value
+    pub fn has_tags<'a>(&self, filters: impl IntoIterator<Item = &'a str>) -> bool {
+        filters.into_iter().all(|filter| {
+            let (key, value) = filter.split_once('=').unwrap_or((filter, ""));
+            self.tags.get(key).is_some_and(|tag| tag == value)
+        })
+    }
+}
+
+/// Tags are a `key = "value"` table; the older list form maps each `key=value` entry the
+/// same way and keeps bare entries as keys with an empty value
+fn deserialize_tags<'de, D>(
+    deserializer: D,
+) -> std::result::Result<HashMap<String, String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Tags {
+        Map(HashMap<String, String>),
+        List(Vec<String>),
+    }
+
+    Ok(match Tags::deserialize(deserializer)? {
+        Tags::Map(tags) => tags,
+        Tags::List(tags) => tags
+            .into_iter()
+            .map(|tag| match tag.split_once('=') {
+                Some((key, value)) => (key.to_string(), value.to_string()),
+                None => (tag, String::new()),
+            })
+            .collect(),
+    })
 }
 
 impl EndpointConfig {
@@ -78,11 +494,26 @@ impl EndpointConfig {
     pub(crate) fn to_local_settings(&self) -> Result<LocalEndpointSettings> {
         match &self.endpoint_type {
             EndpointKindConfig::Local {
-                command, args, env, ..
+                command,
+                args,
+                env,
+                env_file,
+                clean_env,
+                health_command,
+                cwd,
+                restart_on_failure,
+                startup_retry,
+                ..
             } => Ok(LocalEndpointSettings {
                 command: command.clone(),
                 args: args.clone(),
                 env: env.clone(),
+                env_file: env_file.clone(),
+                clean_env: *clean_env,
+                health_command: health_command.clone(),
+                cwd: cwd.clone(),
+                restart_on_failure: *restart_on_failure,
+                startup_retry: startup_retry.clone(),
             }),
             _ => Err(ProxyError::Config(
                 "Expected local endpoint configuration".to_string(),
@@ -91,7 +522,7 @@ impl EndpointConfig {
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 #[serde(tag = "type", rename_all = "lowercase")]
 pub enum EndpointKindConfig {
     Local {
@@ -99,11 +530,48 @@ pub enum EndpointKindConfig {
         args: Vec<String>,
         #[serde(default)]
         env: HashMap<String, String>,
+        /// Dotenv-style file read at start; `env` wins over its keys. Relative paths
+        /// resolve against the config file's directory
+        #[serde(default)]
+        env_file: Option<String>,
+        /// Run the child with only `env`, instead of inheriting the proxy's environment
+        #[serde(default)]
+        clean_env: bool,
         #[serde(default = "default_auto_start")]
         auto_start: bool,
+        /// External readiness check run after start and then periodically
+        #[serde(default)]
+        health_command: Option<HealthCommand>,
+        /// Working directory for the child process
+        #[serde(default)]
+        cwd: Option<String>,
+        /// Restart the endpoint automatically when its child process dies
+        #[serde(default)]
+        restart_on_failure: bool,
+        /// Retry a failed start whose error looks transient, e.g. a Docker image pull
+        #[serde(default)]
+        startup_retry: Option<StartupRetry>,
     },
     Remote {
+        /// Upstream MCP URL; set this or `urls`
+        #[serde(default)]
         url: String,
+        /// Upstream MCP URLs tried in order, failing over to the next when one can't be reached
+        #[serde(default)]
+        urls: Vec<String>,
+        /// Fail start when the initial tool listing errors after a successful handshake
+        #[serde(default)]
+        require_tools_on_start: bool,
+        /// Handshake and list tools at startup, so a later start doesn't wait on the upstream;
+        /// the endpoint still stays stopped until started
+        #[serde(default)]
+        prewarm: bool,
+        /// Headers sent with every upstream request; values may reference `${ENV_VAR}`
+        #[serde(default)]
+        headers: HashMap<String, String>,
+        /// Seconds to open a TCP connection, overriding `mcp.connect_timeout_secs`
+        #[serde(default)]
+        connect_timeout_secs: Option<u64>,
     },
 }
 
@@ -123,10 +591,47 @@ fn default_log_format() -> String {
     "pretty".to_string()
 }
 
+fn default_http_requests() -> bool {
+    true
+}
+
+fn default_http_request_level() -> String {
+    "debug".to_string()
+}
+
 fn default_auto_start() -> bool {
     true
 }
 
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_health_interval_secs() -> u64 {
+    30
+}
+
+fn default_startup_retry_attempts() -> u32 {
+    3
+}
+
+fn default_startup_retry_delay_secs() -> u64 {
+    10
+}
+
+/// Docker's stderr while an image is still being pulled, the daemon isn't up yet, or the
+/// registry throttles or times out; a failed start carries the child's stderr, so these match
+/// the handshake timeout or early exit they cause
+fn default_startup_retry_errors() -> Vec<String> {
+    vec![
+        "Unable to find image".to_string(),
+        "Pulling from".to_string(),
+        "Cannot connect to the Docker daemon".to_string(),
+        "toomanyrequests".to_string(),
+        "TLS handshake timeout".to_string(),
+    ]
+}
+
 fn default_request_timeout_secs() -> u64 {
     30
 }
@@ -135,53 +640,269 @@ fn default_restart_delay_ms() -> u64 {
     500
 }
 
+fn default_restart_max_delay_ms() -> u64 {
+    30_000
+}
+
+fn default_restart_backoff_reset_secs() -> u64 {
+    60
+}
+
+fn default_max_list_pages() -> usize {
+    100
+}
+
+fn default_max_retries() -> u32 {
+    2
+}
+
+fn default_retry_backoff_ms() -> u64 {
+    200
+}
+
+fn default_remote_pool_max_idle() -> usize {
+    8
+}
+
+fn default_remote_pool_idle_timeout_secs() -> u64 {
+    90
+}
+
+fn default_connect_timeout_secs() -> u64 {
+    10
+}
+
+fn default_stop_timeout_secs() -> u64 {
+    10
+}
+
+fn default_sse_keep_alive_secs() -> u64 {
+    15
+}
+
+fn default_sse_retry_secs() -> u64 {
+    3
+}
+
+fn default_shutdown_grace_secs() -> u64 {
+    5
+}
+
+fn default_shutdown_drain_secs() -> u64 {
+    10
+}
+
+fn default_client_name() -> String {
+    env!("CARGO_PKG_NAME").to_string()
+}
+
+fn default_client_version() -> String {
+    env!("CARGO_PKG_VERSION").to_string()
+}
+
+fn default_max_json_depth() -> usize {
+    64
+}
+
+fn default_health_check_failure_threshold() -> u32 {
+    3
+}
+
+fn default_webhook_debounce_ms() -> u64 {
+    1000
+}
+
+fn default_webhook_events() -> Vec<LifecycleEvent> {
+    vec![
+        LifecycleEvent::Started,
+        LifecycleEvent::Stopped,
+        LifecycleEvent::Failed,
+        LifecycleEvent::Restarted,
+    ]
+}
+
 /// Local endpoint settings extracted from config
 #[derive(Debug, Clone)]
 pub(crate) struct LocalEndpointSettings {
     pub command: String,
     pub args: Vec<String>,
     pub env: HashMap<String, String>,
+    /// Dotenv-style file whose variables are applied before `env`
+    pub env_file: Option<String>,
+    /// Clear the inherited environment before applying `env`
+    pub clean_env: bool,
+    pub health_command: Option<HealthCommand>,
+    pub cwd: Option<String>,
+    pub restart_on_failure: bool,
+    pub startup_retry: Option<StartupRetry>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+/// Command whose exit status gates whether a local endpoint is healthy
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct HealthCommand {
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Seconds between periodic checks while the endpoint is running
+    #[serde(default = "default_health_interval_secs")]
+    pub interval_secs: u64,
+}
+
+/// Bounded retry of a local endpoint start that failed with a recognised error
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct StartupRetry {
+    /// Total start attempts, including the first
+    #[serde(default = "default_startup_retry_attempts")]
+    pub max_attempts: u32,
+    #[serde(default = "default_startup_retry_delay_secs")]
+    pub delay_secs: u64,
+    /// Substrings of the start error that make it worth retrying
+    #[serde(default = "default_startup_retry_errors")]
+    pub on_errors: Vec<String>,
+}
+
+impl StartupRetry {
+    pub(crate) fn should_retry(&self, error: &ProxyError) -> bool {
+        let message = error.to_string();
+        self.on_errors
+            .iter()
+            .any(|pattern| message.contains(pattern.as_str()))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
 pub struct ToolFilter {
     pub include: Option<Vec<String>>,
     pub exclude: Option<Vec<String>>,
+    /// Tools matching this pattern are included alongside the `include` list
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub include_regex: Option<ToolRegex>,
+    /// Tools matching this pattern are excluded, even when included
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exclude_regex: Option<ToolRegex>,
+    /// Names tools are exposed under, keyed by upstream name
+    /// Include/exclude rules still refer to upstream names
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub rename: HashMap<String, String>,
+    /// Descriptions replacing the upstream ones, keyed by upstream name
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub descriptions: HashMap<String, String>,
+    /// Header-conditional variants evaluated per request; the first match
+    /// replaces include/exclude, otherwise this filter applies as-is
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub when_header: Vec<HeaderToolFilter>,
+}
+
+/// Tool filter variant selected when a request header has a given value
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct HeaderToolFilter {
+    pub header: String,
+    pub value: String,
+    pub include: Option<Vec<String>>,
+    pub exclude: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub include_regex: Option<ToolRegex>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exclude_regex: Option<ToolRegex>,
+}
+
+/// Tool name pattern, compiled when the configuration is loaded
+/// Matches anywhere in the name unless anchored with `^`/`$`
+#[derive(Debug, Clone)]
+pub struct ToolRegex(regex::Regex);
+
+impl ToolRegex {
+    pub fn new(pattern: &str) -> Result<Self> {
+        regex::Regex::new(pattern).map(Self).map_err(|e| {
+            ProxyError::config(format!("Invalid tool filter regex '{}': {}", pattern, e))
+        })
+    }
+
+    pub fn is_match(&self, tool_name: &str) -> bool {
+        self.0.is_match(tool_name)
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl PartialEq for ToolRegex {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl<'de> Deserialize<'de> for ToolRegex {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        let pattern = String::deserialize(deserializer)?;
+        Self::new(&pattern).map_err(serde::de::Error::custom)
+    }
+}
+
+impl Serialize for ToolRegex {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_auth_accepts_any_configured_token() {
+        let auth = AuthConfig {
+            tokens: vec!["first".to_string(), "second".to_string()],
+            token: Some("single".to_string()),
+            ..Default::default()
+        };
+
+        for token in ["first", "second", "single"] {
+            assert!(auth.accepts(token), "{} should be accepted", token);
+        }
+        for token in ["", "firs", "first!", "SECOND"] {
+            assert!(!auth.accepts(token), "{} should be rejected", token);
+        }
+        assert!(!AuthConfig::default().accepts(""));
+    }
+
     #[test]
     fn test_tool_filter_include_only() {
-        use crate::routing::tool_filter::is_tool_allowed;
+        use crate::routing::tool_filter::resolve_tool_name;
         let filter = ToolFilter {
             include: Some(vec!["tool1".to_string(), "tool2".to_string()]),
             exclude: None,
+            ..Default::default()
         };
 
-        assert!(is_tool_allowed("tool1", Some(&filter)));
-        assert!(is_tool_allowed("tool2", Some(&filter)));
-        assert!(!is_tool_allowed("tool3", Some(&filter)));
+        assert!(resolve_tool_name("tool1", Some(&filter)).is_some());
+        assert!(resolve_tool_name("tool2", Some(&filter)).is_some());
+        assert!(resolve_tool_name("tool3", Some(&filter)).is_none());
     }
 
     #[test]
     fn test_tool_filter_exclude_only() {
-        use crate::routing::tool_filter::is_tool_allowed;
+        use crate::routing::tool_filter::resolve_tool_name;
         let filter = ToolFilter {
             include: None,
             exclude: Some(vec!["tool1".to_string()]),
+            ..Default::default()
         };
 
-        assert!(!is_tool_allowed("tool1", Some(&filter)));
-        assert!(is_tool_allowed("tool2", Some(&filter)));
-        assert!(is_tool_allowed("tool3", Some(&filter)));
+        assert!(resolve_tool_name("tool1", Some(&filter)).is_none());
+        assert!(resolve_tool_name("tool2", Some(&filter)).is_some());
+        assert!(resolve_tool_name("tool3", Some(&filter)).is_some());
     }
 
     #[test]
     fn test_tool_filter_include_and_exclude() {
-        use crate::routing::tool_filter::is_tool_allowed;
+        use crate::routing::tool_filter::resolve_tool_name;
         let filter = ToolFilter {
             include: Some(vec![
                 "tool1".to_string(),
@@ -189,24 +910,26 @@ mod tests {
                 "tool3".to_string(),
             ]),
             exclude: Some(vec!["tool2".to_string()]),
+            ..Default::default()
         };
 
-        assert!(is_tool_allowed("tool1", Some(&filter)));
-        assert!(!is_tool_allowed("tool2", Some(&filter))); // excluded even though in include
-        assert!(is_tool_allowed("tool3", Some(&filter)));
-        assert!(!is_tool_allowed("tool4", Some(&filter))); // not in include list
+        assert!(resolve_tool_name("tool1", Some(&filter)).is_some());
+        assert!(resolve_tool_name("tool2", Some(&filter)).is_none()); // excluded even though in include
+        assert!(resolve_tool_name("tool3", Some(&filter)).is_some());
+        assert!(resolve_tool_name("tool4", Some(&filter)).is_none()); // not in include list
     }
 
     #[test]
     fn test_tool_filter_no_filters() {
-        use crate::routing::tool_filter::is_tool_allowed;
+        use crate::routing::tool_filter::resolve_tool_name;
         let filter = ToolFilter {
             include: None,
             exclude: None,
+            ..Default::default()
         };
 
-        assert!(is_tool_allowed("tool1", Some(&filter)));
-        assert!(is_tool_allowed("tool2", Some(&filter)));
-        assert!(is_tool_allowed("anything", Some(&filter)));
+        assert!(resolve_tool_name("tool1", Some(&filter)).is_some());
+        assert!(resolve_tool_name("tool2", Some(&filter)).is_some());
+        assert!(resolve_tool_name("anything", Some(&filter)).is_some());
     }
 }
@@ -0,0 +1,147 @@
+use crate::config::McpConfig;
+use std::time::{Duration, Instant};
+
+/// How restart delays grow for an endpoint that keeps failing
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RestartPolicy {
+    /// Delay before the first restart
+    pub base: Duration,
+    /// Upper bound on the delay between restarts
+    pub max: Duration,
+    /// Running this long without a restart resets the delay to `base`
+    pub reset_after: Duration,
+    /// Consecutive automatic restarts allowed before giving up; unlimited when unset
+    pub max_attempts: Option<u32>,
+}
+
+impl RestartPolicy {
+    pub(crate) fn with_base(base: Duration) -> Self {
+        Self {
+            base,
+            ..Self::from(&McpConfig::default())
+        }
+    }
+}
+
+impl From<&McpConfig> for RestartPolicy {
+    fn from(config: &McpConfig) -> Self {
+        let base = Duration::from_millis(config.restart_delay_ms);
+        Self {
+            base,
+            max: Duration::from_millis(config.restart_max_delay_ms).max(base),
+            reset_after: Duration::from_secs(config.restart_backoff_reset_secs),
+            max_attempts: config.max_restart_attempts,
+        }
+    }
+}
+
+/// Per-endpoint restart backoff state
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RestartBackoff {
+    attempts: u32,
+    running_since: Option<Instant>,
+}
+
+impl RestartBackoff {
+    /// Note that the endpoint came up, starting its stability window
+    pub(crate) fn record_started(&mut self, now: Instant) {
+        self.running_since = Some(now);
+    }
+
+    /// Whether consecutive restarts have used up the policy's attempts
+    pub(crate) fn exhausted(&self, policy: &RestartPolicy, now: Instant) -> bool {
+        policy
+            .max_attempts
+            .is_some_and(|max| !self.is_stable(policy, now) && self.attempts >= max)
+    }
+
+    fn is_stable(&self, policy: &RestartPolicy, now: Instant) -> bool {
+        self.running_since
+            .is_some_and(|since| now.saturating_duration_since(since) >= policy.reset_after)
+    }
+
+    /// Delay to wait before the next restart, doubling on each consecutive restart
+    pub(crate) fn next_delay(&mut self, policy: &RestartPolicy, now: Instant) -> Duration {
+        if self.is_stable(policy, now) {
+            self.attempts = 0;
+        }
+        self.running_since = None;
+
+        let factor = 2u32.saturating_pow(self.attempts);
+        self.attempts = self.attempts.saturating_add(1);
+        policy.base.saturating_mul(factor).min(policy.max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> RestartPolicy {
+        RestartPolicy {
+            base: Duration::from_millis(100),
+            max: Duration::from_millis(1000),
+            reset_after: Duration::from_secs(60),
+            max_attempts: Some(2),
+        }
+    }
+
+    #[test]
+    fn test_delay_grows_across_consecutive_failures() {
+        let policy = policy();
+        let mut backoff = RestartBackoff::default();
+        let now = Instant::now();
+
+        let delays: Vec<_> = (0..6)
+            .map(|i| {
+                backoff.record_started(now + Duration::from_secs(i));
+                backoff
+                    .next_delay(&policy, now + Duration::from_secs(i + 1))
+                    .as_millis()
+            })
+            .collect();
+
+        assert_eq!(delays, vec![100, 200, 400, 800, 1000, 1000]);
+    }
+
+    #[test]
+    fn test_delay_resets_after_stable_period() {
+        let policy = policy();
+        let mut backoff = RestartBackoff::default();
+        let now = Instant::now();
+
+        backoff.next_delay(&policy, now);
+        backoff.next_delay(&policy, now);
+        backoff.record_started(now);
+
+        let delay = backoff.next_delay(&policy, now + Duration::from_secs(61));
+        assert_eq!(delay, policy.base);
+    }
+
+    #[test]
+    fn test_exhausted_after_max_attempts_until_stable() {
+        let policy = policy();
+        let mut backoff = RestartBackoff::default();
+        let now = Instant::now();
+
+        assert!(!backoff.exhausted(&policy, now));
+        backoff.next_delay(&policy, now);
+        assert!(!backoff.exhausted(&policy, now));
+        backoff.next_delay(&policy, now);
+        assert!(backoff.exhausted(&policy, now));
+
+        backoff.record_started(now);
+        assert!(!backoff.exhausted(&policy, now + Duration::from_secs(61)));
+    }
+
+    #[test]
+    fn test_policy_max_never_below_base() {
+        let policy = RestartPolicy::from(&McpConfig {
+            restart_delay_ms: 5000,
+            restart_max_delay_ms: 1000,
+            ..Default::default()
+        });
+
+        assert_eq!(policy.max, Duration::from_millis(5000));
+    }
+}
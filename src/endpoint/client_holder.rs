@@ -1,4 +1,4 @@
-use crate::mcp::McpClient;
+use crate::mcp::{McpClient, McpClientSettings};
 use std::sync::Arc;
 
 /// Shared MCP client lifecycle helper.
@@ -10,9 +10,9 @@ pub(crate) struct ClientHolder {
 }
 
 impl ClientHolder {
-    pub(crate) fn new(name: String) -> Self {
+    pub(crate) fn new(name: String, settings: McpClientSettings) -> Self {
         Self {
-            client: Arc::new(McpClient::new(name)),
+            client: Arc::new(McpClient::new(name, settings)),
         }
     }
 
@@ -0,0 +1,71 @@
+use crate::config::HealthCommand;
+use crate::error::{ProxyError, Result};
+use std::time::Duration;
+use tokio::process::Command;
+
+/// Upper bound on a single health command run
+const HEALTH_COMMAND_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Run a health command, succeeding only when it exits with status zero
+pub(crate) async fn run_health_command(name: &str, health: &HealthCommand) -> Result<()> {
+    let mut cmd = Command::new(&health.command);
+    cmd.args(&health.args).kill_on_drop(true);
+
+    let status = tokio::time::timeout(HEALTH_COMMAND_TIMEOUT, cmd.status())
+        .await
+        .map_err(|_| {
+            ProxyError::server_runtime_failed(
+                name,
+                format!(
+                    "health command timed out after {:?}",
+                    HEALTH_COMMAND_TIMEOUT
+                ),
+            )
+        })?
+        .map_err(|e| {
+            ProxyError::server_runtime_failed(name, format!("health command failed to run: {}", e))
+        })?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(ProxyError::server_runtime_failed(
+            name,
+            format!("health command exited with {}", status),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_health_command(command: &str) -> HealthCommand {
+        HealthCommand {
+            command: command.to_string(),
+            args: vec![],
+            interval_secs: 30,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_health_command_exit_status() {
+        assert!(
+            run_health_command("test", &create_health_command("true"))
+                .await
+                .is_ok()
+        );
+        assert!(
+            run_health_command("test", &create_health_command("false"))
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_health_command_missing_binary() {
+        let result =
+            run_health_command("test", &create_health_command("definitely-not-a-command")).await;
+        assert!(result.is_err());
+    }
+}
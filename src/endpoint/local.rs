@@ -1,14 +1,21 @@
-use crate::config::LocalEndpointSettings;
+use crate::api::mcp_sse_service::SseSettings;
+use crate::config::{LocalEndpointSettings, ToolFilter};
 use crate::endpoint::HttpTransportAdapter;
 use crate::endpoint::client_holder::ClientHolder;
+use crate::endpoint::health::run_health_command;
+use crate::endpoint::stderr::{self, StderrTail};
 use crate::error::Result;
-use crate::mcp::McpClient;
+use crate::mcp::{McpClient, McpClientSettings};
 use axum::Router;
-use rmcp::transport::TokioChildProcess;
+use std::path::Path;
+use std::process::Stdio;
 use std::sync::Arc;
-use tokio::process::Command;
+use std::time::Duration;
+use tokio::process::{Child, Command};
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
 use tokio_util::sync::CancellationToken;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
 /// Represents a local MCP endpoint running as a child process
 #[derive(Clone)]
@@ -16,18 +23,46 @@ pub(crate) struct LocalEndpoint {
     pub(crate) name: String,
     pub(crate) config: LocalEndpointSettings,
     client_holder: ClientHolder,
+    /// OS process id of the running child, if any
+    pid: Option<u32>,
+    /// The running child, kept so stop can terminate it gracefully
+    child: Arc<Mutex<Option<Child>>>,
+    shutdown_grace: Duration,
+    sse: SseSettings,
+    /// Tail of the child's stderr, kept to explain failed starts and crashes
+    stderr: StderrTail,
+    /// Tool config applied to the SSE bridge
+    tools: Option<ToolFilter>,
 }
 
 impl LocalEndpoint {
-    pub(crate) fn new(name: String, config: LocalEndpointSettings) -> Self {
-        let client_holder = ClientHolder::new(name.clone());
+    pub(crate) fn new(
+        name: String,
+        config: LocalEndpointSettings,
+        client_settings: McpClientSettings,
+    ) -> Self {
+        let shutdown_grace = client_settings.shutdown_grace;
+        let sse = client_settings.sse.clone();
+        let client_holder = ClientHolder::new(name.clone(), client_settings);
         Self {
             name,
             config,
             client_holder,
+            pid: None,
+            child: Arc::new(Mutex::new(None)),
+            shutdown_grace,
+            sse,
+            stderr: StderrTail::default(),
+            tools: None,
         }
     }
 
+    /// Rename tools and override descriptions on the SSE bridge per the endpoint's tool config
+    pub(crate) fn with_tools(mut self, tools: Option<ToolFilter>) -> Self {
+        self.tools = tools;
+        self
+    }
+
     pub(crate) async fn get_client(&self) -> Result<Arc<McpClient>> {
         let client = self.client_holder.get();
         if client.is_running().await {
@@ -42,6 +77,42 @@ impl LocalEndpoint {
 
 impl LocalEndpoint {
     pub(crate) async fn start(&mut self) -> Result<()> {
+        let Some(retry) = self.config.startup_retry.clone() else {
+            return self.start_once().await;
+        };
+
+        let mut attempt = 1;
+        loop {
+            match self.start_once().await {
+                Err(e) if attempt < retry.max_attempts && retry.should_retry(&e) => {
+                    let delay = Duration::from_secs(retry.delay_secs);
+                    if self.is_container() {
+                        warn!(
+                            "Endpoint {} did not come up ({}); the container image may still be pulling, retrying in {:?} (attempt {}/{})",
+                            self.name, e, delay, attempt, retry.max_attempts
+                        );
+                    } else {
+                        warn!(
+                            "Endpoint {} did not come up ({}), retrying in {:?} (attempt {}/{})",
+                            self.name, e, delay, attempt, retry.max_attempts
+                        );
+                    }
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Whether the endpoint is launched through a container runtime that pulls images on demand
+    fn is_container(&self) -> bool {
+        Path::new(&self.config.command)
+            .file_name()
+            .is_some_and(|name| name == "docker" || name == "podman")
+    }
+
+    async fn start_once(&mut self) -> Result<()> {
         info!("Starting local MCP endpoint: {}", self.name);
         debug!(
             "Command: {} {}",
@@ -49,26 +120,110 @@ impl LocalEndpoint {
             self.config.args.join(" ")
         );
 
-        let mut cmd = Command::new(&self.config.command);
-        cmd.args(&self.config.args).envs(&self.config.env);
-
-        let transport = TokioChildProcess::new(cmd).map_err(|e| {
-            error!("Failed to create TokioChildProcess: {}", e);
-            crate::error::ProxyError::server_start_failed(&self.name, e)
-        })?;
+        let mut cmd = self.build_command()?;
+        let mut child = cmd
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| {
+                error!("Failed to spawn child process: {}", e);
+                crate::error::ProxyError::server_start_failed(&self.name, e)
+            })?;
+        let (Some(stdout), Some(stdin)) = (child.stdout.take(), child.stdin.take()) else {
+            return Err(crate::error::ProxyError::server_start_failed(
+                &self.name,
+                "child stdio is not piped",
+            ));
+        };
+        let capture = child
+            .stderr
+            .take()
+            .map(|stderr| self.stderr.capture(&self.name, stderr));
 
         let client = self.client_holder.get();
-        client.init_with_transport(transport).await?;
+        if let Err(e) = client.init_with_transport((stdout, stdin)).await {
+            let _ = child.start_kill();
+            return Err(self.start_error(e, capture).await);
+        }
 
+        if let Some(health) = &self.config.health_command
+            && let Err(e) = run_health_command(&self.name, health).await
+        {
+            // Don't report a running endpoint whose external dependencies aren't ready
+            terminate_child(&self.name, child, self.shutdown_grace).await;
+            let _ = client.stop().await;
+            return Err(e);
+        }
+
+        self.pid = child.id();
+        *self.child.lock().await = Some(child);
         info!("Successfully started local MCP endpoint: {}", self.name);
         Ok(())
     }
 
+    /// Attach whatever the child wrote to stderr to the error of a failed start
+    async fn start_error(
+        &self,
+        error: crate::error::ProxyError,
+        capture: Option<JoinHandle<()>>,
+    ) -> crate::error::ProxyError {
+        if let Some(capture) = capture {
+            stderr::drain(capture).await;
+        }
+        match self.stderr.text() {
+            Some(tail) => crate::error::ProxyError::server_start_failed(
+                &self.name,
+                format!("{}; stderr:\n{}", error, tail),
+            ),
+            None => error,
+        }
+    }
+
+    /// Build the child process command from the endpoint settings
+    fn build_command(&self) -> Result<Command> {
+        let mut cmd = Command::new(&self.config.command);
+        if self.config.clean_env {
+            cmd.env_clear();
+        }
+        cmd.args(&self.config.args);
+        if let Some(env_file) = &self.config.env_file {
+            let vars = read_env_file(Path::new(env_file)).map_err(|e| {
+                crate::error::ProxyError::server_start_failed(
+                    &self.name,
+                    format!("cannot read env_file '{}': {}", env_file, e),
+                )
+            })?;
+            cmd.envs(vars);
+        }
+        cmd.envs(&self.config.env);
+
+        if let Some(cwd) = &self.config.cwd {
+            if !Path::new(cwd).is_dir() {
+                return Err(crate::error::ProxyError::server_start_failed(
+                    &self.name,
+                    format!("working directory '{}' does not exist", cwd),
+                ));
+            }
+            cmd.current_dir(cwd);
+        }
+
+        Ok(cmd)
+    }
+
     pub(crate) async fn stop(&mut self) -> Result<()> {
         info!("Stopping local MCP endpoint: {}", self.name);
 
         let client = self.client_holder.get();
-        client.stop().await?;
+        self.pid = None;
+        // Close the client first: killing the child under a live worker makes it
+        // report the closed transport as a failure mid-stop
+        let stopped = client.stop().await;
+        if let Some(child) = self.child.lock().await.take() {
+            terminate_child(&self.name, child, self.shutdown_grace).await;
+        }
+        stopped?;
 
         info!("Successfully stopped local MCP endpoint: {}", self.name);
         Ok(())
@@ -77,6 +232,72 @@ impl LocalEndpoint {
     pub(crate) async fn get_or_create_client(&self) -> Result<Arc<McpClient>> {
         self.get_client().await
     }
+
+    pub(crate) fn pid(&self) -> Option<u32> {
+        self.pid
+    }
+
+    /// Handle on the stderr tail that keeps filling without holding on to the endpoint
+    pub(crate) fn stderr_tail(&self) -> StderrTail {
+        self.stderr.clone()
+    }
+
+    /// Whether the child process went away after a successful start
+    pub(crate) async fn has_failed(&self) -> bool {
+        self.client_holder.get().has_failed().await
+    }
+}
+
+/// Read `KEY=value` lines from a dotenv-style file
+///
+/// Blank lines and `#` comments are skipped, an `export ` prefix is allowed, and values
+/// may be wrapped in single or double quotes.
+fn read_env_file(path: &Path) -> std::io::Result<Vec<(String, String)>> {
+    let contents = std::fs::read_to_string(path)?;
+    Ok(contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (key, value) = line.split_once('=')?;
+            let value = value.trim();
+            let value = ['"', '\'']
+                .iter()
+                .find_map(|quote| {
+                    value
+                        .strip_prefix(*quote)
+                        .and_then(|v| v.strip_suffix(*quote))
+                })
+                .unwrap_or(value);
+            Some((key.trim().to_string(), value.to_string()))
+        })
+        .collect())
+}
+
+/// Ask a child to exit with SIGTERM, killing it if it is still running after `grace`
+/// Platforms without signals get the kill straight away
+async fn terminate_child(name: &str, mut child: Child, grace: Duration) {
+    #[cfg(unix)]
+    if let Some(pid) = child.id() {
+        // SAFETY: `pid` is our own child, which can't have been reaped while we hold it
+        unsafe {
+            libc::kill(pid as libc::pid_t, libc::SIGTERM);
+        }
+        if tokio::time::timeout(grace, child.wait()).await.is_ok() {
+            return;
+        }
+        warn!(
+            "Endpoint {} did not exit within {:?} of SIGTERM, killing it",
+            name, grace
+        );
+    }
+    #[cfg(not(unix))]
+    let _ = grace;
+
+    if let Err(e) = child.kill().await {
+        warn!("Failed to kill child process of endpoint {}: {}", name, e);
+    }
 }
 
 impl HttpTransportAdapter for LocalEndpoint {
@@ -95,8 +316,13 @@ impl HttpTransportAdapter for LocalEndpoint {
         );
 
         let client = self.client_holder.get();
-        let sse_service =
-            crate::api::mcp_sse_service::create_local_sse_service(client, self.name.clone(), ct);
+        let sse_service = crate::api::mcp_sse_service::create_local_sse_service(
+            client,
+            self.name.clone(),
+            self.tools.clone(),
+            &self.sse,
+            ct,
+        );
 
         Ok(router.nest_service(&format!("/mcp/{}", path), sse_service))
     }
@@ -119,9 +345,19 @@ mod tests {
             command: "echo".to_string(),
             args: vec!["not-an-mcp-server".to_string()],
             env: HashMap::new(),
+            clean_env: false,
+            health_command: None,
+            cwd: None,
+            restart_on_failure: false,
+            startup_retry: None,
+            env_file: None,
         };
 
-        let mut endpoint = LocalEndpoint::new("test-echo".to_string(), config);
+        let mut endpoint = LocalEndpoint::new(
+            "test-echo".to_string(),
+            config,
+            McpClientSettings::default(),
+        );
 
         let start_result = endpoint.start().await;
         assert!(
@@ -130,15 +366,49 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_sse_settings_follow_mcp_config() {
+        let settings = McpClientSettings::from(&crate::config::McpConfig {
+            sse_keep_alive_secs: 5,
+            sse_retry_secs: 10,
+            ..Default::default()
+        });
+        let config = LocalEndpointSettings {
+            command: "true".to_string(),
+            args: vec![],
+            env: HashMap::new(),
+            clean_env: false,
+            health_command: None,
+            cwd: None,
+            restart_on_failure: false,
+            startup_retry: None,
+            env_file: None,
+        };
+
+        let endpoint = LocalEndpoint::new("sse".to_string(), config, settings);
+        assert_eq!(endpoint.sse.keep_alive, Some(Duration::from_secs(5)));
+        assert_eq!(endpoint.sse.retry, Some(Duration::from_secs(10)));
+    }
+
     #[tokio::test]
     async fn test_process_exit_behavior() {
         let config = LocalEndpointSettings {
             command: "true".to_string(),
             args: vec![],
             env: HashMap::new(),
+            clean_env: false,
+            health_command: None,
+            cwd: None,
+            restart_on_failure: false,
+            startup_retry: None,
+            env_file: None,
         };
 
-        let mut endpoint = LocalEndpoint::new("test-exit".to_string(), config);
+        let mut endpoint = LocalEndpoint::new(
+            "test-exit".to_string(),
+            config,
+            McpClientSettings::default(),
+        );
 
         let result = endpoint.start().await;
         assert!(
@@ -146,4 +416,238 @@ mod tests {
             "start() should fail when process exits immediately"
         );
     }
+
+    #[tokio::test]
+    async fn test_start_failure_surfaces_stderr() {
+        let config = LocalEndpointSettings {
+            command: "sh".to_string(),
+            args: vec![
+                "-c".to_string(),
+                "echo 'Traceback (most recent call last):' >&2; echo 'ImportError: no module' >&2; exit 1"
+                    .to_string(),
+            ],
+            env: HashMap::new(),
+            clean_env: false,
+            health_command: None,
+            cwd: None,
+            restart_on_failure: false,
+            startup_retry: None,
+            env_file: None,
+        };
+
+        let mut endpoint = LocalEndpoint::new(
+            "test-stderr".to_string(),
+            config,
+            McpClientSettings::default(),
+        );
+
+        let err = endpoint.start().await.unwrap_err();
+        assert!(matches!(
+            err,
+            crate::error::ProxyError::ServerStartFailed(_)
+        ));
+        assert!(err.to_string().contains("ImportError: no module"));
+        assert_eq!(
+            endpoint.stderr_tail().text().as_deref(),
+            Some("Traceback (most recent call last):\nImportError: no module")
+        );
+    }
+
+    fn create_pwd_endpoint(cwd: Option<String>) -> LocalEndpoint {
+        let config = LocalEndpointSettings {
+            command: "pwd".to_string(),
+            args: vec![],
+            env: HashMap::new(),
+            clean_env: false,
+            health_command: None,
+            cwd,
+            restart_on_failure: false,
+            startup_retry: None,
+            env_file: None,
+        };
+
+        LocalEndpoint::new("test-cwd".to_string(), config, McpClientSettings::default())
+    }
+
+    #[tokio::test]
+    async fn test_command_runs_in_cwd() {
+        let dir = tempfile::tempdir().unwrap();
+        let endpoint = create_pwd_endpoint(Some(dir.path().display().to_string()));
+
+        let output = endpoint.build_command().unwrap().output().await.unwrap();
+        let reported = std::path::PathBuf::from(String::from_utf8(output.stdout).unwrap().trim());
+        assert_eq!(
+            reported.canonicalize().unwrap(),
+            dir.path().canonicalize().unwrap()
+        );
+    }
+
+    /// Environment the endpoint's child sees, as `NAME=value` lines
+    async fn child_env(clean_env: bool) -> String {
+        // SAFETY: the variable name is unique to this test
+        unsafe { std::env::set_var("RUSTED_TOOLS_TEST_PARENT_VAR", "inherited") };
+        let config = LocalEndpointSettings {
+            command: "/usr/bin/env".to_string(),
+            args: vec![],
+            env: HashMap::from([("CONFIGURED".to_string(), "yes".to_string())]),
+            clean_env,
+            health_command: None,
+            cwd: None,
+            restart_on_failure: false,
+            startup_retry: None,
+            env_file: None,
+        };
+        let endpoint = LocalEndpoint::new("env".to_string(), config, McpClientSettings::default());
+
+        let output = endpoint.build_command().unwrap().output().await.unwrap();
+        String::from_utf8(output.stdout).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_child_inherits_parent_env_by_default() {
+        let env = child_env(false).await;
+        assert!(env.contains("RUSTED_TOOLS_TEST_PARENT_VAR=inherited"));
+        assert!(env.contains("CONFIGURED=yes"));
+    }
+
+    #[tokio::test]
+    async fn test_clean_env_passes_only_configured_env() {
+        let env = child_env(true).await;
+        assert!(!env.contains("RUSTED_TOOLS_TEST_PARENT_VAR"), "{}", env);
+        assert_eq!(env.trim(), "CONFIGURED=yes");
+    }
+
+    #[tokio::test]
+    async fn test_child_sees_env_file_vars_with_env_taking_precedence() {
+        let dir = tempfile::tempdir().unwrap();
+        let env_file = dir.path().join("secrets.env");
+        std::fs::write(
+            &env_file,
+            "# upstream credentials\nexport API_TOKEN=\"s3cret\"\n\nREGION='eu-west'\nCONFIGURED=from-file\n",
+        )
+        .unwrap();
+        let config = LocalEndpointSettings {
+            command: "/usr/bin/env".to_string(),
+            args: vec![],
+            env: HashMap::from([("CONFIGURED".to_string(), "yes".to_string())]),
+            env_file: Some(env_file.display().to_string()),
+            clean_env: true,
+            health_command: None,
+            cwd: None,
+            restart_on_failure: false,
+            startup_retry: None,
+        };
+        let endpoint = LocalEndpoint::new("env".to_string(), config, McpClientSettings::default());
+
+        let output = endpoint.build_command().unwrap().output().await.unwrap();
+        let mut env: Vec<_> = String::from_utf8(output.stdout)
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect();
+        env.sort();
+        assert_eq!(
+            env,
+            vec!["API_TOKEN=s3cret", "CONFIGURED=yes", "REGION=eu-west"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_start_fails_with_unreadable_env_file() {
+        let mut endpoint = create_pwd_endpoint(None);
+        endpoint.config.env_file = Some("/definitely/not/a/secrets.env".to_string());
+
+        let err = endpoint.start().await.unwrap_err();
+        assert!(matches!(
+            err,
+            crate::error::ProxyError::ServerStartFailed(_)
+        ));
+        assert!(
+            err.to_string()
+                .contains("cannot read env_file '/definitely/not/a/secrets.env'"),
+            "{}",
+            err
+        );
+    }
+
+    #[tokio::test]
+    async fn test_start_fails_with_missing_cwd() {
+        let mut endpoint = create_pwd_endpoint(Some("/definitely/not/a/dir".to_string()));
+
+        let err = endpoint.start().await.unwrap_err();
+        assert!(matches!(
+            err,
+            crate::error::ProxyError::ServerStartFailed(_)
+        ));
+        assert!(err.to_string().contains("/definitely/not/a/dir"));
+    }
+
+    /// Endpoint whose process records each launch in `runs` and exits without serving MCP
+    fn create_counting_endpoint(runs: &std::path::Path, on_errors: Vec<String>) -> LocalEndpoint {
+        let config = LocalEndpointSettings {
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), format!("echo run >> {}", runs.display())],
+            env: HashMap::new(),
+            clean_env: false,
+            health_command: None,
+            cwd: None,
+            restart_on_failure: false,
+            startup_retry: Some(crate::config::StartupRetry {
+                max_attempts: 3,
+                delay_secs: 0,
+                on_errors,
+            }),
+            env_file: None,
+        };
+
+        LocalEndpoint::new(
+            "test-retry".to_string(),
+            config,
+            McpClientSettings::default(),
+        )
+    }
+
+    fn count_runs(runs: &std::path::Path) -> usize {
+        std::fs::read_to_string(runs).unwrap().lines().count()
+    }
+
+    #[tokio::test]
+    async fn test_startup_retry_on_matching_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let runs = dir.path().join("runs");
+        let mut endpoint = create_counting_endpoint(&runs, vec!["initialize".to_string()]);
+
+        assert!(endpoint.start().await.is_err());
+        assert_eq!(count_runs(&runs), 3);
+    }
+
+    #[tokio::test]
+    async fn test_startup_retry_skips_other_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let runs = dir.path().join("runs");
+        let mut endpoint = create_counting_endpoint(&runs, vec!["no such error".to_string()]);
+
+        assert!(endpoint.start().await.is_err());
+        assert_eq!(count_runs(&runs), 1);
+    }
+
+    #[tokio::test]
+    async fn test_default_retry_patterns_match_docker_pull() {
+        let retry: crate::config::StartupRetry = serde_json::from_str("{}").unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let runs = dir.path().join("runs");
+
+        let mut pulling = create_counting_endpoint(&runs, vec![]);
+        pulling.config.args = vec![
+            "-c".to_string(),
+            "echo \"Unable to find image 'mcp/time:latest' locally\" >&2".to_string(),
+        ];
+        let err = pulling.start_once().await.unwrap_err();
+        assert!(retry.should_retry(&err), "unexpected error: {}", err);
+
+        // A server that exits without a Docker error isn't retried by default
+        let mut silent = create_counting_endpoint(&runs, vec![]);
+        let err = silent.start_once().await.unwrap_err();
+        assert!(!retry.should_retry(&err), "unexpected retry: {}", err);
+    }
 }
@@ -1,15 +1,43 @@
-use crate::config::{EndpointConfig, EndpointKindConfig};
-use crate::endpoint::EndpointKind;
+use crate::config::{
+    EndpointConfig, EndpointKindConfig, HealthCommand, LifecycleEvent, McpConfig, RateLimit,
+    WebhooksConfig,
+};
+use crate::endpoint::backoff::{RestartBackoff, RestartPolicy};
+use crate::endpoint::health::run_health_command;
 use crate::endpoint::local::LocalEndpoint;
-use crate::endpoint::registry::{EndpointInfo, EndpointRegistry, EndpointStatus, EndpointType};
+use crate::endpoint::rate_limit::RateLimiter;
+use crate::endpoint::registry::{
+    EndpointInfo, EndpointRegistry, EndpointStatus, EndpointType, HealthCheckResult,
+};
 use crate::endpoint::remote::RemoteEndpoint;
+use crate::endpoint::tools_cache::ToolsCache;
+use crate::endpoint::webhook::WebhookNotifier;
+use crate::endpoint::{EndpointDiagnostics, EndpointKind};
 use crate::error::{ProxyError, Result};
-use crate::mcp::McpClient;
+use crate::mcp::{McpClient, McpClientSettings, ToolDefinition};
+use crate::metrics::Metrics;
 use dashmap::DashMap;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Duration;
-use tokio::sync::RwLock;
-use tracing::{error, info, warn};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::{OwnedSemaphorePermit, RwLock, Semaphore};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info, warn};
+
+/// How often supervisors check whether a local endpoint's child process is alive
+const SUPERVISOR_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Endpoints to bring up once registered
+#[derive(Debug, Default)]
+pub(crate) struct InitialStart {
+    /// Local endpoints with `auto_start`
+    auto_start: Vec<String>,
+    /// Remote endpoints with `prewarm`
+    prewarm: Vec<String>,
+}
 
 /// Manager for all MCP endpoint instances (local and remote)
 /// Uses polymorphic storage via EndpointKind enum for unified handling
@@ -17,7 +45,34 @@ use tracing::{error, info, warn};
 pub struct EndpointManager {
     registry: EndpointRegistry,
     endpoints: Arc<DashMap<String, Arc<RwLock<EndpointKind>>>>,
-    restart_delay: Duration,
+    restart_policy: RestartPolicy,
+    restart_backoffs: Arc<DashMap<String, RestartBackoff>>,
+    client_settings: McpClientSettings,
+    webhook: Option<WebhookNotifier>,
+    metrics: Arc<Metrics>,
+    max_endpoints: Option<usize>,
+    /// Held while checking `max_endpoints` and registering, so concurrent adds can't exceed it
+    registering: Arc<std::sync::Mutex<()>>,
+    health_monitors: Arc<DashMap<String, JoinHandle<()>>>,
+    supervisors: Arc<DashMap<String, JoinHandle<()>>>,
+    probe_interval: Option<Duration>,
+    probe_failure_threshold: u32,
+    probe_timeout: Duration,
+    probes: Arc<DashMap<String, JoinHandle<()>>>,
+    default_rate_limit: Option<RateLimit>,
+    rate_limiters: Arc<DashMap<String, Arc<RateLimiter>>>,
+    default_max_concurrent_requests: Option<usize>,
+    request_slots: Arc<DashMap<String, Arc<Semaphore>>>,
+    sessions: CancellationToken,
+    tools_cache: Arc<ToolsCache>,
+    /// Set once the configured endpoints have had their first start attempt
+    ready: Arc<AtomicBool>,
+    /// Settings each endpoint was registered with, to tell whether a reload changes them
+    configs: Arc<DashMap<String, EndpointConfig>>,
+    /// Diagnostics of each endpoint, so status reads never wait on the endpoint lock
+    diagnostics: Arc<DashMap<String, EndpointDiagnostics>>,
+    /// Config file endpoints are reloaded from
+    config_path: Option<PathBuf>,
 }
 
 impl EndpointManager {
@@ -29,70 +84,379 @@ impl EndpointManager {
         Self {
             registry: EndpointRegistry::new(),
             endpoints: Arc::new(DashMap::new()),
-            restart_delay,
+            restart_policy: RestartPolicy::with_base(restart_delay),
+            restart_backoffs: Arc::new(DashMap::new()),
+            client_settings: McpClientSettings::default(),
+            webhook: None,
+            metrics: Arc::new(Metrics::new()),
+            max_endpoints: None,
+            registering: Arc::default(),
+            health_monitors: Arc::new(DashMap::new()),
+            supervisors: Arc::new(DashMap::new()),
+            probe_interval: None,
+            probe_failure_threshold: 1,
+            probe_timeout: Duration::from_secs(30),
+            probes: Arc::new(DashMap::new()),
+            default_rate_limit: None,
+            rate_limiters: Arc::new(DashMap::new()),
+            default_max_concurrent_requests: None,
+            request_slots: Arc::new(DashMap::new()),
+            sessions: CancellationToken::new(),
+            tools_cache: Arc::new(ToolsCache::new(Duration::ZERO)),
+            ready: Arc::new(AtomicBool::new(false)),
+            configs: Arc::new(DashMap::new()),
+            diagnostics: Arc::new(DashMap::new()),
+            config_path: None,
+        }
+    }
+
+    /// Create a manager using the global MCP settings from configuration
+    pub fn from_config(config: &McpConfig) -> Self {
+        Self {
+            client_settings: McpClientSettings::from(config),
+            max_endpoints: config.max_endpoints,
+            registry: EndpointRegistry::new().with_path_matching(config.path_matching),
+            restart_policy: RestartPolicy::from(config),
+            probe_interval: (config.health_check_interval_secs > 0)
+                .then(|| Duration::from_secs(config.health_check_interval_secs)),
+            probe_failure_threshold: config.health_check_failure_threshold.max(1),
+            probe_timeout: Duration::from_secs(config.request_timeout_secs),
+            default_rate_limit: config.rate_limit,
+            default_max_concurrent_requests: config.max_concurrent_requests,
+            tools_cache: Arc::new(ToolsCache::new(Duration::from_secs(
+                config.tools_cache_secs,
+            ))),
+            ..Self::new()
+        }
+    }
+
+    /// Report endpoint lifecycle events to the configured webhook
+    pub fn with_webhooks(mut self, config: &WebhooksConfig) -> Self {
+        self.webhook = WebhookNotifier::from_config(config);
+        self
+    }
+
+    /// Reload endpoint settings from this file
+    pub fn with_config_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config_path = Some(path.into());
+        self
+    }
+
+    pub(crate) fn config_path(&self) -> Option<&Path> {
+        self.config_path.as_deref()
+    }
+
+    /// The settings an endpoint is currently registered with
+    pub(crate) fn endpoint_config(&self, name: &str) -> Option<EndpointConfig> {
+        self.configs.get(name).map(|config| config.clone())
+    }
+
+    /// Metrics shared with the HTTP handlers
+    pub fn metrics(&self) -> Arc<Metrics> {
+        self.metrics.clone()
+    }
+
+    fn emit(&self, name: &str, event: LifecycleEvent, error: Option<&ProxyError>) {
+        self.metrics.record_event(name, event);
+        if let Some(webhook) = &self.webhook {
+            webhook.notify(name, event, error.map(|e| e.to_string()));
+        }
+    }
+
+    /// Mark an endpoint failed, remembering the error and reporting it
+    fn mark_failed(&self, name: &str, error: &ProxyError) -> Result<()> {
+        self.tools_cache.invalidate(name);
+        let result = self.registry.set_failed(name, error.to_string());
+        self.emit(name, LifecycleEvent::Failed, Some(error));
+        result
+    }
+
+    /// Mark a running endpoint failed and release its runtime, so a failed health
+    /// check doesn't leave a live client behind a `Failed` status
+    async fn fail_unhealthy(&self, name: &str, error: &ProxyError) {
+        let _ = self.mark_failed(name, error);
+        if let Ok(endpoint) = self.get_endpoint(name)
+            && let Err(e) = endpoint.write().await.stop().await
+        {
+            warn!("Failed to stop unhealthy endpoint {}: {}", name, e);
         }
     }
 
     /// Initialize endpoints from configuration
     pub async fn init_from_config(&self, configs: Vec<EndpointConfig>) -> Result<()> {
+        let initial = self.register_from_config(configs)?;
+        self.start_initial(initial).await;
+        Ok(())
+    }
+
+    /// Register the enabled endpoints from configuration without starting any,
+    /// returning those to auto-start or prewarm
+    pub(crate) fn register_from_config(
+        &self,
+        configs: Vec<EndpointConfig>,
+    ) -> Result<InitialStart> {
+        let (configs, disabled): (Vec<_>, Vec<_>) =
+            configs.into_iter().partition(|config| config.enabled);
+        for config in &disabled {
+            info!("Skipping disabled endpoint: {}", config.name);
+        }
+
         info!(
             "Initializing {} MCP endpoints from configuration",
             configs.len()
         );
 
+        let _registering = self.lock_registering();
+        if let Some(max) = self.max_endpoints {
+            let total = self.endpoints.len() + configs.len();
+            if total > max {
+                return Err(ProxyError::config(format!(
+                    "Configuration defines {} endpoints, exceeding max_endpoints = {}",
+                    total, max
+                )));
+            }
+        }
+        self.register_endpoints(configs)
+    }
+
+    fn lock_registering(&self) -> std::sync::MutexGuard<'_, ()> {
+        self.registering
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Register enabled endpoints; callers hold the `registering` lock
+    fn register_endpoints(&self, configs: Vec<EndpointConfig>) -> Result<InitialStart> {
+        let mut initial = InitialStart::default();
         for config in configs {
+            let name = config.name.clone();
+            let rate_limit = config.rate_limit.or(self.default_rate_limit);
+            let max_concurrent = config
+                .max_concurrent_requests
+                .or(self.default_max_concurrent_requests);
             let endpoint_type = config.endpoint_type.clone();
+            let registered = config.clone();
             match endpoint_type {
-                EndpointKindConfig::Local { auto_start, .. } => {
-                    self.init_local_endpoint(config, auto_start).await?;
+                EndpointKindConfig::Local {
+                    auto_start: start, ..
+                } => {
+                    self.init_local_endpoint(config)?;
+                    if start {
+                        initial.auto_start.push(name.clone());
+                    }
+                }
+                EndpointKindConfig::Remote { prewarm, .. } => {
+                    self.init_remote_endpoint(config)?;
+                    if prewarm {
+                        initial.prewarm.push(name.clone());
+                    }
                 }
-                EndpointKindConfig::Remote { .. } => {
-                    self.init_remote_endpoint(config).await?;
+            }
+            if let Some(limit) = rate_limit {
+                self.rate_limiters
+                    .insert(name.clone(), Arc::new(RateLimiter::new(limit)));
+            }
+            if let Some(max) = max_concurrent {
+                self.request_slots
+                    .insert(name.clone(), Arc::new(Semaphore::new(max)));
+            }
+            self.configs.insert(name, registered);
+        }
+
+        Ok(initial)
+    }
+
+    /// Make the first start attempt of each auto-start endpoint and prewarm remote ones,
+    /// then mark the manager ready
+    /// Failures are logged and recorded on the endpoint rather than returned
+    pub(crate) async fn start_initial(&self, initial: InitialStart) {
+        self.bring_up(initial).await;
+        self.ready.store(true, Ordering::Release);
+    }
+
+    async fn bring_up(&self, initial: InitialStart) {
+        for name in initial.auto_start {
+            info!("Auto-starting local endpoint: {}", name);
+            if let Err(e) = self.start_endpoint(&name).await {
+                error!("Failed to auto-start endpoint {}: {}", name, e);
+            }
+        }
+        for name in initial.prewarm {
+            self.prewarm_endpoint(&name).await;
+        }
+    }
+
+    /// Connect to a remote endpoint ahead of its first start, without changing its status
+    async fn prewarm_endpoint(&self, name: &str) {
+        let Some(endpoint_lock) = self.endpoints.get(name).map(|e| e.clone()) else {
+            return;
+        };
+        // The clone shares the client, so a start doesn't wait for the handshake to finish here
+        let remote = match &*endpoint_lock.read().await {
+            EndpointKind::Remote(remote) => remote.clone(),
+            EndpointKind::Local(_) => return,
+        };
+        info!("Prewarming remote endpoint: {}", name);
+        match remote.prewarm().await {
+            // Starting keeps the cached listing; only a stop or failure drops it
+            Ok(tools) => self.cache_tools(name, &tools),
+            Err(e) => warn!("Failed to prewarm remote endpoint {}: {}", name, e),
+        }
+    }
+
+    /// Whether initialization has finished and every auto-start endpoint has been tried
+    pub fn is_ready(&self) -> bool {
+        self.ready.load(Ordering::Acquire)
+    }
+
+    /// Register an endpoint while the proxy is running, starting it if `auto_start` is set
+    pub(crate) async fn add_endpoint(&self, config: EndpointConfig) -> Result<()> {
+        let initial = self.register_endpoint(config)?;
+        self.bring_up(initial).await;
+        Ok(())
+    }
+
+    /// Validate and register an endpoint while the proxy is running, leaving it stopped
+    pub(crate) fn register_endpoint(&self, config: EndpointConfig) -> Result<InitialStart> {
+        crate::config::validate_endpoint(&config, &self.registry.path_matching())?;
+        if !config.enabled {
+            return Err(ProxyError::InvalidRequest(format!(
+                "Cannot add endpoint '{}': it is disabled",
+                config.name
+            )));
+        }
+
+        let name = config.name.clone();
+        let result = {
+            let _registering = self.lock_registering();
+            match self.max_endpoints {
+                Some(max) if self.endpoints.len() >= max => {
+                    return Err(ProxyError::endpoint_limit_reached(&name, max));
                 }
+                _ => self.register_endpoints(vec![config]),
             }
+        };
+        // A config rejected after registration must not leave a half-initialized entry
+        if result.is_err() && !self.endpoints.contains_key(&name) {
+            let _ = self.registry.unregister(&name);
+        }
+        result
+    }
+
+    /// Admit one request to an endpoint under its rate limit, if it has one
+    pub(crate) fn check_rate_limit(&self, name: &str) -> Result<()> {
+        let Some(limiter) = self.rate_limiters.get(name).map(|l| l.clone()) else {
+            return Ok(());
+        };
+        limiter
+            .try_acquire()
+            .map_err(|retry_after| ProxyError::RateLimited {
+                endpoint: name.to_string(),
+                retry_after,
+            })
+    }
+
+    /// Wait for one of an endpoint's request slots, if its concurrency is bounded
+    /// The returned permit frees the slot when dropped
+    pub(crate) async fn acquire_request_slot(
+        &self,
+        name: &str,
+        timeout: Duration,
+    ) -> Result<Option<OwnedSemaphorePermit>> {
+        let Some(slots) = self.request_slots.get(name).map(|s| s.clone()) else {
+            return Ok(None);
+        };
+        match tokio::time::timeout(timeout, slots.acquire_owned()).await {
+            Ok(Ok(permit)) => Ok(Some(permit)),
+            Ok(Err(_)) => Err(ProxyError::server_not_found(name)),
+            Err(_) => Err(ProxyError::mcp_unavailable(format!(
+                "{}: no request slot freed up within {:?}",
+                name, timeout
+            ))),
+        }
+    }
+
+    /// The endpoint's unfiltered tool listing, if a recent one is cached
+    pub(crate) fn cached_tools(&self, name: &str) -> Option<Vec<ToolDefinition>> {
+        self.tools_cache.get(name)
+    }
+
+    /// Remember an endpoint's unfiltered tool listing for `tools_cache_secs`
+    pub(crate) fn cache_tools(&self, name: &str, tools: &[ToolDefinition]) {
+        self.tools_cache.insert(name, tools);
+    }
+
+    /// Stop an endpoint and forget it entirely
+    pub(crate) async fn remove_endpoint(&self, name: &str) -> Result<()> {
+        let info = self.registry.get(name)?;
+        if info.status != EndpointStatus::Stopped
+            && let Err(e) = self.stop_endpoint(name).await
+        {
+            // Dropping the endpoint below still kills a local child process
+            warn!("Error stopping endpoint {} before removal: {}", name, e);
         }
 
+        for tasks in [&self.supervisors, &self.health_monitors, &self.probes] {
+            if let Some((_, task)) = tasks.remove(name) {
+                task.abort();
+            }
+        }
+        self.restart_backoffs.remove(name);
+        self.rate_limiters.remove(name);
+        self.request_slots.remove(name);
+        self.tools_cache.invalidate(name);
+        if let Some(webhook) = &self.webhook {
+            webhook.forget(name);
+        }
+        self.configs.remove(name);
+        self.diagnostics.remove(name);
+        self.endpoints.remove(name);
+        self.registry.unregister(name)?;
+
+        info!("Removed endpoint: {}", name);
         Ok(())
     }
 
-    async fn init_local_endpoint(&self, config: EndpointConfig, auto_start: bool) -> Result<()> {
+    fn init_local_endpoint(&self, config: EndpointConfig) -> Result<()> {
         let name = config.name.clone();
 
         self.registry.register(
             name.clone(),
             name.clone(),
+            config.aliases.clone(),
             EndpointType::Local,
             config.tools.clone(),
+            config.metadata.clone(),
         )?;
 
         let local_config = config.to_local_settings()?;
-        let endpoint = LocalEndpoint::new(name.clone(), local_config);
-        let endpoint_kind = EndpointKind::Local(endpoint);
+        let endpoint = LocalEndpoint::new(name.clone(), local_config, self.client_settings.clone())
+            .with_tools(config.tools.clone());
+        let endpoint_kind = EndpointKind::Local(Box::new(endpoint));
+        self.diagnostics
+            .insert(name.clone(), endpoint_kind.diagnostics());
         self.endpoints
-            .insert(name.clone(), Arc::new(RwLock::new(endpoint_kind)));
-
-        if auto_start {
-            info!("Auto-starting local endpoint: {}", name);
-            if let Err(e) = self.start_endpoint(&name).await {
-                error!("Failed to auto-start endpoint {}: {}", name, e);
-            }
-        }
+            .insert(name, Arc::new(RwLock::new(endpoint_kind)));
 
         Ok(())
     }
 
-    async fn init_remote_endpoint(&self, config: EndpointConfig) -> Result<()> {
+    fn init_remote_endpoint(&self, config: EndpointConfig) -> Result<()> {
         let name = config.name.clone();
 
         self.registry.register(
             name.clone(),
             name.clone(),
+            config.aliases.clone(),
             EndpointType::Remote,
             config.tools.clone(),
+            config.metadata.clone(),
         )?;
 
-        let remote_endpoint = RemoteEndpoint::from_config(&config)?;
+        let remote_endpoint = RemoteEndpoint::from_config(&config, self.client_settings.clone())?;
         let endpoint_kind = EndpointKind::Remote(remote_endpoint);
+        self.diagnostics
+            .insert(name.clone(), endpoint_kind.diagnostics());
         self.endpoints
             .insert(name.clone(), Arc::new(RwLock::new(endpoint_kind)));
 
@@ -111,27 +475,53 @@ impl EndpointManager {
 
         self.registry.set_status(name, EndpointStatus::Starting)?;
 
-        let endpoint_lock = self
-            .endpoints
-            .get(name)
-            .ok_or_else(|| ProxyError::server_not_found(name.to_string()))?;
+        // Clone the lock out so writers to the map aren't blocked for the whole start
+        let endpoint_lock = self.get_endpoint(name)?;
 
         let mut endpoint = endpoint_lock.write().await;
+        // A concurrent start may have finished while this one waited for the lock
+        if self.registry.get(name)?.status == EndpointStatus::Running {
+            return Err(ProxyError::server_already_running(name.to_string()));
+        }
 
         match endpoint.start().await {
             Ok(()) => {
                 self.registry.set_status(name, EndpointStatus::Running)?;
+                self.registry.set_pid(name, endpoint.pid())?;
+                self.registry.set_stopped_by_operator(name, false)?;
+                self.restart_backoffs
+                    .entry(name.to_string())
+                    .or_default()
+                    .record_started(Instant::now());
                 info!("Successfully started endpoint: {}", name);
+                self.emit(name, LifecycleEvent::Started, None);
+                if let Some(interval) = self.probe_interval {
+                    self.spawn_probe(name, interval);
+                }
+                if let EndpointKind::Local(local) = &*endpoint {
+                    if let Some(health) = &local.config.health_command {
+                        self.spawn_health_monitor(name, health.clone());
+                    }
+                    if local.config.restart_on_failure {
+                        self.spawn_supervisor(name);
+                    }
+                }
                 Ok(())
             }
             Err(e) => {
-                self.registry.set_status(name, EndpointStatus::Failed)?;
                 error!("Failed to start endpoint {}: {}", name, e);
+                self.mark_failed(name, &e)?;
                 Err(e)
             }
         }
     }
 
+    /// Stop an endpoint on an operator's request, keeping on-demand starts from undoing it
+    pub(crate) async fn stop_endpoint_by_operator(&self, name: &str) -> Result<()> {
+        self.registry.set_stopped_by_operator(name, true)?;
+        self.stop_endpoint(name).await
+    }
+
     /// Stop an MCP endpoint (works for both local and remote)
     pub(crate) async fn stop_endpoint(&self, name: &str) -> Result<()> {
         let info = self.registry.get(name)?;
@@ -141,11 +531,15 @@ impl EndpointManager {
         }
 
         self.registry.set_status(name, EndpointStatus::Stopping)?;
+        self.tools_cache.invalidate(name);
+        if let Some((_, monitor)) = self.health_monitors.remove(name) {
+            monitor.abort();
+        }
+        if let Some((_, probe)) = self.probes.remove(name) {
+            probe.abort();
+        }
 
-        let endpoint_lock = self
-            .endpoints
-            .get(name)
-            .ok_or_else(|| ProxyError::server_not_found(name.to_string()))?;
+        let endpoint_lock = self.get_endpoint(name)?;
 
         let mut endpoint = endpoint_lock.write().await;
 
@@ -153,30 +547,256 @@ impl EndpointManager {
             Ok(()) => {
                 self.registry.set_status(name, EndpointStatus::Stopped)?;
                 info!("Successfully stopped endpoint: {}", name);
+                self.emit(name, LifecycleEvent::Stopped, None);
                 Ok(())
             }
             Err(e) => {
-                if let Err(status_err) = self.registry.set_status(name, EndpointStatus::Failed) {
+                error!("Failed to stop endpoint {}: {}", name, e);
+                if let Err(status_err) = self.mark_failed(name, &e) {
                     warn!(
                         "Failed to set endpoint status to failed for {}: {}",
                         name, status_err
                     );
                 }
-                error!("Failed to stop endpoint {}: {}", name, e);
                 Err(e)
             }
         }
     }
 
-    /// Restart an MCP endpoint
+    /// Periodically run an endpoint's health command, failing and stopping it on a nonzero exit
+    fn spawn_health_monitor(&self, name: &str, health: HealthCommand) {
+        let manager = self.clone();
+        let name_owned = name.to_string();
+        // Validation rejects 0, but a zero sleep would spawn the command in a tight loop
+        let interval = Duration::from_secs(health.interval_secs.max(1));
+
+        let monitor = tokio::spawn(async move {
+            let name = name_owned;
+            loop {
+                tokio::time::sleep(interval).await;
+                let running = manager
+                    .registry
+                    .get(&name)
+                    .is_ok_and(|info| info.status == EndpointStatus::Running);
+                if !running {
+                    break;
+                }
+
+                if let Err(e) = run_health_command(&name, &health).await {
+                    warn!("Endpoint {} failed its health check: {}", name, e);
+                    manager.fail_unhealthy(&name, &e).await;
+                    break;
+                }
+            }
+        });
+
+        if let Some(previous) = self.health_monitors.insert(name.to_string(), monitor) {
+            previous.abort();
+        }
+    }
+
+    /// Periodically list an endpoint's tools, failing and stopping it after repeated errors
+    fn spawn_probe(&self, name: &str, interval: Duration) {
+        let manager = self.clone();
+        let name_owned = name.to_string();
+
+        let probe = tokio::spawn(async move {
+            let name = name_owned;
+            let mut failures = 0;
+            loop {
+                tokio::time::sleep(interval).await;
+                let running = manager
+                    .registry
+                    .get(&name)
+                    .is_ok_and(|info| info.status == EndpointStatus::Running);
+                if !running {
+                    break;
+                }
+
+                // Requests are served one at a time, so a probe queued behind slow calls
+                // can time out on an upstream that is busy rather than unhealthy
+                let (result, busy) = match manager.get_client(&name).await {
+                    Ok(client) => {
+                        let busy = client.pending_requests().await > 0;
+                        let result =
+                            tokio::time::timeout(manager.probe_timeout, client.list_tools())
+                                .await
+                                .unwrap_or_else(|_| {
+                                    Err(ProxyError::mcp_timeout(manager.probe_timeout))
+                                })
+                                .map(|_| ());
+                        (result, busy)
+                    }
+                    Err(e) => (Err(e), false),
+                };
+                if busy && matches!(result, Err(ProxyError::McpTimeout(_))) {
+                    debug!(
+                        "Health probe of endpoint {} timed out behind requests in flight; not counted",
+                        name
+                    );
+                    continue;
+                }
+                let _ = manager.registry.record_health_check(
+                    &name,
+                    HealthCheckResult {
+                        checked_at: SystemTime::now(),
+                        error: result.as_ref().err().map(|e| e.to_string()),
+                    },
+                );
+
+                match result {
+                    Ok(()) => failures = 0,
+                    Err(e) => {
+                        failures += 1;
+                        warn!(
+                            "Health probe of endpoint {} failed ({}/{}): {}",
+                            name, failures, manager.probe_failure_threshold, e
+                        );
+                        if failures >= manager.probe_failure_threshold {
+                            manager.fail_unhealthy(&name, &e).await;
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+
+        if let Some(previous) = self.probes.insert(name.to_string(), probe) {
+            previous.abort();
+        }
+    }
+
+    /// Watch a local endpoint's runtime and restart it when the child process dies
+    /// The supervisor survives its own restarts and exits once the endpoint is stopped
+    fn spawn_supervisor(&self, name: &str) {
+        if self
+            .supervisors
+            .get(name)
+            .is_some_and(|supervisor| !supervisor.is_finished())
+        {
+            return;
+        }
+
+        let manager = self.clone();
+        let name_owned = name.to_string();
+
+        let supervisor = tokio::spawn(async move {
+            let name = name_owned;
+            // Set while the supervisor's own restart attempts keep failing
+            let mut retrying = false;
+            loop {
+                tokio::time::sleep(SUPERVISOR_INTERVAL).await;
+                // Whether a dead runtime has to be released before starting again
+                let release = match manager.registry.get(&name).map(|info| info.status) {
+                    Ok(EndpointStatus::Running) => true,
+                    Ok(EndpointStatus::Failed) if retrying => false,
+                    Ok(EndpointStatus::Starting | EndpointStatus::Stopping) => continue,
+                    _ => break,
+                };
+
+                if release {
+                    let Ok(endpoint) = manager.get_endpoint(&name) else {
+                        break;
+                    };
+                    let failed = match &*endpoint.read().await {
+                        EndpointKind::Local(local) => local.has_failed().await,
+                        EndpointKind::Remote(_) => false,
+                    };
+                    if !failed {
+                        continue;
+                    }
+
+                    let e = ProxyError::server_runtime_failed(&name, "child process exited");
+                    warn!("Endpoint {} died: {}", name, e);
+                    let _ = manager.mark_failed(&name, &e);
+                }
+
+                if manager.restarts_exhausted(&name) {
+                    let attempts = manager.restart_policy.max_attempts.unwrap_or_default();
+                    let reason = format!("gave up after {} restart attempts", attempts);
+                    error!("Endpoint {} {}", name, reason);
+                    let _ = manager.registry.set_status_with_reason(
+                        &name,
+                        EndpointStatus::Failed,
+                        Some(reason),
+                    );
+                    break;
+                }
+
+                tokio::time::sleep(manager.next_restart_delay(&name)).await;
+                let restarted = if release {
+                    // Stopping only releases the dead runtime before starting afresh
+                    match manager.stop_endpoint(&name).await {
+                        Ok(()) => manager.start_endpoint(&name).await,
+                        Err(e) => Err(e),
+                    }
+                } else {
+                    manager.start_endpoint(&name).await
+                };
+
+                match restarted {
+                    Ok(()) => {
+                        retrying = false;
+                        manager.emit(&name, LifecycleEvent::Restarted, None);
+                    }
+                    Err(e) => {
+                        error!("Failed to restart endpoint {}: {}", name, e);
+                        retrying = true;
+                    }
+                }
+            }
+        });
+
+        self.supervisors.insert(name.to_string(), supervisor);
+    }
+
+    /// Restart an MCP endpoint on request
+    /// Backoff is for the supervisor; an operator's restart waits the base delay and starts
+    /// the endpoint's restart attempts over
     pub(crate) async fn restart_endpoint(&self, name: &str) -> Result<()> {
         info!("Restarting endpoint: {}", name);
         self.stop_endpoint(name).await?;
-        tokio::time::sleep(self.restart_delay).await;
+        self.restart_backoffs.remove(name);
+        tokio::time::sleep(self.restart_policy.base).await;
         self.start_endpoint(name).await?;
+        self.emit(name, LifecycleEvent::Restarted, None);
         Ok(())
     }
 
+    /// Whether an endpoint has used up its consecutive automatic restarts
+    fn restarts_exhausted(&self, name: &str) -> bool {
+        self.restart_backoffs
+            .get(name)
+            .is_some_and(|backoff| backoff.exhausted(&self.restart_policy, Instant::now()))
+    }
+
+    /// Delay before restarting an endpoint, growing while it keeps failing
+    fn next_restart_delay(&self, name: &str) -> Duration {
+        let delay = self
+            .restart_backoffs
+            .entry(name.to_string())
+            .or_default()
+            .next_delay(&self.restart_policy, Instant::now());
+        info!("Restarting endpoint {} in {:?}", name, delay);
+        delay
+    }
+
+    /// Handshake with a remote URL and count its tools without registering it
+    pub(crate) async fn test_remote(
+        &self,
+        url: &str,
+        headers: &HashMap<String, String>,
+    ) -> Result<usize> {
+        let client = McpClient::new(format!("test-remote {}", url), self.client_settings.clone());
+        client.init_with_http_headers(url, headers).await?;
+
+        let result = client.list_tools().await;
+        if let Err(e) = client.stop().await {
+            warn!("Failed to close test connection to {}: {}", url, e);
+        }
+        result.map(|tools| tools.len())
+    }
+
     /// Get endpoint info by name
     pub(crate) fn get_endpoint_info(&self, name: &str) -> Result<EndpointInfo> {
         self.registry.get(name)
@@ -191,6 +811,16 @@ impl EndpointManager {
         self.registry.list()
     }
 
+    /// Snapshot of every registered endpoint, for library consumers
+    pub fn list(&self) -> Vec<EndpointInfo> {
+        self.list_endpoints()
+    }
+
+    /// Current status of the endpoint called `name`
+    pub fn status(&self, name: &str) -> Result<EndpointStatus> {
+        self.registry.get(name).map(|info| info.status)
+    }
+
     /// Get an endpoint instance by name (polymorphic access)
     pub(crate) fn get_endpoint(&self, name: &str) -> Result<Arc<RwLock<EndpointKind>>> {
         self.endpoints
@@ -199,6 +829,14 @@ impl EndpointManager {
             .ok_or_else(|| ProxyError::server_not_found(name.to_string()))
     }
 
+    /// Live diagnostics of an endpoint, without waiting on its lock
+    pub(crate) fn get_endpoint_diagnostics(&self, name: &str) -> Result<EndpointDiagnostics> {
+        self.diagnostics
+            .get(name)
+            .map(|entry| entry.value().clone())
+            .ok_or_else(|| ProxyError::server_not_found(name.to_string()))
+    }
+
     /// Get an MCP client for any endpoint (works for both local and remote)
     pub(crate) async fn get_client(&self, name: &str) -> Result<Arc<McpClient>> {
         let info = self.registry.get(name)?;
@@ -211,17 +849,24 @@ impl EndpointManager {
         endpoint_guard.get_or_create_client().await
     }
 
+    /// Token for SSE sessions served by the proxy; cancelled on shutdown
+    pub(crate) fn session_token(&self) -> CancellationToken {
+        self.sessions.child_token()
+    }
+
     /// Shutdown all endpoints
-    pub(crate) async fn shutdown(&self) -> Result<()> {
+    pub async fn shutdown(&self) -> Result<()> {
         info!("Shutting down all endpoints");
 
-        for entry in self.endpoints.iter() {
-            let name = entry.key();
+        // Close open SSE sessions before their upstreams go away
+        self.sessions.cancel();
 
-            // Only stop local endpoints; remote endpoints are external services
-            // that don't need lifecycle management
+        // Collect names first; stopping awaits and must not hold the map
+        let names: Vec<String> = self.endpoints.iter().map(|e| e.key().clone()).collect();
+        for name in &names {
+            // Remote endpoints are stopped too, closing their upstream sessions
             if let Ok(info) = self.registry.get(name)
-                && info.endpoint_type == EndpointType::Local
+                && info.status != EndpointStatus::Stopped
                 && let Err(e) = self.stop_endpoint(name).await
             {
                 warn!("Error stopping endpoint {} during shutdown: {}", name, e);
@@ -250,13 +895,24 @@ mod tests {
 
         let config = EndpointConfig {
             name: "test-server".to_string(),
+            aliases: Vec::new(),
             endpoint_type: EndpointKindConfig::Local {
                 command: "echo".to_string(),
                 args: vec!["hello".to_string()],
                 env: HashMap::new(),
+                clean_env: false,
                 auto_start: false,
+                health_command: None,
+                cwd: None,
+                restart_on_failure: false,
+                startup_retry: None,
+                env_file: None,
             },
             tools: None,
+            rate_limit: None,
+            max_concurrent_requests: None,
+            enabled: true,
+            metadata: Default::default(),
         };
 
         manager.init_from_config(vec![config]).await.unwrap();
@@ -265,19 +921,151 @@ mod tests {
         assert_eq!(info.status, EndpointStatus::Stopped);
     }
 
+    #[tokio::test]
+    async fn test_init_keeps_endpoint_metadata() {
+        let manager = EndpointManager::new();
+
+        let config = EndpointConfig {
+            name: "docs".to_string(),
+            aliases: Vec::new(),
+            endpoint_type: EndpointKindConfig::Remote {
+                url: "http://localhost:8080".to_string(),
+                urls: Vec::new(),
+                require_tools_on_start: false,
+                headers: HashMap::new(),
+                connect_timeout_secs: None,
+                prewarm: false,
+            },
+            tools: None,
+            rate_limit: None,
+            max_concurrent_requests: None,
+            enabled: true,
+            metadata: crate::config::EndpointMetadata {
+                display_name: Some("Docs".to_string()),
+                description: None,
+                tags: HashMap::from([("team".to_string(), "search".to_string())]),
+            },
+        };
+
+        manager.init_from_config(vec![config]).await.unwrap();
+
+        let info = manager.get_endpoint_info_by_path("docs").unwrap();
+        assert_eq!(info.metadata.display_name.as_deref(), Some("Docs"));
+        assert_eq!(info.metadata.tags["team"], "search");
+    }
+
+    #[tokio::test]
+    async fn test_init_rejects_too_many_endpoints() {
+        let manager = EndpointManager::from_config(&McpConfig {
+            max_endpoints: Some(1),
+            ..Default::default()
+        });
+
+        let configs = ["first", "second"]
+            .into_iter()
+            .map(|name| EndpointConfig {
+                name: name.to_string(),
+                aliases: Vec::new(),
+                endpoint_type: EndpointKindConfig::Local {
+                    command: "echo".to_string(),
+                    args: vec![],
+                    env: HashMap::new(),
+                    clean_env: false,
+                    auto_start: false,
+                    health_command: None,
+                    cwd: None,
+                    restart_on_failure: false,
+                    startup_retry: None,
+                    env_file: None,
+                },
+                tools: None,
+                rate_limit: None,
+                max_concurrent_requests: None,
+                enabled: true,
+                metadata: Default::default(),
+            })
+            .collect();
+
+        let err = manager.init_from_config(configs).await.unwrap_err();
+        assert!(err.to_string().contains("max_endpoints = 1"));
+        assert!(manager.list_endpoints().is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_adds_stop_at_max_endpoints() {
+        let manager = EndpointManager::from_config(&McpConfig {
+            max_endpoints: Some(2),
+            ..Default::default()
+        });
+
+        let adds = (0..8).map(|i| {
+            let manager = manager.clone();
+            let config = EndpointConfig {
+                name: format!("endpoint-{}", i),
+                aliases: Vec::new(),
+                endpoint_type: EndpointKindConfig::Local {
+                    command: "echo".to_string(),
+                    args: vec![],
+                    env: HashMap::new(),
+                    clean_env: false,
+                    auto_start: false,
+                    health_command: None,
+                    cwd: None,
+                    restart_on_failure: false,
+                    startup_retry: None,
+                    env_file: None,
+                },
+                tools: None,
+                rate_limit: None,
+                max_concurrent_requests: None,
+                enabled: true,
+                metadata: Default::default(),
+            };
+            tokio::spawn(async move { manager.add_endpoint(config).await })
+        });
+        let results: Vec<_> = futures::future::join_all(adds)
+            .await
+            .into_iter()
+            .map(|joined| joined.unwrap())
+            .collect();
+
+        assert_eq!(results.iter().filter(|r| r.is_ok()).count(), 2);
+        for err in results.iter().filter_map(|r| r.as_ref().err()) {
+            assert!(
+                matches!(err, ProxyError::EndpointLimitReached(_)),
+                "{}",
+                err
+            );
+            assert_eq!(err.status_code(), axum::http::StatusCode::CONFLICT);
+            assert!(err.to_string().contains("max_endpoints = 2"));
+        }
+        assert_eq!(manager.list_endpoints().len(), 2);
+    }
+
     #[tokio::test]
     async fn test_start_endpoint_fails_with_non_mcp_process() {
         let manager = EndpointManager::new();
 
         let config = EndpointConfig {
             name: "test-echo".to_string(),
+            aliases: Vec::new(),
             endpoint_type: EndpointKindConfig::Local {
                 command: "echo".to_string(),
                 args: vec!["hello".to_string()],
                 env: HashMap::new(),
+                clean_env: false,
                 auto_start: false,
+                health_command: None,
+                cwd: None,
+                restart_on_failure: false,
+                startup_retry: None,
+                env_file: None,
             },
             tools: None,
+            rate_limit: None,
+            max_concurrent_requests: None,
+            enabled: true,
+            metadata: Default::default(),
         };
 
         manager.init_from_config(vec![config]).await.unwrap();
@@ -287,6 +1075,12 @@ mod tests {
 
         let info = manager.get_endpoint_info("test-echo").unwrap();
         assert_eq!(info.status, EndpointStatus::Failed);
+        assert_eq!(
+            info.last_error,
+            Some(result.unwrap_err().to_string()),
+            "the start error should be kept for status reporting"
+        );
+        assert!(info.started_at.is_none());
     }
 
     #[tokio::test]
@@ -295,10 +1089,20 @@ mod tests {
 
         let config = EndpointConfig {
             name: "remote-server".to_string(),
+            aliases: Vec::new(),
             endpoint_type: EndpointKindConfig::Remote {
                 url: "https://example.com".to_string(),
+                urls: Vec::new(),
+                require_tools_on_start: false,
+                headers: HashMap::new(),
+                connect_timeout_secs: None,
+                prewarm: false,
             },
             tools: None,
+            rate_limit: None,
+            max_concurrent_requests: None,
+            enabled: true,
+            metadata: Default::default(),
         };
 
         manager.init_from_config(vec![config]).await.unwrap();
@@ -309,4 +1113,590 @@ mod tests {
         let result = manager.start_endpoint("remote-server").await;
         assert!(result.is_err());
     }
+
+    #[derive(Clone)]
+    struct EmptyServer;
+
+    impl rmcp::ServerHandler for EmptyServer {}
+
+    #[tokio::test]
+    async fn test_shutdown_stops_remote_endpoints() {
+        use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+        use rmcp::transport::streamable_http_server::{
+            StreamableHttpServerConfig, StreamableHttpService,
+        };
+
+        let service = StreamableHttpService::new(
+            || Ok(EmptyServer),
+            Arc::new(LocalSessionManager::default()),
+            StreamableHttpServerConfig::default(),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, axum::Router::new().nest_service("/mcp", service)).await
+        });
+
+        let manager = EndpointManager::new();
+        manager
+            .init_from_config(vec![EndpointConfig {
+                name: "remote-server".to_string(),
+                aliases: Vec::new(),
+                endpoint_type: EndpointKindConfig::Remote {
+                    url: format!("http://{}/mcp", addr),
+                    urls: Vec::new(),
+                    require_tools_on_start: false,
+                    headers: HashMap::new(),
+                    connect_timeout_secs: None,
+                    prewarm: false,
+                },
+                tools: None,
+                rate_limit: None,
+                max_concurrent_requests: None,
+                enabled: true,
+                metadata: Default::default(),
+            }])
+            .await
+            .unwrap();
+        manager.start_endpoint("remote-server").await.unwrap();
+
+        let client = manager.get_client("remote-server").await.unwrap();
+        assert!(client.is_running().await);
+        let sessions = manager.session_token();
+
+        manager.shutdown().await.unwrap();
+
+        let info = manager.get_endpoint_info("remote-server").unwrap();
+        assert_eq!(info.status, EndpointStatus::Stopped);
+        assert!(!client.is_running().await);
+        assert!(sessions.is_cancelled());
+    }
+
+    fn prewarm_endpoint_config(url: String) -> EndpointConfig {
+        EndpointConfig {
+            name: "remote-server".to_string(),
+            aliases: Vec::new(),
+            endpoint_type: EndpointKindConfig::Remote {
+                url,
+                urls: Vec::new(),
+                require_tools_on_start: false,
+                prewarm: true,
+                headers: HashMap::new(),
+                connect_timeout_secs: None,
+            },
+            tools: None,
+            rate_limit: None,
+            max_concurrent_requests: None,
+            enabled: true,
+            metadata: Default::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prewarm_connects_without_starting_remote_endpoint() {
+        use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+        use rmcp::transport::streamable_http_server::{
+            StreamableHttpServerConfig, StreamableHttpService,
+        };
+        use std::sync::atomic::AtomicUsize;
+
+        // Each MCP handshake opens a new session on the upstream
+        let sessions = Arc::new(AtomicUsize::new(0));
+        let counter = sessions.clone();
+        let service = StreamableHttpService::new(
+            move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+                Ok(EmptyServer)
+            },
+            Arc::new(LocalSessionManager::default()),
+            StreamableHttpServerConfig::default(),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, axum::Router::new().nest_service("/mcp", service)).await
+        });
+
+        let manager = EndpointManager::from_config(&McpConfig {
+            tools_cache_secs: 60,
+            ..Default::default()
+        });
+        manager
+            .init_from_config(vec![prewarm_endpoint_config(format!(
+                "http://{}/mcp",
+                addr
+            ))])
+            .await
+            .unwrap();
+
+        // The client is connected and its tools cached, but the endpoint's lifecycle is untouched
+        assert_eq!(sessions.load(Ordering::SeqCst), 1);
+        assert!(manager.cached_tools("remote-server").is_some());
+        let info = manager.get_endpoint_info("remote-server").unwrap();
+        assert_eq!(info.status, EndpointStatus::Stopped);
+
+        // Starting reuses the prewarmed client instead of handshaking again
+        manager.start_endpoint("remote-server").await.unwrap();
+        assert!(
+            manager
+                .get_client("remote-server")
+                .await
+                .unwrap()
+                .is_running()
+                .await
+        );
+        assert_eq!(sessions.load(Ordering::SeqCst), 1);
+        // The first tool listing after starting is served from the prewarm
+        assert!(manager.cached_tools("remote-server").is_some());
+
+        manager.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_failed_prewarm_does_not_fail_init() {
+        let manager = EndpointManager::new();
+        manager
+            .init_from_config(vec![prewarm_endpoint_config(
+                "http://127.0.0.1:1/mcp".to_string(),
+            )])
+            .await
+            .unwrap();
+
+        assert!(manager.is_ready());
+        let info = manager.get_endpoint_info("remote-server").unwrap();
+        assert_eq!(info.status, EndpointStatus::Stopped);
+    }
+
+    /// Answers the MCP handshake, then exits shortly afterwards like a crashing server
+    const SHORT_LIVED_MCP_SERVER: &str = r#"
+read -r line
+id=$(echo "$line" | sed 's/.*"id":\([0-9]*\).*/\1/')
+echo "{\"jsonrpc\":\"2.0\",\"id\":$id,\"result\":{\"protocolVersion\":\"2025-06-18\",\"capabilities\":{},\"serverInfo\":{\"name\":\"stub\",\"version\":\"0.0.0\"}}}"
+sleep 1
+"#;
+
+    async fn wait_for_status(manager: &EndpointManager, name: &str, status: EndpointStatus) {
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(10);
+        while manager.get_endpoint_info(name).unwrap().status != status {
+            assert!(
+                tokio::time::Instant::now() < deadline,
+                "endpoint {} never reached {:?}",
+                name,
+                status
+            );
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_supervisor_restarts_dead_local_endpoint() {
+        let manager = EndpointManager::new_with_restart_delay(Duration::from_millis(200));
+
+        let config = EndpointConfig {
+            name: "flaky".to_string(),
+            aliases: Vec::new(),
+            endpoint_type: EndpointKindConfig::Local {
+                command: "sh".to_string(),
+                args: vec!["-c".to_string(), SHORT_LIVED_MCP_SERVER.to_string()],
+                env: HashMap::new(),
+                clean_env: false,
+                auto_start: false,
+                health_command: None,
+                cwd: None,
+                restart_on_failure: true,
+                startup_retry: None,
+                env_file: None,
+            },
+            tools: None,
+            rate_limit: None,
+            max_concurrent_requests: None,
+            enabled: true,
+            metadata: Default::default(),
+        };
+
+        manager.init_from_config(vec![config]).await.unwrap();
+        manager.start_endpoint("flaky").await.unwrap();
+
+        wait_for_status(&manager, "flaky", EndpointStatus::Failed).await;
+        wait_for_status(&manager, "flaky", EndpointStatus::Running).await;
+
+        let _ = manager.stop_endpoint("flaky").await;
+    }
+
+    #[tokio::test]
+    async fn test_local_endpoint_reports_pid_while_running() {
+        let manager = EndpointManager::new();
+
+        let config = EndpointConfig {
+            name: "stub".to_string(),
+            aliases: Vec::new(),
+            endpoint_type: EndpointKindConfig::Local {
+                command: "sh".to_string(),
+                args: vec![
+                    "-c".to_string(),
+                    SHORT_LIVED_MCP_SERVER.replace("sleep 1", "exec sleep 30"),
+                ],
+                env: HashMap::new(),
+                clean_env: false,
+                auto_start: false,
+                health_command: None,
+                cwd: None,
+                restart_on_failure: false,
+                startup_retry: None,
+                env_file: None,
+            },
+            tools: None,
+            rate_limit: None,
+            max_concurrent_requests: None,
+            enabled: true,
+            metadata: Default::default(),
+        };
+
+        manager.init_from_config(vec![config]).await.unwrap();
+        let pid = || manager.get_endpoint_info("stub").unwrap().pid;
+        assert_eq!(pid(), None);
+
+        manager.start_endpoint("stub").await.unwrap();
+        assert!(pid().is_some_and(|pid| pid > 0));
+
+        manager.stop_endpoint("stub").await.unwrap();
+        assert_eq!(pid(), None);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_stop_kills_child_ignoring_sigterm_after_grace() {
+        let manager = EndpointManager::from_config(&McpConfig {
+            shutdown_grace_secs: 1,
+            ..Default::default()
+        });
+
+        // The ignored disposition survives exec, so sleep shrugs off SIGTERM too
+        let script = format!(
+            "trap '' TERM\n{}",
+            SHORT_LIVED_MCP_SERVER.replace("sleep 1", "exec sleep 30")
+        );
+        let config = EndpointConfig {
+            name: "stubborn".to_string(),
+            aliases: Vec::new(),
+            endpoint_type: EndpointKindConfig::Local {
+                command: "sh".to_string(),
+                args: vec!["-c".to_string(), script],
+                env: HashMap::new(),
+                clean_env: false,
+                auto_start: false,
+                health_command: None,
+                cwd: None,
+                restart_on_failure: false,
+                startup_retry: None,
+                env_file: None,
+            },
+            tools: None,
+            rate_limit: None,
+            max_concurrent_requests: None,
+            enabled: true,
+            metadata: Default::default(),
+        };
+
+        manager.init_from_config(vec![config]).await.unwrap();
+        manager.start_endpoint("stubborn").await.unwrap();
+        let pid = manager
+            .get_endpoint("stubborn")
+            .unwrap()
+            .read()
+            .await
+            .pid()
+            .unwrap();
+
+        let started = Instant::now();
+        manager.stop_endpoint("stubborn").await.unwrap();
+        assert!(started.elapsed() >= Duration::from_secs(1));
+
+        // SAFETY: signal 0 only checks whether the process exists
+        let alive = unsafe { libc::kill(pid as libc::pid_t, 0) } == 0;
+        assert!(!alive, "child {} should have been killed", pid);
+    }
+
+    #[tokio::test]
+    async fn test_supervisor_gives_up_after_max_restart_attempts() {
+        let manager = EndpointManager::from_config(&McpConfig {
+            restart_delay_ms: 50,
+            max_restart_attempts: Some(2),
+            ..Default::default()
+        });
+
+        // Serves once, then every later launch exits before the handshake
+        let dir = tempfile::tempdir().unwrap();
+        let runs = dir.path().join("runs");
+        let script = format!(
+            "[ -f {runs} ] && {{ echo run >> {runs}; exit 1; }}\necho run >> {runs}\n{}",
+            SHORT_LIVED_MCP_SERVER,
+            runs = runs.display()
+        );
+
+        let config = EndpointConfig {
+            name: "hopeless".to_string(),
+            aliases: Vec::new(),
+            endpoint_type: EndpointKindConfig::Local {
+                command: "sh".to_string(),
+                args: vec!["-c".to_string(), script],
+                env: HashMap::new(),
+                clean_env: false,
+                auto_start: false,
+                health_command: None,
+                cwd: None,
+                restart_on_failure: true,
+                startup_retry: None,
+                env_file: None,
+            },
+            tools: None,
+            rate_limit: None,
+            max_concurrent_requests: None,
+            enabled: true,
+            metadata: Default::default(),
+        };
+
+        manager.init_from_config(vec![config]).await.unwrap();
+        manager.start_endpoint("hopeless").await.unwrap();
+
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(15);
+        let info = loop {
+            let info = manager.get_endpoint_info("hopeless").unwrap();
+            if info.status_reason.is_some() {
+                break info;
+            }
+            assert!(
+                tokio::time::Instant::now() < deadline,
+                "supervisor never gave up"
+            );
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        };
+        assert_eq!(info.status, EndpointStatus::Failed);
+        assert!(info.status_reason.unwrap().contains("2 restart attempts"));
+
+        // One successful launch plus two failed restarts, and nothing afterwards
+        let count_runs = || std::fs::read_to_string(&runs).unwrap().lines().count();
+        assert_eq!(count_runs(), 3);
+        tokio::time::sleep(Duration::from_millis(2500)).await;
+        assert_eq!(count_runs(), 3);
+        assert_eq!(
+            manager.get_endpoint_info("hopeless").unwrap().status,
+            EndpointStatus::Failed
+        );
+    }
+
+    /// Answers the handshake and one `tools/list`, then errors on every later listing
+    const DEGRADING_MCP_SERVER: &str = r#"
+read -r line
+id=$(echo "$line" | sed 's/.*"id":\([0-9]*\).*/\1/')
+echo "{\"jsonrpc\":\"2.0\",\"id\":$id,\"result\":{\"protocolVersion\":\"2025-06-18\",\"capabilities\":{\"tools\":{}},\"serverInfo\":{\"name\":\"stub\",\"version\":\"0.0.0\"}}}"
+listed=0
+while read -r line; do
+  case "$line" in
+    *tools/list*)
+      id=$(echo "$line" | sed 's/.*"id":\([0-9]*\).*/\1/')
+      if [ $listed -eq 0 ]; then
+        listed=1
+        echo "{\"jsonrpc\":\"2.0\",\"id\":$id,\"result\":{\"tools\":[]}}"
+      else
+        echo "{\"jsonrpc\":\"2.0\",\"id\":$id,\"error\":{\"code\":-32603,\"message\":\"unhealthy\"}}"
+      fi
+      ;;
+  esac
+done
+"#;
+
+    #[tokio::test]
+    async fn test_probe_marks_degraded_endpoint_failed() {
+        let manager = EndpointManager::from_config(&McpConfig {
+            health_check_interval_secs: 1,
+            health_check_failure_threshold: 2,
+            ..Default::default()
+        });
+
+        let config = EndpointConfig {
+            name: "degrading".to_string(),
+            aliases: Vec::new(),
+            endpoint_type: EndpointKindConfig::Local {
+                command: "sh".to_string(),
+                args: vec!["-c".to_string(), DEGRADING_MCP_SERVER.to_string()],
+                env: HashMap::new(),
+                clean_env: false,
+                auto_start: false,
+                health_command: None,
+                cwd: None,
+                restart_on_failure: false,
+                startup_retry: None,
+                env_file: None,
+            },
+            tools: None,
+            rate_limit: None,
+            max_concurrent_requests: None,
+            enabled: true,
+            metadata: Default::default(),
+        };
+
+        manager.init_from_config(vec![config]).await.unwrap();
+        manager.start_endpoint("degrading").await.unwrap();
+
+        wait_for_status(&manager, "degrading", EndpointStatus::Failed).await;
+        let check = manager
+            .get_endpoint_info("degrading")
+            .unwrap()
+            .last_health_check
+            .unwrap();
+        assert!(check.error.unwrap().contains("unhealthy"));
+
+        // The child is released too, rather than left serving behind `Failed`
+        let endpoint = manager.get_endpoint("degrading").unwrap();
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while endpoint.read().await.get_or_create_client().await.is_ok() {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .unwrap();
+
+        let _ = manager.stop_endpoint("degrading").await;
+    }
+
+    /// Answers tool calls only after a few seconds, holding up requests queued behind them
+    const SLOW_CALL_MCP_SERVER: &str = r#"
+while read -r line; do
+  id=$(echo "$line" | sed -n 's/.*"id":\([0-9]*\).*/\1/p')
+  case "$line" in
+    *notifications/*)
+      ;;
+    *initialize*)
+      echo "{\"jsonrpc\":\"2.0\",\"id\":$id,\"result\":{\"protocolVersion\":\"2025-06-18\",\"capabilities\":{\"tools\":{}},\"serverInfo\":{\"name\":\"slow\",\"version\":\"0.0.0\"}}}"
+      ;;
+    *tools/list*)
+      echo "{\"jsonrpc\":\"2.0\",\"id\":$id,\"result\":{\"tools\":[]}}"
+      ;;
+    *tools/call*)
+      sleep 4
+      echo "{\"jsonrpc\":\"2.0\",\"id\":$id,\"result\":{\"content\":[{\"type\":\"text\",\"text\":\"done\"}]}}"
+      ;;
+  esac
+done
+"#;
+
+    #[tokio::test]
+    async fn test_probe_queued_behind_slow_call_is_not_counted() {
+        let manager = EndpointManager::from_config(&McpConfig {
+            health_check_interval_secs: 1,
+            health_check_failure_threshold: 1,
+            request_timeout_secs: 1,
+            ..Default::default()
+        });
+
+        let config = EndpointConfig {
+            name: "slow".to_string(),
+            aliases: Vec::new(),
+            endpoint_type: EndpointKindConfig::Local {
+                command: "sh".to_string(),
+                args: vec!["-c".to_string(), SLOW_CALL_MCP_SERVER.to_string()],
+                env: HashMap::new(),
+                clean_env: false,
+                auto_start: false,
+                health_command: None,
+                cwd: None,
+                restart_on_failure: false,
+                startup_retry: None,
+                env_file: None,
+            },
+            tools: None,
+            rate_limit: None,
+            max_concurrent_requests: None,
+            enabled: true,
+            metadata: Default::default(),
+        };
+
+        manager.init_from_config(vec![config]).await.unwrap();
+        manager.start_endpoint("slow").await.unwrap();
+
+        // Probes time out while the call holds the worker, but the endpoint stays up
+        let client = manager.get_client("slow").await.unwrap();
+        let response = client
+            .call_tool(crate::mcp::ToolCallRequest {
+                name: "wait".to_string(),
+                arguments: serde_json::json!({}),
+                task: None,
+            })
+            .await;
+        assert!(response.is_ok(), "{:?}", response.err());
+        assert_eq!(
+            manager.get_endpoint_info("slow").unwrap().status,
+            EndpointStatus::Running
+        );
+
+        let _ = manager.stop_endpoint("slow").await;
+    }
+
+    #[test]
+    fn test_restart_delay_backs_off_per_endpoint() {
+        let manager = EndpointManager::from_config(&McpConfig {
+            restart_delay_ms: 100,
+            restart_max_delay_ms: 300,
+            ..Default::default()
+        });
+
+        assert_eq!(manager.next_restart_delay("a"), Duration::from_millis(100));
+        assert_eq!(manager.next_restart_delay("a"), Duration::from_millis(200));
+        assert_eq!(manager.next_restart_delay("a"), Duration::from_millis(300));
+        // Other endpoints keep their own backoff
+        assert_eq!(manager.next_restart_delay("b"), Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn test_manual_restart_resets_backoff() {
+        let manager = EndpointManager::from_config(&McpConfig {
+            restart_delay_ms: 100,
+            restart_max_delay_ms: 300,
+            max_restart_attempts: Some(2),
+            ..Default::default()
+        });
+
+        let config = EndpointConfig {
+            name: "stub".to_string(),
+            aliases: Vec::new(),
+            endpoint_type: EndpointKindConfig::Local {
+                command: "sh".to_string(),
+                args: vec![
+                    "-c".to_string(),
+                    SHORT_LIVED_MCP_SERVER.replace("sleep 1", "exec sleep 30"),
+                ],
+                env: HashMap::new(),
+                clean_env: false,
+                auto_start: false,
+                health_command: None,
+                cwd: None,
+                restart_on_failure: false,
+                startup_retry: None,
+                env_file: None,
+            },
+            tools: None,
+            rate_limit: None,
+            max_concurrent_requests: None,
+            enabled: true,
+            metadata: Default::default(),
+        };
+
+        manager.init_from_config(vec![config]).await.unwrap();
+        manager.start_endpoint("stub").await.unwrap();
+
+        // As if the supervisor had used up its attempts
+        manager.next_restart_delay("stub");
+        manager.next_restart_delay("stub");
+        assert!(manager.restarts_exhausted("stub"));
+
+        manager.restart_endpoint("stub").await.unwrap();
+        assert!(!manager.restarts_exhausted("stub"));
+        assert_eq!(
+            manager.next_restart_delay("stub"),
+            Duration::from_millis(100)
+        );
+
+        manager.stop_endpoint("stub").await.unwrap();
+    }
 }
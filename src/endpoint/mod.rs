@@ -1,13 +1,22 @@
+pub(crate) mod backoff;
 pub(crate) mod client_holder;
+pub(crate) mod health;
 pub(crate) mod local;
 pub(crate) mod manager;
+pub(crate) mod rate_limit;
 pub(crate) mod registry;
 pub(crate) mod remote;
+pub(crate) mod stderr;
+pub(crate) mod tools_cache;
+pub(crate) mod webhook;
 
 pub(crate) use local::LocalEndpoint;
 pub use manager::EndpointManager;
+pub use registry::{EndpointInfo, EndpointStatus, EndpointType, HealthCheckResult};
 pub(crate) use remote::RemoteEndpoint;
 
+use crate::endpoint::remote::ActiveUrl;
+use crate::endpoint::stderr::StderrTail;
 use crate::error::Result;
 use crate::mcp::McpClient;
 use axum::Router;
@@ -18,10 +27,35 @@ use tokio_util::sync::CancellationToken;
 /// This allows us to store different endpoint types in the same collection
 #[derive(Clone)]
 pub(crate) enum EndpointKind {
-    Local(LocalEndpoint),
+    Local(Box<LocalEndpoint>),
     Remote(RemoteEndpoint),
 }
 
+/// Live diagnostics of an endpoint, readable while a start or stop holds its lock
+#[derive(Clone)]
+pub(crate) enum EndpointDiagnostics {
+    Local(StderrTail),
+    Remote(ActiveUrl),
+}
+
+impl EndpointDiagnostics {
+    /// URL a remote endpoint currently talks to; local endpoints have none
+    pub(crate) fn url(&self) -> Option<String> {
+        match self {
+            EndpointDiagnostics::Local(_) => None,
+            EndpointDiagnostics::Remote(url) => Some(url.get().to_string()),
+        }
+    }
+
+    /// Last lines a local endpoint's child wrote to stderr; remote endpoints have none
+    pub(crate) fn last_stderr(&self) -> Option<String> {
+        match self {
+            EndpointDiagnostics::Local(tail) => tail.text(),
+            EndpointDiagnostics::Remote(_) => None,
+        }
+    }
+}
+
 pub(crate) trait HttpTransportAdapter {
     fn attach_http_route<S>(
         &self,
@@ -54,6 +88,22 @@ impl EndpointKind {
             EndpointKind::Remote(s) => s.get_or_create_client().await,
         }
     }
+
+    /// OS process id of a local endpoint's child; remote endpoints have none
+    pub(crate) fn pid(&self) -> Option<u32> {
+        match self {
+            EndpointKind::Local(s) => s.pid(),
+            EndpointKind::Remote(_) => None,
+        }
+    }
+
+    /// Handles on the endpoint's diagnostics that don't need its lock
+    pub(crate) fn diagnostics(&self) -> EndpointDiagnostics {
+        match self {
+            EndpointKind::Local(s) => EndpointDiagnostics::Local(s.stderr_tail()),
+            EndpointKind::Remote(s) => EndpointDiagnostics::Remote(s.active_url()),
+        }
+    }
 }
 
 impl HttpTransportAdapter for EndpointKind {
@@ -67,7 +117,9 @@ impl HttpTransportAdapter for EndpointKind {
         S: Clone + Send + Sync + 'static,
     {
         match self {
-            EndpointKind::Local(s) => HttpTransportAdapter::attach_http_route(s, router, path, ct),
+            EndpointKind::Local(s) => {
+                HttpTransportAdapter::attach_http_route(&**s, router, path, ct)
+            }
             EndpointKind::Remote(s) => HttpTransportAdapter::attach_http_route(s, router, path, ct),
         }
     }
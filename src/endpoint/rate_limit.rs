@@ -0,0 +1,111 @@
+use crate::config::RateLimit;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Token bucket limiting the requests forwarded to one endpoint
+pub(crate) struct RateLimiter {
+    rate: f64,
+    burst: f64,
+    bucket: Mutex<Bucket>,
+}
+
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(limit: RateLimit) -> Self {
+        let burst = f64::from(limit.burst);
+        Self {
+            rate: limit.requests_per_second,
+            burst,
+            bucket: Mutex::new(Bucket {
+                tokens: burst,
+                refilled_at: Instant::now(),
+            }),
+        }
+    }
+
+    /// Take a token, or report how long until the next one is available
+    pub(crate) fn try_acquire(&self) -> Result<(), Duration> {
+        self.try_acquire_at(Instant::now())
+    }
+
+    fn try_acquire_at(&self, now: Instant) -> Result<(), Duration> {
+        let mut bucket = self
+            .bucket
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let elapsed = now.saturating_duration_since(bucket.refilled_at);
+        bucket.tokens = (bucket.tokens + elapsed.as_secs_f64() * self.rate).min(self.burst);
+        bucket.refilled_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            // Validation bounds the rate, but never let a tiny one panic here
+            let wait = (1.0 - bucket.tokens) / self.rate;
+            Err(Duration::try_from_secs_f64(wait).unwrap_or(Duration::MAX))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(requests_per_second: f64, burst: u32) -> RateLimiter {
+        RateLimiter::new(RateLimit {
+            requests_per_second,
+            burst,
+        })
+    }
+
+    #[test]
+    fn test_burst_then_reject() {
+        let limiter = limiter(1.0, 3);
+        let now = Instant::now();
+
+        for _ in 0..3 {
+            assert!(limiter.try_acquire_at(now).is_ok());
+        }
+        let wait = limiter.try_acquire_at(now).unwrap_err();
+        assert!(wait > Duration::ZERO && wait <= Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_tiny_rate_saturates_wait() {
+        let limiter = limiter(1e-20, 1);
+        let now = Instant::now();
+
+        assert!(limiter.try_acquire_at(now).is_ok());
+        assert_eq!(limiter.try_acquire_at(now).unwrap_err(), Duration::MAX);
+    }
+
+    #[test]
+    fn test_tokens_refill_over_time() {
+        let limiter = limiter(10.0, 1);
+        let now = Instant::now();
+
+        assert!(limiter.try_acquire_at(now).is_ok());
+        assert!(limiter.try_acquire_at(now).is_err());
+        assert!(
+            limiter
+                .try_acquire_at(now + Duration::from_millis(100))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_refill_is_capped_at_burst() {
+        let limiter = limiter(100.0, 2);
+        let later = Instant::now() + Duration::from_secs(60);
+
+        assert!(limiter.try_acquire_at(later).is_ok());
+        assert!(limiter.try_acquire_at(later).is_ok());
+        assert!(limiter.try_acquire_at(later).is_err());
+    }
+}
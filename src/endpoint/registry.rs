@@ -1,14 +1,16 @@
-use crate::config::ToolFilter;
+use crate::config::{EndpointMetadata, PathMatching, ToolFilter};
 use crate::error::{ProxyError, Result};
 use dashmap::DashMap;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 
 /// Status of an MCP endpoint instance
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[non_exhaustive]
 #[serde(rename_all = "lowercase")]
-pub(crate) enum EndpointStatus {
+pub enum EndpointStatus {
     Starting,
     Running,
     Stopping,
@@ -30,18 +32,58 @@ impl fmt::Display for EndpointStatus {
 }
 
 /// Information about a registered endpoint
+/// Non-exhaustive, so fields can be added without breaking library consumers
 #[derive(Debug, Clone)]
-pub(crate) struct EndpointInfo {
-    pub(crate) name: String,
-    pub(crate) path: String,
-    pub(crate) endpoint_type: EndpointType,
-    pub(crate) status: EndpointStatus,
-    pub(crate) tool_filter: Option<ToolFilter>,
+#[non_exhaustive]
+pub struct EndpointInfo {
+    pub name: String,
+    pub path: String,
+    /// Further paths the endpoint is reachable at, e.g. its name before a rename
+    pub aliases: Vec<String>,
+    pub endpoint_type: EndpointType,
+    pub status: EndpointStatus,
+    /// Why the endpoint is in its current status, when there's more to say
+    pub status_reason: Option<String>,
+    pub tool_filter: Option<ToolFilter>,
+    pub metadata: EndpointMetadata,
+    pub last_health_check: Option<HealthCheckResult>,
+    /// When the endpoint last entered `Running`; cleared when it leaves it
+    pub started_at: Option<SystemTime>,
+    /// OS process id of a running local endpoint's child; cleared when it leaves `Running`
+    pub pid: Option<u32>,
+    /// Set by an operator's stop, so traffic doesn't start the endpoint again until an
+    /// explicit start
+    pub stopped_by_operator: bool,
+    /// Most recent failure message, kept across restarts for debugging
+    pub last_error: Option<String>,
+}
+
+impl EndpointInfo {
+    /// How long the endpoint has been running, if it is
+    pub fn uptime(&self) -> Option<Duration> {
+        self.started_at
+            .map(|started| started.elapsed().unwrap_or_default())
+    }
+
+    /// The primary path followed by any aliases
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.path.as_str()).chain(self.aliases.iter().map(String::as_str))
+    }
+}
+
+/// Outcome of the most recent active health probe
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct HealthCheckResult {
+    pub checked_at: SystemTime,
+    /// Probe error, or `None` when the endpoint answered
+    pub error: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
-pub(crate) enum EndpointType {
+#[non_exhaustive]
+pub enum EndpointType {
     Local,
     Remote,
 }
@@ -60,39 +102,80 @@ impl fmt::Display for EndpointType {
 #[derive(Clone)]
 pub(crate) struct EndpointRegistry {
     endpoints: Arc<DashMap<String, EndpointInfo>>,
+    /// Paths the router can't tell apart count as the same path
+    path_matching: PathMatching,
 }
 
 impl EndpointRegistry {
     pub(crate) fn new() -> Self {
         Self {
             endpoints: Arc::new(DashMap::new()),
+            path_matching: PathMatching::default(),
         }
     }
 
+    /// Check new paths for clashes as configured by `mcp.path_matching`
+    pub(crate) fn with_path_matching(mut self, path_matching: PathMatching) -> Self {
+        self.path_matching = path_matching;
+        self
+    }
+
+    pub(crate) fn path_matching(&self) -> PathMatching {
+        self.path_matching
+    }
+
     /// Register a new endpoint
     pub(crate) fn register(
         &self,
         name: String,
         path: String,
+        aliases: Vec<String>,
         endpoint_type: EndpointType,
         tool_filter: Option<ToolFilter>,
+        metadata: EndpointMetadata,
     ) -> Result<()> {
         if self.endpoints.contains_key(&name) {
             return Err(ProxyError::server_already_exists(name));
         }
+        for entry in self.endpoints.iter() {
+            let other = entry.value();
+            if let Some(taken) = std::iter::once(&path).chain(&aliases).find(|p| {
+                other
+                    .paths()
+                    .any(|taken| self.path_matching.same_path(taken, p))
+            }) {
+                return Err(ProxyError::path_conflict(taken, &other.name));
+            }
+        }
 
         let info = EndpointInfo {
             name: name.clone(),
             path,
+            aliases,
             endpoint_type,
             status: EndpointStatus::Stopped,
+            status_reason: None,
             tool_filter,
+            metadata,
+            last_health_check: None,
+            started_at: None,
+            pid: None,
+            stopped_by_operator: false,
+            last_error: None,
         };
 
         self.endpoints.insert(name, info);
         Ok(())
     }
 
+    /// Remove an endpoint, returning its last known info
+    pub(crate) fn unregister(&self, name: &str) -> Result<EndpointInfo> {
+        self.endpoints
+            .remove(name)
+            .map(|(_, info)| info)
+            .ok_or_else(|| ProxyError::server_not_found(name.to_string()))
+    }
+
     /// Get endpoint info by name
     pub(crate) fn get(&self, name: &str) -> Result<EndpointInfo> {
         self.endpoints
@@ -104,18 +187,74 @@ impl EndpointRegistry {
     pub(crate) fn get_by_path(&self, path: &str) -> Result<EndpointInfo> {
         self.endpoints
             .iter()
-            .find(|entry| entry.value().path == path)
+            .find(|entry| entry.value().paths().any(|p| p == path))
             .map(|entry| entry.value().clone())
             .ok_or_else(|| ProxyError::server_not_found(format!("No endpoint at path: {}", path)))
     }
 
     /// Update endpoint status
     pub(crate) fn set_status(&self, name: &str, status: EndpointStatus) -> Result<()> {
+        self.set_status_with_reason(name, status, None)
+    }
+
+    /// Update endpoint status, recording why it changed
+    pub(crate) fn set_status_with_reason(
+        &self,
+        name: &str,
+        status: EndpointStatus,
+        reason: Option<String>,
+    ) -> Result<()> {
         let mut entry = self
             .endpoints
             .get_mut(name)
             .ok_or_else(|| ProxyError::server_not_found(name.to_string()))?;
+        if status != EndpointStatus::Running {
+            entry.started_at = None;
+            entry.pid = None;
+        } else if entry.status != EndpointStatus::Running {
+            entry.started_at = Some(SystemTime::now());
+        }
         entry.status = status;
+        entry.status_reason = reason;
+        Ok(())
+    }
+
+    /// Record the child process id of an endpoint that just started
+    pub(crate) fn set_pid(&self, name: &str, pid: Option<u32>) -> Result<()> {
+        let mut entry = self
+            .endpoints
+            .get_mut(name)
+            .ok_or_else(|| ProxyError::server_not_found(name.to_string()))?;
+        entry.pid = pid;
+        Ok(())
+    }
+
+    /// Record whether the endpoint's current stop was an operator's
+    pub(crate) fn set_stopped_by_operator(&self, name: &str, stopped: bool) -> Result<()> {
+        let mut entry = self
+            .endpoints
+            .get_mut(name)
+            .ok_or_else(|| ProxyError::server_not_found(name.to_string()))?;
+        entry.stopped_by_operator = stopped;
+        Ok(())
+    }
+
+    /// Mark an endpoint failed, remembering the error
+    pub(crate) fn set_failed(&self, name: &str, error: String) -> Result<()> {
+        self.set_status(name, EndpointStatus::Failed)?;
+        if let Some(mut entry) = self.endpoints.get_mut(name) {
+            entry.last_error = Some(error);
+        }
+        Ok(())
+    }
+
+    /// Record the outcome of an active health probe
+    pub(crate) fn record_health_check(&self, name: &str, result: HealthCheckResult) -> Result<()> {
+        let mut entry = self
+            .endpoints
+            .get_mut(name)
+            .ok_or_else(|| ProxyError::server_not_found(name.to_string()))?;
+        entry.last_health_check = Some(result);
         Ok(())
     }
 
@@ -145,8 +284,10 @@ mod tests {
             .register(
                 "test-server".to_string(),
                 "test".to_string(),
+                Vec::new(),
                 EndpointType::Local,
                 None,
+                EndpointMetadata::default(),
             )
             .unwrap();
 
@@ -154,6 +295,35 @@ mod tests {
         assert_eq!(info.name, "test-server");
         assert_eq!(info.path, "test");
         assert_eq!(info.status, EndpointStatus::Stopped);
+        assert!(info.tool_filter.is_none());
+    }
+
+    #[test]
+    fn test_registered_tool_filter_is_retrievable() {
+        let registry = EndpointRegistry::new();
+        registry
+            .register(
+                "docs".to_string(),
+                "docs".to_string(),
+                vec!["old-docs".to_string()],
+                EndpointType::Remote,
+                Some(ToolFilter {
+                    include: Some(vec!["search".to_string()]),
+                    exclude: Some(vec!["delete".to_string()]),
+                    ..Default::default()
+                }),
+                EndpointMetadata::default(),
+            )
+            .unwrap();
+
+        for info in [
+            registry.get("docs").unwrap(),
+            registry.get_by_path("old-docs").unwrap(),
+        ] {
+            let filter = info.tool_filter.unwrap();
+            assert_eq!(filter.include, Some(vec!["search".to_string()]));
+            assert_eq!(filter.exclude, Some(vec!["delete".to_string()]));
+        }
     }
 
     #[test]
@@ -163,20 +333,60 @@ mod tests {
             .register(
                 "test-server".to_string(),
                 "test".to_string(),
+                Vec::new(),
                 EndpointType::Local,
                 None,
+                EndpointMetadata::default(),
             )
             .unwrap();
 
         let result = registry.register(
             "test-server".to_string(),
             "test2".to_string(),
+            Vec::new(),
             EndpointType::Local,
             None,
+            EndpointMetadata::default(),
         );
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_aliases_resolve_and_must_not_clash() {
+        let registry = EndpointRegistry::new();
+        registry
+            .register(
+                "docs".to_string(),
+                "docs".to_string(),
+                vec!["old-docs".to_string()],
+                EndpointType::Remote,
+                None,
+                EndpointMetadata::default(),
+            )
+            .unwrap();
+
+        assert_eq!(registry.get_by_path("old-docs").unwrap().name, "docs");
+
+        let result = registry.register(
+            "old-docs".to_string(),
+            "old-docs".to_string(),
+            Vec::new(),
+            EndpointType::Remote,
+            None,
+            EndpointMetadata::default(),
+        );
+        assert!(matches!(result, Err(ProxyError::PathConflict(_))));
+        let result = registry.register(
+            "search".to_string(),
+            "search".to_string(),
+            vec!["docs".to_string()],
+            EndpointType::Remote,
+            None,
+            EndpointMetadata::default(),
+        );
+        assert!(matches!(result, Err(ProxyError::PathConflict(_))));
+    }
+
     #[test]
     fn test_set_status() {
         let registry = EndpointRegistry::new();
@@ -184,8 +394,10 @@ mod tests {
             .register(
                 "test-server".to_string(),
                 "test".to_string(),
+                Vec::new(),
                 EndpointType::Local,
                 None,
+                EndpointMetadata::default(),
             )
             .unwrap();
 
@@ -194,6 +406,67 @@ mod tests {
             .unwrap();
         let info = registry.get("test-server").unwrap();
         assert_eq!(info.status, EndpointStatus::Running);
+
+        registry
+            .set_status_with_reason(
+                "test-server",
+                EndpointStatus::Failed,
+                Some("gave up".to_string()),
+            )
+            .unwrap();
+        assert_eq!(
+            registry
+                .get("test-server")
+                .unwrap()
+                .status_reason
+                .as_deref(),
+            Some("gave up")
+        );
+
+        assert!(registry.get("test-server").unwrap().started_at.is_none());
+
+        // A plain status change clears the old reason
+        registry
+            .set_status("test-server", EndpointStatus::Stopped)
+            .unwrap();
+        assert!(registry.get("test-server").unwrap().status_reason.is_none());
+    }
+
+    #[test]
+    fn test_started_at_and_last_error() {
+        let registry = EndpointRegistry::new();
+        registry
+            .register(
+                "test-server".to_string(),
+                "test".to_string(),
+                Vec::new(),
+                EndpointType::Local,
+                None,
+                EndpointMetadata::default(),
+            )
+            .unwrap();
+
+        registry
+            .set_status("test-server", EndpointStatus::Running)
+            .unwrap();
+        let started_at = registry.get("test-server").unwrap().started_at.unwrap();
+        registry
+            .set_status("test-server", EndpointStatus::Running)
+            .unwrap();
+        assert_eq!(
+            registry.get("test-server").unwrap().started_at,
+            Some(started_at)
+        );
+
+        registry
+            .set_failed("test-server", "boom".to_string())
+            .unwrap();
+        registry
+            .set_status("test-server", EndpointStatus::Running)
+            .unwrap();
+        let info = registry.get("test-server").unwrap();
+        assert!(info.started_at.is_some());
+        assert_eq!(info.last_error.as_deref(), Some("boom"));
     }
 
     #[test]
@@ -203,16 +476,20 @@ mod tests {
             .register(
                 "server1".to_string(),
                 "path1".to_string(),
+                Vec::new(),
                 EndpointType::Local,
                 None,
+                EndpointMetadata::default(),
             )
             .unwrap();
         registry
             .register(
                 "server2".to_string(),
                 "path2".to_string(),
+                Vec::new(),
                 EndpointType::Remote,
                 None,
+                EndpointMetadata::default(),
             )
             .unwrap();
 
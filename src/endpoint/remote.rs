@@ -1,37 +1,91 @@
-use crate::config::EndpointConfig;
+use crate::config::{EndpointConfig, expand_env_vars, remote_urls};
 use crate::endpoint::HttpTransportAdapter;
 use crate::endpoint::client_holder::ClientHolder;
 use crate::error::{ProxyError, Result};
-use crate::mcp::McpClient;
+use crate::mcp::client::header_map;
+use crate::mcp::{McpClient, McpClientSettings, ToolDefinition};
 use axum::Router;
+use axum::extract::Request;
+use axum::response::Response;
+use axum::routing::future::RouteFuture;
 use axum_reverse_proxy::ReverseProxy;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::future::Future;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::task::{Context, Poll};
+use std::time::Duration;
 use tokio_util::sync::CancellationToken;
+use tower::Service;
 use tracing::{info, warn};
 
 /// Represents a remote MCP endpoint accessed via HTTP/SSE
 #[derive(Clone)]
 pub(crate) struct RemoteEndpoint {
     pub(crate) name: String,
-    pub(crate) url: String,
+    /// Upstream URLs in failover order
+    pub(crate) urls: Vec<String>,
+    /// Index into `urls` of the URL in use, shared with the proxied route
+    active: Arc<AtomicUsize>,
+    pub(crate) require_tools_on_start: bool,
+    /// Headers added to every upstream request, with `${ENV_VAR}` references resolved
+    pub(crate) headers: HashMap<String, String>,
+    max_retries: u32,
+    retry_backoff: Duration,
     client_holder: ClientHolder,
+    /// Held while handshaking, so concurrent first requests share one upstream session
+    connecting: Arc<tokio::sync::Mutex<()>>,
 }
 
 impl RemoteEndpoint {
-    pub(crate) fn new(name: String, url: String) -> Self {
-        let client_holder = ClientHolder::new(name.clone());
+    pub(crate) fn new(name: String, urls: Vec<String>, client_settings: McpClientSettings) -> Self {
+        let max_retries = client_settings.max_retries;
+        let retry_backoff = client_settings.retry_backoff;
+        let client_holder = ClientHolder::new(name.clone(), client_settings);
         Self {
             name,
-            url,
+            urls,
+            active: Arc::default(),
+            require_tools_on_start: false,
+            headers: HashMap::new(),
+            max_retries,
+            retry_backoff,
             client_holder,
+            connecting: Arc::default(),
         }
     }
 
-    pub(crate) fn from_config(config: &EndpointConfig) -> Result<Self> {
+    pub(crate) fn from_config(
+        config: &EndpointConfig,
+        client_settings: McpClientSettings,
+    ) -> Result<Self> {
         match &config.endpoint_type {
-            crate::config::EndpointKindConfig::Remote { url } => {
-                info!("Configured remote MCP endpoint: {} at {}", config.name, url);
-                Ok(Self::new(config.name.clone(), url.clone()))
+            crate::config::EndpointKindConfig::Remote {
+                url,
+                urls,
+                require_tools_on_start,
+                headers,
+                connect_timeout_secs,
+                ..
+            } => {
+                let urls = remote_urls(&config.name, url, urls)?;
+                info!(
+                    "Configured remote MCP endpoint: {} at {}",
+                    config.name,
+                    urls.join(", ")
+                );
+                let mut client_settings = client_settings;
+                if let Some(secs) = connect_timeout_secs {
+                    client_settings.connect_timeout = Duration::from_secs(*secs);
+                }
+                let mut endpoint = Self::new(config.name.clone(), urls, client_settings);
+                endpoint.require_tools_on_start = *require_tools_on_start;
+                endpoint.headers = headers
+                    .iter()
+                    .map(|(name, value)| Ok((name.clone(), expand_env_vars(value)?)))
+                    .collect::<Result<_>>()?;
+                Ok(endpoint)
             }
             _ => Err(ProxyError::config("Expected remote endpoint configuration")),
         }
@@ -39,16 +93,34 @@ impl RemoteEndpoint {
 }
 
 impl RemoteEndpoint {
+    /// The URL requests currently go to
+    pub(crate) fn url(&self) -> &str {
+        &self.urls[self.active.load(Ordering::Relaxed)]
+    }
+
+    /// Handle that follows failovers without holding on to the endpoint
+    pub(crate) fn active_url(&self) -> ActiveUrl {
+        ActiveUrl {
+            urls: self.urls.clone().into(),
+            active: self.active.clone(),
+        }
+    }
+
     pub(crate) async fn start(&mut self) -> Result<()> {
         info!(
             "Starting remote MCP endpoint: {} at {}",
-            self.name, self.url
+            self.name,
+            self.url()
         );
 
-        let client = self.client_holder.get();
-        client.init_with_http(&self.url).await?;
+        if self.client_holder.get().is_running().await {
+            // A prewarm already handshook and listed tools
+            info!("Successfully started remote MCP endpoint: {}", self.name);
+            return Ok(());
+        }
+        let client = self.get_or_create_client().await?;
 
-        match client.list_tools().await {
+        match self.retrying("list tools of", || client.list_tools()).await {
             Ok(tools) => {
                 info!(
                     "Successfully connected to remote endpoint {} ({} tools available)",
@@ -56,6 +128,14 @@ impl RemoteEndpoint {
                     tools.len()
                 );
             }
+            Err(e) if self.require_tools_on_start => {
+                // Don't leave a half-working endpoint marked as running
+                let _ = client.stop().await;
+                return Err(ProxyError::server_start_failed(
+                    &self.name,
+                    format!("failed to list tools after handshake: {}", e),
+                ));
+            }
             Err(e) => {
                 warn!(
                     "Connected to remote endpoint {} but failed to list tools: {}",
@@ -71,25 +151,111 @@ impl RemoteEndpoint {
     pub(crate) async fn stop(&mut self) -> Result<()> {
         info!("Stopping remote MCP endpoint: {}", self.name);
 
+        // Requests are proxied without a client, so one may never have been created
         let client = self.client_holder.get();
-        client.stop().await?;
+        match client.stop().await {
+            Ok(()) | Err(ProxyError::ServerNotRunning(_)) => {}
+            Err(e) => return Err(e),
+        }
 
         info!("Successfully stopped remote MCP endpoint: {}", self.name);
         Ok(())
     }
 
+    /// Handshake and list tools ahead of the first start, leaving the endpoint stopped
+    pub(crate) async fn prewarm(&self) -> Result<Vec<ToolDefinition>> {
+        let client = self.get_or_create_client().await?;
+        match self.retrying("list tools of", || client.list_tools()).await {
+            Ok(tools) => {
+                info!(
+                    "Prewarmed remote endpoint {} ({} tools available)",
+                    self.name,
+                    tools.len()
+                );
+                Ok(tools)
+            }
+            Err(e) => {
+                // Leave the handshake to the next start, which lists tools itself
+                let _ = client.stop().await;
+                Err(e)
+            }
+        }
+    }
+
     pub(crate) async fn get_or_create_client(&self) -> Result<Arc<McpClient>> {
         let client = self.client_holder.get();
+        if client.is_running().await {
+            return Ok(client);
+        }
+
+        // Requests that waited here find the client connected by the first one
+        let _connecting = self.connecting.lock().await;
         if !client.is_running().await {
             info!(
                 "Creating new HTTP client for remote endpoint: {}",
                 self.name
             );
-            client.init_with_http(&self.url).await?;
+            self.connect(&client).await?;
         }
 
         Ok(client)
     }
+
+    /// Handshake with the first URL that answers, starting at the active one
+    /// A URL that can't be reached is skipped until the others have failed too
+    async fn connect(&self, client: &McpClient) -> Result<()> {
+        let start = self.active.load(Ordering::Relaxed);
+        let mut result = Ok(());
+        for index in (start..start + self.urls.len()).map(|i| i % self.urls.len()) {
+            let url = &self.urls[index];
+            result = self
+                .retrying("connect to", || {
+                    client.init_with_http_headers(url, &self.headers)
+                })
+                .await;
+
+            match &result {
+                Ok(()) => {
+                    if index != start {
+                        info!("Remote endpoint {} failed over to {}", self.name, url);
+                        self.active.store(index, Ordering::Relaxed);
+                    }
+                    break;
+                }
+                Err(e) if e.is_transient() && self.urls.len() > 1 => {
+                    warn!(
+                        "Marking {} unhealthy for remote endpoint {}: {}",
+                        url, self.name, e
+                    );
+                }
+                Err(_) => break,
+            }
+        }
+        result
+    }
+
+    /// Run `attempt`, retrying with exponential backoff while it fails with a transient error
+    async fn retrying<T, F, Fut>(&self, action: &str, mut attempt: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut delay = self.retry_backoff;
+        for retry in 1..=self.max_retries {
+            match attempt().await {
+                Err(e) if e.is_transient() => {
+                    warn!(
+                        "Failed to {} remote endpoint {} (retry {}/{} in {:?}): {}",
+                        action, self.name, retry, self.max_retries, delay, e
+                    );
+                    tokio::time::sleep(delay).await;
+                    delay = delay.saturating_mul(2);
+                }
+                result => return result,
+            }
+        }
+        attempt().await
+    }
 }
 
 impl HttpTransportAdapter for RemoteEndpoint {
@@ -104,15 +270,75 @@ impl HttpTransportAdapter for RemoteEndpoint {
     {
         info!(
             "Setting up HTTP reverse proxy for remote endpoint {} at /mcp/{} → {}",
-            self.name, path, self.url
+            self.name,
+            path,
+            self.url()
         );
 
-        let proxy = ReverseProxy::new(&format!("/mcp/{}", path), &self.url);
+        let mount = format!("/mcp/{}", path);
+        let proxy = FailoverProxy {
+            proxies: self
+                .urls
+                .iter()
+                .map(|url| {
+                    Router::new().fallback_service(ReverseProxy::new(mount.as_str(), url.as_str()))
+                })
+                .collect(),
+            active: self.active.clone(),
+        };
+        let proxy: Router<S> = Router::new().nest(&mount, Router::new().fallback_service(proxy));
+        if self.headers.is_empty() {
+            return Ok(router.merge(proxy));
+        }
 
+        let headers = header_map(&self.headers)?;
+        let proxy = proxy.layer(axum::middleware::map_request(
+            move |mut request: Request| {
+                request.headers_mut().extend(headers.clone());
+                async move { request }
+            },
+        ));
         Ok(router.merge(proxy))
     }
 }
 
+/// The URL a remote endpoint currently talks to, shared with the endpoint
+#[derive(Clone)]
+pub(crate) struct ActiveUrl {
+    urls: Arc<[String]>,
+    active: Arc<AtomicUsize>,
+}
+
+impl ActiveUrl {
+    pub(crate) fn get(&self) -> &str {
+        &self.urls[self.active.load(Ordering::Relaxed)]
+    }
+}
+
+/// Reverse proxy forwarding each request to the endpoint's active URL
+#[derive(Clone)]
+struct FailoverProxy {
+    /// One proxy per URL, in the same order
+    proxies: Arc<[Router]>,
+    active: Arc<AtomicUsize>,
+}
+
+impl Service<Request> for FailoverProxy {
+    type Response = Response;
+    type Error = Infallible;
+    type Future = RouteFuture<Infallible>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<std::result::Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        self.proxies[self.active.load(Ordering::Relaxed)]
+            .clone()
+            .call(request)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -122,31 +348,344 @@ mod tests {
     fn test_create_remote_endpoint() {
         let config = EndpointConfig {
             name: "test-remote".to_string(),
+            aliases: Vec::new(),
             endpoint_type: EndpointKindConfig::Remote {
                 url: "https://example.com".to_string(),
+                urls: Vec::new(),
+                require_tools_on_start: false,
+                headers: Default::default(),
+                connect_timeout_secs: None,
+                prewarm: false,
             },
             tools: None,
+            rate_limit: None,
+            max_concurrent_requests: None,
+            enabled: true,
+            metadata: Default::default(),
         };
 
-        let endpoint = RemoteEndpoint::from_config(&config).unwrap();
+        let endpoint = RemoteEndpoint::from_config(&config, McpClientSettings::default()).unwrap();
         assert_eq!(endpoint.name, "test-remote");
-        assert_eq!(endpoint.url, "https://example.com");
+        assert_eq!(endpoint.url(), "https://example.com");
+        assert!(!endpoint.require_tools_on_start);
+    }
+
+    #[test]
+    fn test_from_config_with_require_tools_on_start() {
+        let config = EndpointConfig {
+            name: "strict-remote".to_string(),
+            aliases: Vec::new(),
+            endpoint_type: EndpointKindConfig::Remote {
+                url: "https://example.com".to_string(),
+                urls: Vec::new(),
+                require_tools_on_start: true,
+                headers: Default::default(),
+                connect_timeout_secs: None,
+                prewarm: false,
+            },
+            tools: None,
+            rate_limit: None,
+            max_concurrent_requests: None,
+            enabled: true,
+            metadata: Default::default(),
+        };
+
+        let endpoint = RemoteEndpoint::from_config(&config, McpClientSettings::default()).unwrap();
+        assert!(endpoint.require_tools_on_start);
     }
 
     #[test]
     fn test_from_config_with_local_config_fails() {
         let config = EndpointConfig {
             name: "test-local".to_string(),
+            aliases: Vec::new(),
             endpoint_type: EndpointKindConfig::Local {
                 command: "echo".to_string(),
                 args: vec![],
                 env: Default::default(),
+                clean_env: false,
                 auto_start: false,
+                health_command: None,
+                cwd: None,
+                restart_on_failure: false,
+                startup_retry: None,
+                env_file: None,
             },
             tools: None,
+            rate_limit: None,
+            max_concurrent_requests: None,
+            enabled: true,
+            metadata: Default::default(),
         };
 
-        let result = RemoteEndpoint::from_config(&config);
+        let result = RemoteEndpoint::from_config(&config, McpClientSettings::default());
         assert!(result.is_err());
     }
+
+    #[derive(Clone)]
+    struct ToolsServer;
+
+    impl rmcp::ServerHandler for ToolsServer {}
+
+    /// Serve `ToolsServer`, returning the MCP URL and the number of sessions opened
+    /// Each MCP handshake opens a new session on the upstream
+    async fn spawn_session_counter() -> (String, Arc<std::sync::atomic::AtomicUsize>) {
+        use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+        use rmcp::transport::streamable_http_server::{
+            StreamableHttpServerConfig, StreamableHttpService,
+        };
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let sessions = Arc::new(AtomicUsize::new(0));
+        let counter = sessions.clone();
+        let service = StreamableHttpService::new(
+            move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+                Ok(ToolsServer)
+            },
+            Arc::new(LocalSessionManager::default()),
+            StreamableHttpServerConfig::default(),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, Router::new().nest_service("/mcp", service)).await
+        });
+
+        (format!("http://{}/mcp", addr), sessions)
+    }
+
+    #[tokio::test]
+    async fn test_get_or_create_client_reuses_initialized_client() {
+        let (url, sessions) = spawn_session_counter().await;
+        let mut endpoint = RemoteEndpoint::new(
+            "test-remote".to_string(),
+            vec![url],
+            McpClientSettings::default(),
+        );
+
+        let first = endpoint.get_or_create_client().await.unwrap();
+        let second = endpoint.get_or_create_client().await.unwrap();
+
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(sessions.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        endpoint.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_first_requests_share_one_handshake() {
+        let (url, sessions) = spawn_session_counter().await;
+        let mut endpoint = RemoteEndpoint::new(
+            "test-remote".to_string(),
+            vec![url],
+            McpClientSettings::default(),
+        );
+
+        let clients =
+            futures::future::join_all((0..8).map(|_| endpoint.get_or_create_client())).await;
+        let clients: Vec<_> = clients.into_iter().map(Result::unwrap).collect();
+
+        assert!(clients.iter().all(|c| Arc::ptr_eq(c, &clients[0])));
+        assert_eq!(sessions.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        endpoint.stop().await.unwrap();
+    }
+
+    /// Serve `ToolsServer` behind a TCP front that drops the first `drops` connections
+    /// Returns the MCP URL and the number of connections accepted so far
+    async fn spawn_flaky_upstream(drops: usize) -> (String, Arc<std::sync::atomic::AtomicUsize>) {
+        use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+        use rmcp::transport::streamable_http_server::{
+            StreamableHttpServerConfig, StreamableHttpService,
+        };
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let service = StreamableHttpService::new(
+            || Ok(ToolsServer),
+            Arc::new(LocalSessionManager::default()),
+            StreamableHttpServerConfig::default(),
+        );
+        let upstream = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let upstream_addr = upstream.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(upstream, Router::new().nest_service("/mcp", service)).await
+        });
+
+        let front = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = front.local_addr().unwrap();
+        let connections = Arc::new(AtomicUsize::new(0));
+        let accepted = connections.clone();
+        tokio::spawn(async move {
+            while let Ok((mut inbound, _)) = front.accept().await {
+                if accepted.fetch_add(1, Ordering::SeqCst) < drops {
+                    continue;
+                }
+                tokio::spawn(async move {
+                    let mut outbound = tokio::net::TcpStream::connect(upstream_addr).await?;
+                    tokio::io::copy_bidirectional(&mut inbound, &mut outbound).await
+                });
+            }
+        });
+
+        (format!("http://{}/mcp", addr), connections)
+    }
+
+    fn retry_settings(max_retries: u32) -> McpClientSettings {
+        McpClientSettings {
+            max_retries,
+            retry_backoff: std::time::Duration::from_millis(10),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_start_retries_dropped_connections() {
+        let (url, connections) = spawn_flaky_upstream(2).await;
+        let mut endpoint = RemoteEndpoint::new("flaky".to_string(), vec![url], retry_settings(2));
+
+        endpoint.start().await.unwrap();
+        assert!(connections.load(std::sync::atomic::Ordering::SeqCst) > 2);
+        assert!(endpoint.client_holder.get().is_running().await);
+
+        endpoint.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_get_or_create_client_gives_up_after_max_retries() {
+        let (url, _) = spawn_flaky_upstream(2).await;
+        let endpoint = RemoteEndpoint::new("flaky".to_string(), vec![url], retry_settings(1));
+
+        let err = endpoint.get_or_create_client().await.err().unwrap();
+        assert!(err.is_transient(), "{}", err);
+
+        // The third connection gets through
+        let client = endpoint.get_or_create_client().await.unwrap();
+        assert!(client.is_running().await);
+    }
+
+    #[tokio::test]
+    async fn test_start_fails_over_to_reachable_url() {
+        let dead = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let dead_url = format!("http://{}/mcp", dead.local_addr().unwrap());
+        drop(dead);
+        let (live_url, _) = spawn_flaky_upstream(0).await;
+
+        let urls = vec![dead_url, live_url.clone()];
+        let mut endpoint = RemoteEndpoint::new("regions".to_string(), urls, retry_settings(0));
+
+        endpoint.start().await.unwrap();
+        assert_eq!(endpoint.url(), live_url);
+        assert!(endpoint.client_holder.get().is_running().await);
+
+        // Clones, like the one behind the proxied route, follow the failover
+        let clone = endpoint.clone();
+        assert_eq!(clone.url(), live_url);
+        assert_eq!(endpoint.active_url().get(), live_url);
+
+        endpoint.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_connect_fails_when_every_url_is_down() {
+        let dead = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let dead_url = format!("http://{}/mcp", dead.local_addr().unwrap());
+        drop(dead);
+
+        let urls = vec![dead_url.clone(), dead_url];
+        let endpoint = RemoteEndpoint::new("regions".to_string(), urls, retry_settings(0));
+
+        let err = endpoint.get_or_create_client().await.err().unwrap();
+        assert!(err.is_transient(), "{}", err);
+        assert_eq!(endpoint.active.load(Ordering::Relaxed), 0);
+    }
+
+    type RecordedKeys = Arc<std::sync::Mutex<Vec<Option<String>>>>;
+
+    /// Serve plain HTTP on a local port, recording the `x-api-key` of each request
+    async fn spawn_key_recorder() -> (String, RecordedKeys) {
+        let recorded = RecordedKeys::default();
+        let keys = recorded.clone();
+        let app = Router::new().fallback(move |headers: axum::http::HeaderMap| {
+            let keys = keys.clone();
+            async move {
+                let key = headers
+                    .get("x-api-key")
+                    .and_then(|v| v.to_str().ok())
+                    .map(String::from);
+                keys.lock().unwrap().push(key);
+                "not an MCP server"
+            }
+        });
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        (format!("http://{}", addr), recorded)
+    }
+
+    fn create_keyed_endpoint(url: String) -> RemoteEndpoint {
+        // SAFETY: the variable name is unique to these tests
+        unsafe { std::env::set_var("RUSTED_TOOLS_TEST_REMOTE_KEY", "s3cret") };
+
+        let config = EndpointConfig {
+            name: "keyed".to_string(),
+            aliases: Vec::new(),
+            endpoint_type: EndpointKindConfig::Remote {
+                url,
+                urls: Vec::new(),
+                require_tools_on_start: false,
+                headers: HashMap::from([(
+                    "X-Api-Key".to_string(),
+                    "${RUSTED_TOOLS_TEST_REMOTE_KEY}".to_string(),
+                )]),
+                connect_timeout_secs: None,
+                prewarm: false,
+            },
+            tools: None,
+            rate_limit: None,
+            max_concurrent_requests: None,
+            enabled: true,
+            metadata: Default::default(),
+        };
+        RemoteEndpoint::from_config(&config, McpClientSettings::default()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_configured_headers_reach_proxied_requests() {
+        use tower::ServiceExt;
+
+        let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+        let (url, recorded) = spawn_key_recorder().await;
+        let endpoint = create_keyed_endpoint(url);
+
+        let app: Router = endpoint
+            .attach_http_route(Router::new(), "keyed", CancellationToken::new())
+            .unwrap();
+        let response = app
+            .oneshot(
+                axum::http::Request::builder()
+                    .uri("/mcp/keyed/ping")
+                    .body(axum::body::Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert!(response.status().is_success());
+        assert_eq!(*recorded.lock().unwrap(), vec![Some("s3cret".to_string())]);
+    }
+
+    #[tokio::test]
+    async fn test_configured_headers_reach_client_requests() {
+        let (url, recorded) = spawn_key_recorder().await;
+        let endpoint = create_keyed_endpoint(url);
+
+        // The recorder can't complete a handshake, but it sees the attempt
+        assert!(endpoint.get_or_create_client().await.is_err());
+
+        let recorded = recorded.lock().unwrap();
+        assert!(!recorded.is_empty());
+        assert!(recorded.iter().all(|key| key.as_deref() == Some("s3cret")));
+    }
 }
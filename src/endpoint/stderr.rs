@@ -0,0 +1,98 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::task::JoinHandle;
+use tracing::warn;
+
+/// Stderr lines kept per local endpoint
+const STDERR_TAIL_LINES: usize = 20;
+
+/// How long a failed start waits for the rest of the child's stderr
+const STDERR_DRAIN_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// The most recent stderr lines of a local endpoint's child process
+#[derive(Clone, Default)]
+pub(crate) struct StderrTail {
+    lines: Arc<Mutex<VecDeque<String>>>,
+}
+
+impl StderrTail {
+    /// Read a new child's stderr in the background, logging each line and keeping the last ones
+    pub(crate) fn capture<R>(&self, name: &str, stderr: R) -> JoinHandle<()>
+    where
+        R: AsyncRead + Unpin + Send + 'static,
+    {
+        self.lines().clear();
+        let tail = self.clone();
+        let name = name.to_string();
+
+        tokio::spawn(async move {
+            // Split on raw bytes so non-UTF-8 output doesn't end the capture
+            let mut lines = BufReader::new(stderr).split(b'\n');
+            while let Ok(Some(line)) = lines.next_segment().await {
+                let line = String::from_utf8_lossy(&line).trim_end().to_string();
+                warn!("[{} stderr] {}", name, line);
+                tail.push(line);
+            }
+        })
+    }
+
+    /// Captured lines joined by newlines, if the child wrote anything
+    pub(crate) fn text(&self) -> Option<String> {
+        let lines = self.lines();
+        (!lines.is_empty()).then(|| Vec::from(lines.clone()).join("\n"))
+    }
+
+    fn push(&self, line: String) {
+        let mut lines = self.lines();
+        if lines.len() == STDERR_TAIL_LINES {
+            lines.pop_front();
+        }
+        lines.push_back(line);
+    }
+
+    fn lines(&self) -> std::sync::MutexGuard<'_, VecDeque<String>> {
+        self.lines
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Give a capture task a moment to read what an exiting child left on stderr
+pub(crate) async fn drain(capture: JoinHandle<()>) {
+    let _ = tokio::time::timeout(STDERR_DRAIN_TIMEOUT, capture).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_capture_keeps_last_lines() {
+        let output: String = (0..25).map(|i| format!("line {}\n", i)).collect();
+        let tail = StderrTail::default();
+
+        tail.capture("test", std::io::Cursor::new(output.into_bytes()))
+            .await
+            .unwrap();
+
+        let text = tail.text().unwrap();
+        assert_eq!(text.lines().count(), STDERR_TAIL_LINES);
+        assert!(text.starts_with("line 5\n"));
+        assert!(text.ends_with("line 24"));
+    }
+
+    #[tokio::test]
+    async fn test_capture_clears_previous_child_output() {
+        let tail = StderrTail::default();
+        tail.capture("test", std::io::Cursor::new(b"old\n".to_vec()))
+            .await
+            .unwrap();
+        tail.capture("test", std::io::Cursor::new(Vec::new()))
+            .await
+            .unwrap();
+
+        assert_eq!(tail.text(), None);
+    }
+}
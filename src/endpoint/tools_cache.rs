@@ -0,0 +1,92 @@
+use crate::mcp::ToolDefinition;
+use dashmap::DashMap;
+use std::time::{Duration, Instant};
+
+/// Recent unfiltered tool listings, keyed by endpoint name
+pub(crate) struct ToolsCache {
+    ttl: Duration,
+    entries: DashMap<String, (Instant, Vec<ToolDefinition>)>,
+}
+
+impl ToolsCache {
+    /// A zero `ttl` disables caching
+    pub(crate) fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: DashMap::new(),
+        }
+    }
+
+    /// The endpoint's last listing, if it is still fresh
+    pub(crate) fn get(&self, endpoint: &str) -> Option<Vec<ToolDefinition>> {
+        self.get_at(endpoint, Instant::now())
+    }
+
+    fn get_at(&self, endpoint: &str, now: Instant) -> Option<Vec<ToolDefinition>> {
+        let entry = self.entries.get(endpoint)?;
+        let (listed_at, tools) = entry.value();
+        (now.saturating_duration_since(*listed_at) < self.ttl).then(|| tools.clone())
+    }
+
+    pub(crate) fn insert(&self, endpoint: &str, tools: &[ToolDefinition]) {
+        if !self.ttl.is_zero() {
+            self.entries
+                .insert(endpoint.to_string(), (Instant::now(), tools.to_vec()));
+        }
+    }
+
+    pub(crate) fn invalidate(&self, endpoint: &str) {
+        self.entries.remove(endpoint);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn tools(names: &[&str]) -> Vec<ToolDefinition> {
+        names
+            .iter()
+            .map(|name| ToolDefinition {
+                name: name.to_string(),
+                description: None,
+                input_schema: json!({"type": "object"}),
+                output_schema: None,
+                annotations: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_entries_expire_after_ttl() {
+        let cache = ToolsCache::new(Duration::from_secs(10));
+        cache.insert("docs", &tools(&["search"]));
+
+        let now = Instant::now();
+        assert_eq!(cache.get_at("docs", now).unwrap()[0].name, "search");
+        assert!(
+            cache
+                .get_at("docs", now + Duration::from_secs(11))
+                .is_none()
+        );
+        assert!(cache.get_at("other", now).is_none());
+    }
+
+    #[test]
+    fn test_zero_ttl_disables_caching() {
+        let cache = ToolsCache::new(Duration::ZERO);
+        cache.insert("docs", &tools(&["search"]));
+
+        assert!(cache.get("docs").is_none());
+    }
+
+    #[test]
+    fn test_invalidate_drops_entry() {
+        let cache = ToolsCache::new(Duration::from_secs(10));
+        cache.insert("docs", &tools(&["search"]));
+        cache.invalidate("docs");
+
+        assert!(cache.get("docs").is_none());
+    }
+}
@@ -0,0 +1,384 @@
+use crate::config::{LifecycleEvent, WebhooksConfig};
+use crate::endpoint::registry::EndpointStatus;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{debug, warn};
+
+/// Timeout for a single webhook delivery
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Posts endpoint lifecycle events to a configured webhook URL
+/// Deliveries run in the background; failures are logged and never propagate
+#[derive(Clone)]
+pub(crate) struct WebhookNotifier {
+    client: reqwest::Client,
+    url: Arc<str>,
+    events: Arc<[LifecycleEvent]>,
+    debounce: Duration,
+    endpoints: Arc<Mutex<HashMap<String, EndpointState>>>,
+}
+
+/// What the notifier knows about one endpoint
+#[derive(Default)]
+struct EndpointState {
+    /// Status after the latest event, whether or not it was sent
+    status: Option<EndpointStatus>,
+    /// Event waiting out the debounce period
+    pending: Option<Pending>,
+    /// Bumped by every selected event, so only the last one's timer delivers
+    generation: u64,
+    /// The endpoint was removed; drop this state once the pending event is delivered
+    removed: bool,
+}
+
+struct Pending {
+    /// Status before the first event of the burst
+    old_status: Option<EndpointStatus>,
+    event: LifecycleEvent,
+    error: Option<String>,
+}
+
+/// Status an endpoint is in after `event`
+fn status_after(event: LifecycleEvent) -> EndpointStatus {
+    match event {
+        LifecycleEvent::Started | LifecycleEvent::Restarted => EndpointStatus::Running,
+        LifecycleEvent::Stopped => EndpointStatus::Stopped,
+        LifecycleEvent::Failed => EndpointStatus::Failed,
+    }
+}
+
+/// Webhook body reporting `pending` for an endpoint
+fn payload(endpoint: &str, pending: Pending) -> Value {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    json!({
+        "endpoint": endpoint,
+        "event": pending.event,
+        "old_status": pending.old_status,
+        "new_status": status_after(pending.event),
+        "error": pending.error,
+        "timestamp": timestamp,
+    })
+}
+
+impl WebhookNotifier {
+    /// Build a notifier, or `None` when no webhook URL is configured
+    pub(crate) fn from_config(config: &WebhooksConfig) -> Option<Self> {
+        let url = config.url.as_deref()?;
+        let client = reqwest::Client::builder()
+            .timeout(DELIVERY_TIMEOUT)
+            .build()
+            .inspect_err(|e| warn!("Webhooks disabled, failed to build HTTP client: {}", e))
+            .ok()?;
+
+        Some(Self {
+            client,
+            url: Arc::from(url),
+            events: Arc::from(config.events.as_slice()),
+            debounce: Duration::from_millis(config.debounce_ms),
+            endpoints: Arc::default(),
+        })
+    }
+
+    /// Send an event for an endpoint if it is one of the configured events
+    /// Selected events arriving within the debounce period are coalesced into the last one,
+    /// reported against the status from before the first; a failure is never coalesced
+    /// away, but sent as soon as a later event arrives
+    pub(crate) fn notify(&self, endpoint: &str, event: LifecycleEvent, error: Option<String>) {
+        let (generation, failure) = {
+            let mut endpoints = self.endpoints.lock().unwrap_or_else(|e| e.into_inner());
+            let state = endpoints.entry(endpoint.to_string()).or_default();
+            let old_status = state.status.replace(status_after(event));
+            state.removed = false;
+            if !self.events.contains(&event) {
+                return;
+            }
+
+            let (old_status, failure) = match state.pending.take() {
+                Some(pending) if pending.event == LifecycleEvent::Failed => {
+                    (old_status, Some(pending))
+                }
+                Some(pending) => (pending.old_status, None),
+                None => (old_status, None),
+            };
+            state.pending = Some(Pending {
+                old_status,
+                event,
+                error,
+            });
+            state.generation += 1;
+            (state.generation, failure)
+        };
+
+        let notifier = self.clone();
+        let endpoint = endpoint.to_string();
+        tokio::spawn(async move {
+            if let Some(failure) = failure {
+                notifier
+                    .deliver(&endpoint, payload(&endpoint, failure))
+                    .await;
+            }
+            tokio::time::sleep(notifier.debounce).await;
+            if let Some(payload) = notifier.take_pending(&endpoint, generation) {
+                notifier.deliver(&endpoint, payload).await;
+            }
+        });
+    }
+
+    /// Forget a removed endpoint, once any event still waiting out the debounce is delivered
+    pub(crate) fn forget(&self, endpoint: &str) {
+        let mut endpoints = self.endpoints.lock().unwrap_or_else(|e| e.into_inner());
+        match endpoints.get_mut(endpoint) {
+            Some(state) if state.pending.is_some() => state.removed = true,
+            _ => {
+                endpoints.remove(endpoint);
+            }
+        }
+    }
+
+    /// The payload for an endpoint's pending event, unless a later event superseded it
+    fn take_pending(&self, endpoint: &str, generation: u64) -> Option<Value> {
+        let mut endpoints = self.endpoints.lock().unwrap_or_else(|e| e.into_inner());
+        let state = endpoints.get_mut(endpoint)?;
+        if state.generation != generation {
+            return None;
+        }
+
+        let pending = state.pending.take();
+        if state.removed {
+            endpoints.remove(endpoint);
+        }
+        Some(payload(endpoint, pending?))
+    }
+
+    async fn deliver(&self, endpoint: &str, payload: Value) {
+        let event = payload["event"].clone();
+        let result = self
+            .client
+            .post(&*self.url)
+            .json(&payload)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+        match result {
+            Ok(_) => debug!("Delivered {} webhook for endpoint {}", event, endpoint),
+            Err(e) => warn!(
+                "Failed to deliver {} webhook for endpoint {}: {}",
+                event, endpoint, e
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Json, Router, extract::State, routing::post};
+    use serde_json::Value;
+    use tokio::sync::mpsc;
+
+    async fn spawn_receiver() -> (String, mpsc::UnboundedReceiver<Value>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let app =
+            Router::new()
+                .route(
+                    "/hook",
+                    post(
+                        |State(tx): State<mpsc::UnboundedSender<Value>>,
+                         Json(body): Json<Value>| async move {
+                            let _ = tx.send(body);
+                        },
+                    ),
+                )
+                .with_state(tx);
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        (format!("http://{}/hook", addr), rx)
+    }
+
+    #[test]
+    fn test_from_config_without_url() {
+        assert!(WebhookNotifier::from_config(&WebhooksConfig::default()).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_notify_posts_selected_events() {
+        let (url, mut rx) = spawn_receiver().await;
+        let notifier = WebhookNotifier::from_config(&WebhooksConfig {
+            url: Some(url),
+            events: vec![LifecycleEvent::Failed],
+            debounce_ms: 0,
+        })
+        .unwrap();
+
+        // Not selected, so never delivered
+        notifier.notify("time", LifecycleEvent::Started, None);
+        notifier.notify("time", LifecycleEvent::Failed, Some("boom".to_string()));
+
+        let body = receive(&mut rx).await;
+        assert_eq!(body["endpoint"], "time");
+        assert_eq!(body["event"], "failed");
+        assert_eq!(body["old_status"], "running");
+        assert_eq!(body["new_status"], "failed");
+        assert_eq!(body["error"], "boom");
+        assert!(rx.try_recv().is_err());
+    }
+
+    async fn receive(rx: &mut mpsc::UnboundedReceiver<Value>) -> Value {
+        tokio::time::timeout(Duration::from_secs(5), rx.recv())
+            .await
+            .unwrap()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_notify_debounces_flaps() {
+        let (url, mut rx) = spawn_receiver().await;
+        let notifier = WebhookNotifier::from_config(&WebhooksConfig {
+            url: Some(url),
+            debounce_ms: 100,
+            ..Default::default()
+        })
+        .unwrap();
+
+        notifier.notify("time", LifecycleEvent::Started, None);
+        let body = receive(&mut rx).await;
+        assert_eq!(body["old_status"], Value::Null);
+        assert_eq!(body["new_status"], "running");
+
+        // Stops and comes back within the debounce period: one delivery
+        notifier.notify("time", LifecycleEvent::Stopped, None);
+        notifier.notify("time", LifecycleEvent::Started, None);
+        let body = receive(&mut rx).await;
+        assert_eq!(body["event"], "started");
+        assert_eq!(body["old_status"], "running");
+        assert_eq!(body["new_status"], "running");
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(rx.try_recv().is_err());
+
+        // A failure isn't coalesced into the restart that follows it
+        notifier.notify("time", LifecycleEvent::Failed, Some("boom".to_string()));
+        notifier.notify("time", LifecycleEvent::Restarted, None);
+        let body = receive(&mut rx).await;
+        assert_eq!(body["event"], "failed");
+        assert_eq!(body["old_status"], "running");
+        let body = receive(&mut rx).await;
+        assert_eq!(body["event"], "restarted");
+        assert_eq!(body["old_status"], "failed");
+        assert_eq!(body["new_status"], "running");
+    }
+
+    #[tokio::test]
+    async fn test_unselected_event_keeps_pending_failure() {
+        let (url, mut rx) = spawn_receiver().await;
+        let notifier = WebhookNotifier::from_config(&WebhooksConfig {
+            url: Some(url),
+            events: vec![LifecycleEvent::Failed],
+            debounce_ms: 100,
+        })
+        .unwrap();
+
+        // Restarted by the supervisor within the debounce period; the crash is still reported
+        notifier.notify("time", LifecycleEvent::Started, None);
+        notifier.notify("time", LifecycleEvent::Failed, Some("boom".to_string()));
+        notifier.notify("time", LifecycleEvent::Started, None);
+
+        let body = receive(&mut rx).await;
+        assert_eq!(body["event"], "failed");
+        assert_eq!(body["old_status"], "running");
+        assert_eq!(body["error"], "boom");
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_forget_drops_endpoint_after_pending_delivery() {
+        let (url, mut rx) = spawn_receiver().await;
+        let notifier = WebhookNotifier::from_config(&WebhooksConfig {
+            url: Some(url),
+            debounce_ms: 50,
+            ..Default::default()
+        })
+        .unwrap();
+        let tracked = |endpoint: &str| notifier.endpoints.lock().unwrap().contains_key(endpoint);
+
+        notifier.notify("time", LifecycleEvent::Stopped, None);
+        notifier.forget("time");
+        assert!(tracked("time"));
+
+        // The stop still goes out, and then nothing is left behind
+        let body = receive(&mut rx).await;
+        assert_eq!(body["event"], "stopped");
+        assert!(!tracked("time"));
+
+        notifier.notify("other", LifecycleEvent::Started, None);
+        receive(&mut rx).await;
+        notifier.forget("other");
+        assert!(!tracked("other"));
+    }
+
+    #[tokio::test]
+    async fn test_failed_start_is_reported() {
+        use crate::config::{EndpointConfig, EndpointKindConfig};
+        use crate::endpoint::EndpointManager;
+
+        let (url, mut rx) = spawn_receiver().await;
+        let manager = EndpointManager::new().with_webhooks(&WebhooksConfig {
+            url: Some(url),
+            debounce_ms: 0,
+            ..Default::default()
+        });
+        let config = EndpointConfig {
+            name: "broken".to_string(),
+            aliases: Vec::new(),
+            endpoint_type: EndpointKindConfig::Local {
+                command: "echo".to_string(),
+                args: vec!["not mcp".to_string()],
+                env: Default::default(),
+                clean_env: false,
+                auto_start: false,
+                health_command: None,
+                cwd: None,
+                restart_on_failure: false,
+                startup_retry: None,
+                env_file: None,
+            },
+            tools: None,
+            rate_limit: None,
+            max_concurrent_requests: None,
+            enabled: true,
+            metadata: Default::default(),
+        };
+        manager.init_from_config(vec![config]).await.unwrap();
+
+        let err = manager.start_endpoint("broken").await.unwrap_err();
+
+        let body = receive(&mut rx).await;
+        assert_eq!(body["endpoint"], "broken");
+        assert_eq!(body["event"], "failed");
+        assert_eq!(body["new_status"], "failed");
+        assert_eq!(body["error"], err.to_string());
+        assert!(body["timestamp"].is_u64());
+    }
+
+    #[tokio::test]
+    async fn test_notify_tolerates_unreachable_url() {
+        let notifier = WebhookNotifier::from_config(&WebhooksConfig {
+            url: Some("http://127.0.0.1:9/hook".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+
+        // Delivery happens in the background and must not panic
+        notifier.notify("time", LifecycleEvent::Stopped, None);
+    }
+}
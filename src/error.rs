@@ -1,4 +1,6 @@
 use rmcp::ErrorData as McpError;
+use rmcp::ServiceError;
+use rmcp::model::ErrorCode;
 use std::fmt::Display;
 use std::time::Duration;
 use thiserror::Error;
@@ -15,6 +17,12 @@ pub enum ProxyError {
     #[error("Server already exists: {0}")]
     ServerAlreadyExists(String),
 
+    #[error("Endpoint limit reached: {0}")]
+    EndpointLimitReached(String),
+
+    #[error("Path conflict: {0}")]
+    PathConflict(String),
+
     #[error("Server is not running: {0}")]
     ServerNotRunning(String),
 
@@ -30,6 +38,18 @@ pub enum ProxyError {
     #[error("MCP protocol error: {0}")]
     McpProtocol(String),
 
+    /// The upstream didn't answer in time; kept apart from protocol errors so metrics can
+    /// count timeouts on their own, and answered with 504 rather than 502
+    #[error("MCP timeout: {0}")]
+    McpTimeout(String),
+
+    #[error("MCP server unavailable: {0}")]
+    McpUnavailable(String),
+
+    /// A JSON-RPC error returned by the upstream server, kept so its code and data reach the client
+    #[error("Failed to {action}: {error}")]
+    McpUpstream { action: String, error: McpError },
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
@@ -42,6 +62,23 @@ pub enum ProxyError {
     #[error("Tool not allowed: {0}")]
     ToolNotAllowed(String),
 
+    #[error("Tool not found: {0}")]
+    ToolNotFound(String),
+
+    /// Several endpoints offer the same tool name and the aggregate naming refuses to pick one
+    #[error("Tool name conflict: {0}")]
+    ToolNameConflict(String),
+
+    /// The upstream task is still running, so its result can't be fetched yet
+    #[error("Task not finished: {0}")]
+    TaskNotFinished(String),
+
+    #[error("Rate limit exceeded for endpoint: {endpoint}")]
+    RateLimited {
+        endpoint: String,
+        retry_after: Duration,
+    },
+
     #[error("Internal error: {0}")]
     Internal(String),
 }
@@ -56,19 +93,64 @@ impl ProxyError {
             ProxyError::Config(_) => StatusCode::INTERNAL_SERVER_ERROR,
             ProxyError::ServerNotFound(_) => StatusCode::NOT_FOUND,
             ProxyError::ServerAlreadyExists(_) => StatusCode::CONFLICT,
+            ProxyError::EndpointLimitReached(_) => StatusCode::CONFLICT,
+            ProxyError::PathConflict(_) => StatusCode::CONFLICT,
             ProxyError::ServerNotRunning(_) => StatusCode::SERVICE_UNAVAILABLE,
             ProxyError::ServerAlreadyRunning(_) => StatusCode::CONFLICT,
             ProxyError::ServerRuntimeFailed(_) => StatusCode::SERVICE_UNAVAILABLE,
             ProxyError::ServerStartFailed(_) => StatusCode::INTERNAL_SERVER_ERROR,
             ProxyError::McpProtocol(_) => StatusCode::BAD_GATEWAY,
+            ProxyError::McpTimeout(_) => StatusCode::GATEWAY_TIMEOUT,
+            ProxyError::McpUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            ProxyError::McpUpstream { error, .. } => upstream_status(error),
             ProxyError::Io(_) => StatusCode::INTERNAL_SERVER_ERROR,
             ProxyError::Json(_) => StatusCode::BAD_REQUEST,
             ProxyError::InvalidRequest(_) => StatusCode::BAD_REQUEST,
             ProxyError::ToolNotAllowed(_) => StatusCode::FORBIDDEN,
+            ProxyError::ToolNotFound(_) => StatusCode::NOT_FOUND,
+            ProxyError::ToolNameConflict(_) => StatusCode::CONFLICT,
+            ProxyError::TaskNotFinished(_) => StatusCode::CONFLICT,
+            ProxyError::RateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
             ProxyError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
 
+    /// Whether the upstream couldn't be reached or didn't answer in time, so trying again may help
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            ProxyError::McpUnavailable(_) | ProxyError::McpTimeout(_)
+        )
+    }
+
+    /// Variant name, used to label error metrics
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ProxyError::Config(_) => "Config",
+            ProxyError::ServerNotFound(_) => "ServerNotFound",
+            ProxyError::ServerAlreadyExists(_) => "ServerAlreadyExists",
+            ProxyError::EndpointLimitReached(_) => "EndpointLimitReached",
+            ProxyError::PathConflict(_) => "PathConflict",
+            ProxyError::ServerNotRunning(_) => "ServerNotRunning",
+            ProxyError::ServerAlreadyRunning(_) => "ServerAlreadyRunning",
+            ProxyError::ServerRuntimeFailed(_) => "ServerRuntimeFailed",
+            ProxyError::ServerStartFailed(_) => "ServerStartFailed",
+            ProxyError::McpProtocol(_) => "McpProtocol",
+            ProxyError::McpTimeout(_) => "McpTimeout",
+            ProxyError::McpUnavailable(_) => "McpUnavailable",
+            ProxyError::McpUpstream { .. } => "McpUpstream",
+            ProxyError::Io(_) => "Io",
+            ProxyError::Json(_) => "Json",
+            ProxyError::InvalidRequest(_) => "InvalidRequest",
+            ProxyError::ToolNotAllowed(_) => "ToolNotAllowed",
+            ProxyError::ToolNotFound(_) => "ToolNotFound",
+            ProxyError::ToolNameConflict(_) => "ToolNameConflict",
+            ProxyError::TaskNotFinished(_) => "TaskNotFinished",
+            ProxyError::RateLimited { .. } => "RateLimited",
+            ProxyError::Internal(_) => "Internal",
+        }
+    }
+
     pub fn config(message: impl Into<String>) -> Self {
         ProxyError::Config(message.into())
     }
@@ -81,6 +163,20 @@ impl ProxyError {
         ProxyError::ServerAlreadyExists(name.into())
     }
 
+    pub fn endpoint_limit_reached(name: impl Display, max: usize) -> Self {
+        ProxyError::EndpointLimitReached(format!(
+            "cannot add '{}', max_endpoints = {} reached",
+            name, max
+        ))
+    }
+
+    pub fn path_conflict(path: impl Display, owner: impl Display) -> Self {
+        ProxyError::PathConflict(format!(
+            "/mcp/{} is already used by endpoint '{}'",
+            path, owner
+        ))
+    }
+
     pub fn server_not_running(name: impl Into<String>) -> Self {
         ProxyError::ServerNotRunning(name.into())
     }
@@ -101,8 +197,19 @@ impl ProxyError {
         ProxyError::InvalidRequest(format!("Invalid request format: {}", err))
     }
 
+    pub fn tool_name_conflict(name: impl Display, endpoints: impl Display) -> Self {
+        ProxyError::ToolNameConflict(format!(
+            "tool '{}' is offered by more than one endpoint ({}); call it as <endpoint>:{} or rename it in their tool filters",
+            name, endpoints, name
+        ))
+    }
+
+    pub fn task_not_finished(task_id: impl Display, status: impl Display) -> Self {
+        ProxyError::TaskNotFinished(format!("task '{}' is {}", task_id, status))
+    }
+
     pub fn mcp_timeout(timeout: Duration) -> Self {
-        ProxyError::McpProtocol(format!("MCP request timed out after {:?}", timeout))
+        ProxyError::McpTimeout(format!("request timed out after {:?}", timeout))
     }
 
     pub fn mcp_handshake_timeout(timeout: Duration, server_name: &str, url: Option<&str>) -> Self {
@@ -116,7 +223,18 @@ impl ProxyError {
                 timeout, server_name
             ),
         };
-        ProxyError::McpProtocol(message)
+        ProxyError::McpTimeout(message)
+    }
+
+    pub fn mcp_connect_timeout(timeout: Duration, server_name: &str, url: &str) -> Self {
+        ProxyError::McpTimeout(format!(
+            "Connecting to {} timed out after {:?} for server: {}",
+            url, timeout, server_name
+        ))
+    }
+
+    pub fn mcp_unavailable(message: impl Into<String>) -> Self {
+        ProxyError::McpUnavailable(message.into())
     }
 
     pub fn mcp_cancelled(action: &str, server_name: &str) -> Self {
@@ -130,8 +248,14 @@ impl ProxyError {
         ProxyError::McpProtocol(message.into())
     }
 
-    pub fn mcp_service_error(action: &str, err: impl Display) -> Self {
-        ProxyError::McpProtocol(format!("Failed to {}: {}", action, err))
+    pub fn mcp_service_error(action: &str, err: ServiceError) -> Self {
+        match err {
+            ServiceError::McpError(error) => ProxyError::McpUpstream {
+                action: action.to_string(),
+                error,
+            },
+            err => ProxyError::McpProtocol(format!("Failed to {}: {}", action, err)),
+        }
     }
 
     pub fn mcp_client_stop_failed(err: impl Display) -> Self {
@@ -139,10 +263,32 @@ impl ProxyError {
     }
 
     pub fn to_mcp_error(&self, context: &str) -> McpError {
-        McpError::internal_error(format!("Failed to {}: {}", context, self), None)
+        match self {
+            // Pass the upstream's own error through unchanged
+            ProxyError::McpUpstream { error, .. } => error.clone(),
+            _ => McpError::internal_error(format!("Failed to {}: {}", context, self), None),
+        }
     }
 }
 
+/// HTTP status for a JSON-RPC error returned by the upstream
+/// Codes without a clear HTTP counterpart stay a 502
+fn upstream_status(error: &McpError) -> axum::http::StatusCode {
+    use axum::http::StatusCode;
+    match error.code {
+        ErrorCode::METHOD_NOT_FOUND | ErrorCode::RESOURCE_NOT_FOUND => StatusCode::NOT_FOUND,
+        // rmcp and most servers report an unknown tool as invalid params
+        ErrorCode::INVALID_PARAMS if is_unknown_tool(&error.message) => StatusCode::NOT_FOUND,
+        ErrorCode::INVALID_PARAMS | ErrorCode::INVALID_REQUEST => StatusCode::BAD_REQUEST,
+        _ => StatusCode::BAD_GATEWAY,
+    }
+}
+
+fn is_unknown_tool(message: &str) -> bool {
+    let message = message.to_lowercase();
+    message.contains("tool not found") || message.contains("unknown tool")
+}
+
 // Implement conversion from anyhow::Error for convenience
 impl From<anyhow::Error> for ProxyError {
     fn from(err: anyhow::Error) -> Self {
@@ -161,15 +307,48 @@ impl From<anyhow::Error> for ProxyError {
     }
 }
 
-impl axum::response::IntoResponse for ProxyError {
-    fn into_response(self) -> axum::response::Response {
-        let status = self.status_code();
-        let body = serde_json::json!({
+impl ProxyError {
+    /// Body of the error response: message, status code, and the upstream's error if any
+    pub(crate) fn to_json(&self) -> serde_json::Value {
+        let mut body = serde_json::json!({
             "error": self.to_string(),
-            "code": status.as_u16(),
+            "code": self.status_code().as_u16(),
         });
+        if let ProxyError::McpUpstream { error, .. } = self {
+            body["mcp"] = serde_json::json!({
+                "code": error.code.0,
+                "message": error.message,
+                "data": error.data,
+            });
+        }
+        if let Some(secs) = self.retry_after_secs() {
+            body["retry_after_secs"] = serde_json::json!(secs);
+        }
+        body
+    }
+
+    /// Whole seconds, rounded up so a client retrying on time gets a token
+    fn retry_after_secs(&self) -> Option<u64> {
+        match self {
+            ProxyError::RateLimited { retry_after, .. } => {
+                Some(retry_after.as_secs_f64().ceil().max(1.0) as u64)
+            }
+            _ => None,
+        }
+    }
+}
 
-        (status, axum::Json(body)).into_response()
+impl axum::response::IntoResponse for ProxyError {
+    fn into_response(self) -> axum::response::Response {
+        let status = self.status_code();
+        let body = axum::Json(self.to_json());
+        match self.retry_after_secs() {
+            Some(secs) => {
+                let headers = [(axum::http::header::RETRY_AFTER, secs.to_string())];
+                (status, headers, body).into_response()
+            }
+            None => (status, body).into_response(),
+        }
     }
 }
 
@@ -192,6 +371,14 @@ mod tests {
             ProxyError::ServerAlreadyExists("test".to_string()).status_code(),
             StatusCode::CONFLICT
         );
+        assert_eq!(
+            ProxyError::endpoint_limit_reached("test", 1).status_code(),
+            StatusCode::CONFLICT
+        );
+        assert_eq!(
+            ProxyError::path_conflict("test", "other").status_code(),
+            StatusCode::CONFLICT
+        );
         assert_eq!(
             ProxyError::ServerNotRunning("test".to_string()).status_code(),
             StatusCode::SERVICE_UNAVAILABLE
@@ -216,6 +403,18 @@ mod tests {
             ProxyError::McpProtocol("test".to_string()).status_code(),
             StatusCode::BAD_GATEWAY
         );
+        assert_eq!(
+            ProxyError::mcp_timeout(Duration::from_secs(30)).status_code(),
+            StatusCode::GATEWAY_TIMEOUT
+        );
+        assert_eq!(
+            ProxyError::mcp_handshake_timeout(Duration::from_secs(30), "test", None).status_code(),
+            StatusCode::GATEWAY_TIMEOUT
+        );
+        assert_eq!(
+            ProxyError::mcp_unavailable("connection refused").status_code(),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
         assert_eq!(
             ProxyError::InvalidRequest("test".to_string()).status_code(),
             StatusCode::BAD_REQUEST
@@ -224,6 +423,49 @@ mod tests {
             ProxyError::Internal("test".to_string()).status_code(),
             StatusCode::INTERNAL_SERVER_ERROR
         );
+        assert_eq!(
+            ProxyError::ToolNotFound("test".to_string()).status_code(),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            ProxyError::tool_name_conflict("echo", "a, b").status_code(),
+            StatusCode::CONFLICT
+        );
+        assert_eq!(
+            ProxyError::task_not_finished("t-1", "working").status_code(),
+            StatusCode::CONFLICT
+        );
+        assert_eq!(
+            ProxyError::RateLimited {
+                endpoint: "test".to_string(),
+                retry_after: Duration::from_millis(200),
+            }
+            .status_code(),
+            StatusCode::TOO_MANY_REQUESTS
+        );
+    }
+
+    #[test]
+    fn test_upstream_error_codes_map_to_http_statuses() {
+        let status = |code: i32, message: &str| {
+            ProxyError::McpUpstream {
+                action: "call tool".to_string(),
+                error: McpError::new(ErrorCode(code), message.to_string(), None),
+            }
+            .status_code()
+        };
+
+        assert_eq!(status(-32601, "method not found"), StatusCode::NOT_FOUND);
+        assert_eq!(status(-32002, "no such resource"), StatusCode::NOT_FOUND);
+        assert_eq!(status(-32602, "tool not found"), StatusCode::NOT_FOUND);
+        assert_eq!(
+            status(-32602, "Unknown tool: search"),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(status(-32602, "bad city"), StatusCode::BAD_REQUEST);
+        assert_eq!(status(-32600, "invalid request"), StatusCode::BAD_REQUEST);
+        assert_eq!(status(-32603, "internal error"), StatusCode::BAD_GATEWAY);
+        assert_eq!(status(-32000, "server defined"), StatusCode::BAD_GATEWAY);
     }
 
     #[test]
@@ -232,6 +474,28 @@ mod tests {
         assert_eq!(err.to_string(), "Server not found: myserver");
     }
 
+    #[test]
+    fn test_error_kind_names_variant() {
+        assert_eq!(
+            ProxyError::mcp_timeout(Duration::from_secs(1)).kind(),
+            "McpTimeout"
+        );
+        assert_eq!(ProxyError::mcp_protocol("bad").kind(), "McpProtocol");
+        assert_eq!(ProxyError::mcp_unavailable("down").kind(), "McpUnavailable");
+        assert_eq!(
+            ProxyError::endpoint_limit_reached("docs", 1).kind(),
+            "EndpointLimitReached"
+        );
+    }
+
+    #[test]
+    fn test_only_connection_and_timeout_errors_are_transient() {
+        assert!(ProxyError::mcp_timeout(Duration::from_secs(1)).is_transient());
+        assert!(ProxyError::mcp_unavailable("connection refused").is_transient());
+        assert!(!ProxyError::mcp_protocol("bad response").is_transient());
+        assert!(!ProxyError::ToolNotFound("search".to_string()).is_transient());
+    }
+
     #[test]
     fn test_error_from_io() {
         let io_err = std::io::Error::new(std::io::ErrorKind::NotFound, "file not found");
@@ -265,4 +529,47 @@ mod tests {
 
         assert_eq!(response.status(), StatusCode::NOT_FOUND);
     }
+
+    #[test]
+    fn test_rate_limited_response_sets_retry_after() {
+        use axum::response::IntoResponse;
+
+        let err = ProxyError::RateLimited {
+            endpoint: "time".to_string(),
+            retry_after: Duration::from_millis(1500),
+        };
+        let response = err.into_response();
+
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[axum::http::header::RETRY_AFTER], "2");
+    }
+
+    #[tokio::test]
+    async fn test_upstream_error_response_carries_mcp_fields() {
+        use axum::response::IntoResponse;
+
+        let upstream =
+            McpError::invalid_params("missing city", Some(serde_json::json!({"field": "city"})));
+        let err = ProxyError::mcp_service_error("call tool", ServiceError::McpError(upstream));
+        assert_eq!(err.kind(), "McpUpstream");
+
+        let response = err.into_response();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["code"], 400);
+        assert_eq!(json["mcp"]["code"], -32602);
+        assert_eq!(json["mcp"]["message"], "missing city");
+        assert_eq!(json["mcp"]["data"]["field"], "city");
+    }
+
+    #[test]
+    fn test_non_mcp_service_error_stays_protocol() {
+        let err = ProxyError::mcp_service_error("call tool", ServiceError::TransportClosed);
+        assert!(matches!(err, ProxyError::McpProtocol(_)));
+        assert!(err.to_mcp_error("call tool").message.contains("call tool"));
+    }
 }
@@ -3,6 +3,8 @@ pub mod config;
 pub mod endpoint;
 pub(crate) mod error;
 pub(crate) mod mcp;
+pub mod metrics;
 pub mod routing;
 
+pub use endpoint::{EndpointInfo, EndpointManager, EndpointStatus};
 pub use error::{ProxyError, Result};
@@ -1,6 +1,6 @@
 use anyhow::{Context, Result};
-use clap::Parser;
-use rusted_tools::{api, config};
+use clap::{Parser, Subcommand, ValueEnum};
+use rusted_tools::{api, config, routing};
 use std::path::PathBuf;
 use tracing::info;
 
@@ -9,19 +9,51 @@ use tracing::info;
 #[command(about = "High-performance MCP proxy server", long_about = None)]
 #[command(version)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Commands>,
+
     /// Path to configuration file
-    #[arg(short, long, default_value = "config.toml")]
+    #[arg(short, long, default_value = "config.toml", global = true)]
     config: PathBuf,
 
     /// Override log level (trace, debug, info, warn, error)
-    #[arg(long)]
+    #[arg(long, global = true)]
     log_level: Option<String>,
 
     /// Override log format (pretty, json)
-    #[arg(long)]
+    #[arg(long, global = true)]
     log_format: Option<String>,
 }
 
+#[derive(Subcommand, Debug)]
+enum Commands {
+    /// Run the proxy server (the default)
+    Run,
+    /// Check the configuration and exit without starting the server
+    Validate,
+    /// Print the endpoint routes the configuration would mount, without starting the server
+    List {
+        #[arg(long, value_enum, default_value_t = ListFormat::Table)]
+        format: ListFormat,
+    },
+    /// Write a commented starter configuration
+    Init {
+        /// Where to write the configuration
+        #[arg(short, long, default_value = "config.toml")]
+        output: PathBuf,
+
+        /// Overwrite the file if it already exists
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum ListFormat {
+    Table,
+    Json,
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     // Initialize rustls crypto provider for axum-reverse-proxy
@@ -34,6 +66,12 @@ async fn main() -> Result<()> {
     // Parse CLI arguments
     let cli = Cli::parse();
 
+    if let Some(Commands::Init { output, force }) = &cli.command {
+        config::write_starter_config(output, *force)?;
+        println!("Wrote starter configuration to {}", output.display());
+        return Ok(());
+    }
+
     // Load configuration
     let mut config = config::load_config(&cli.config).with_context(|| {
         format!(
@@ -42,9 +80,21 @@ async fn main() -> Result<()> {
         )
     })?;
 
+    match cli.command {
+        Some(Commands::Validate) => {
+            print_validation_summary(&cli.config, &config);
+            return Ok(());
+        }
+        Some(Commands::List { format }) => {
+            print_routes(&config, format)?;
+            return Ok(());
+        }
+        Some(Commands::Run | Commands::Init { .. }) | None => {}
+    }
+
     // Apply CLI overrides
     if let Some(log_level) = cli.log_level {
-        config.logging.level = log_level;
+        config.logging.level = config::normalize_log_level(&log_level);
     }
     if let Some(log_format) = cli.log_format {
         config.logging.format = log_format;
@@ -88,6 +138,49 @@ fn init_logging(config: &config::LoggingConfig) -> Result<()> {
     Ok(())
 }
 
+fn print_validation_summary(path: &std::path::Path, config: &config::AppConfig) {
+    println!("Configuration OK: {}", path.display());
+    println!("Endpoints: {}", config.endpoints.len());
+    for endpoint in &config.endpoints {
+        let kind = match endpoint.endpoint_type {
+            config::EndpointKindConfig::Local { .. } => "local",
+            config::EndpointKindConfig::Remote { .. } => "remote",
+        };
+        println!("  /mcp/{} ({})", endpoint.name, kind);
+    }
+
+    let warnings = config::config_warnings(config);
+    if !warnings.is_empty() {
+        println!("Warnings: {}", warnings.len());
+        for warning in warnings {
+            println!("  - {}", warning);
+        }
+    }
+}
+
+fn print_routes(config: &config::AppConfig, format: ListFormat) -> Result<()> {
+    let routes = routing::routes_from_config(config);
+    if let ListFormat::Json = format {
+        println!("{}", serde_json::to_string_pretty(&routes)?);
+        return Ok(());
+    }
+
+    println!(
+        "{:<20} {:<7} {:<26} {:<11} TOOL FILTER",
+        "NAME", "TYPE", "PATH", "AUTO START"
+    );
+    for route in routes {
+        let auto_start = route
+            .auto_start
+            .map_or("-", |a| if a { "yes" } else { "no" });
+        println!(
+            "{:<20} {:<7} {:<26} {:<11} {}",
+            route.name, route.endpoint_type, route.path, auto_start, route.tool_filter
+        );
+    }
+    Ok(())
+}
+
 fn print_banner(config: &config::AppConfig) {
     let version = env!("CARGO_PKG_VERSION");
     let authors = env!("CARGO_PKG_AUTHORS");
@@ -0,0 +1,276 @@
+// Unified MCP server exposing the tools of every running endpoint
+// Tool names follow `mcp.aggregate_tool_names`: namespaced as `{path}:{tool}` and routed back
+// by that prefix, or kept as they are with collisions prefixed or rejected
+
+use axum::http::HeaderMap;
+use rmcp::model::{
+    CallToolRequestParams, CallToolResult, ListToolsResult, PaginatedRequestParams,
+    ServerCapabilities, ServerInfo,
+};
+use rmcp::service::RequestContext;
+use rmcp::{ErrorData as McpError, RoleServer, ServerHandler};
+use tracing::debug;
+
+use super::bridge::{build_rmcp_result, build_rmcp_tool};
+use super::types::{ToolCallRequest, ToolCallResponse, ToolDefinition};
+use crate::api::handlers::{self, ApiState};
+use crate::config::AggregateToolNames;
+use crate::error::{ProxyError, Result};
+
+/// Separator between the endpoint path and the tool name
+pub(crate) const NAMESPACE_SEPARATOR: char = ':';
+
+/// Build the namespaced name of a tool exposed by the endpoint at `path`
+pub(crate) fn namespaced_tool_name(path: &str, tool: &str) -> String {
+    format!("{}{}{}", path, NAMESPACE_SEPARATOR, tool)
+}
+
+/// Split a namespaced tool name into endpoint path and tool name
+pub(crate) fn split_namespaced_tool_name(name: &str) -> Result<(&str, &str)> {
+    match name.split_once(NAMESPACE_SEPARATOR) {
+        Some((path, tool)) if !path.is_empty() && !tool.is_empty() => Ok((path, tool)),
+        _ => Err(ProxyError::invalid_request(format!(
+            "tool name '{}' must have the form '<endpoint>{}<tool>'",
+            name, NAMESPACE_SEPARATOR
+        ))),
+    }
+}
+
+/// MCP server that merges all running endpoints into one
+/// Requests go through the same filters, limits, timeouts and tools cache as the REST API
+#[derive(Clone)]
+pub(crate) struct AggregateBridge {
+    state: ApiState,
+}
+
+impl AggregateBridge {
+    pub(crate) fn new(state: ApiState) -> Self {
+        Self { state }
+    }
+
+    /// List the filtered tools of every running endpoint under aggregate names
+    /// Endpoints that aren't running or fail to list are skipped
+    pub(crate) async fn list_namespaced_tools(&self) -> Result<Vec<ToolDefinition>> {
+        let (tools, _warnings) =
+            handlers::list_namespaced_tools(&self.state, &HeaderMap::new()).await?;
+        Ok(tools
+            .into_iter()
+            .map(|namespaced| namespaced.tool)
+            .collect())
+    }
+
+    /// Route a tool call to the endpoint that owns it
+    pub(crate) async fn call_namespaced_tool(
+        &self,
+        mut request: ToolCallRequest,
+    ) -> Result<ToolCallResponse> {
+        let (path, tool) =
+            handlers::resolve_namespaced_tool(&self.state, &HeaderMap::new(), &request.name)
+                .await?;
+
+        request.name = tool;
+        let forwarded =
+            handlers::call_endpoint_tool(&self.state, &path, &HeaderMap::new(), request).await?;
+        Ok(forwarded.response)
+    }
+}
+
+impl ServerHandler for AggregateBridge {
+    fn get_info(&self) -> ServerInfo {
+        let naming = match self.state.aggregate_tool_names {
+            AggregateToolNames::Namespaced => {
+                format!("are named <endpoint>{}<tool>", NAMESPACE_SEPARATOR)
+            }
+            _ => format!(
+                "keep their names unless several endpoints offer one, which is then named <endpoint>{}<tool>",
+                NAMESPACE_SEPARATOR
+            ),
+        };
+        ServerInfo {
+            instructions: Some(format!(
+                "Unified proxy over all rusted-tools endpoints; tools {}",
+                naming
+            )),
+            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            ..Default::default()
+        }
+    }
+
+    async fn list_tools(
+        &self,
+        _params: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> std::result::Result<ListToolsResult, McpError> {
+        debug!("Aggregate server listing tools");
+        let tools = self
+            .list_namespaced_tools()
+            .await
+            .map_err(|e| e.to_mcp_error("list tools"))?;
+
+        Ok(ListToolsResult {
+            meta: None,
+            tools: tools.into_iter().map(build_rmcp_tool).collect(),
+            next_cursor: None,
+        })
+    }
+
+    async fn call_tool(
+        &self,
+        params: CallToolRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        debug!("Aggregate server calling tool: {}", params.name);
+
+        let request = ToolCallRequest {
+            name: params.name.to_string(),
+            arguments: serde_json::Value::Object(params.arguments.unwrap_or_default()),
+            task: None,
+        };
+
+        match self.call_namespaced_tool(request).await {
+            Ok(response) => Ok(build_rmcp_result(response)),
+            Err(e @ ProxyError::InvalidRequest(_)) => {
+                Err(McpError::invalid_params(e.to_string(), None))
+            }
+            Err(e) => Err(e.to_mcp_error("call tool")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{EndpointConfig, EndpointKindConfig};
+    use crate::endpoint::EndpointManager;
+    use crate::routing::PathRouter;
+    use serde_json::json;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    /// Bridge over one local endpoint named `local-stub` running `sh -c script`
+    async fn create_bridge_with(
+        script: &str,
+        auto_start: bool,
+        timeout: Duration,
+    ) -> AggregateBridge {
+        let manager = Arc::new(EndpointManager::new());
+        manager
+            .init_from_config(vec![EndpointConfig {
+                name: "local-stub".to_string(),
+                aliases: Vec::new(),
+                endpoint_type: EndpointKindConfig::Local {
+                    command: "sh".to_string(),
+                    args: vec!["-c".to_string(), script.to_string()],
+                    env: Default::default(),
+                    clean_env: false,
+                    auto_start,
+                    health_command: None,
+                    cwd: None,
+                    restart_on_failure: false,
+                    startup_retry: None,
+                    env_file: None,
+                },
+                tools: None,
+                rate_limit: None,
+                max_concurrent_requests: None,
+                enabled: true,
+                metadata: Default::default(),
+            }])
+            .await
+            .unwrap();
+
+        AggregateBridge::new(ApiState {
+            metrics: manager.metrics(),
+            router: Arc::new(PathRouter::new(manager.clone())),
+            manager,
+            endpoint_routes: Default::default(),
+            mcp_request_timeout: timeout,
+            validate_output: false,
+            validate_arguments: false,
+            max_json_depth: 64,
+            log_tool_arguments: false,
+            aggregate_tool_names: AggregateToolNames::Namespaced,
+        })
+    }
+
+    async fn create_test_bridge() -> AggregateBridge {
+        create_bridge_with("cat", false, Duration::from_secs(30)).await
+    }
+
+    /// Answers the handshake, then never replies
+    const HANGING_STUB_MCP_SERVER: &str = r#"
+while read -r line; do
+  id=$(echo "$line" | sed -n 's/.*"id":\([0-9]*\).*/\1/p')
+  case "$line" in
+    *notifications/*)
+      ;;
+    *initialize*)
+      echo "{\"jsonrpc\":\"2.0\",\"id\":$id,\"result\":{\"protocolVersion\":\"2025-06-18\",\"capabilities\":{\"tools\":{}},\"serverInfo\":{\"name\":\"stub\",\"version\":\"0.0.0\"}}}"
+      ;;
+  esac
+done
+"#;
+
+    fn create_request(name: &str) -> ToolCallRequest {
+        ToolCallRequest {
+            name: name.to_string(),
+            arguments: json!({}),
+            task: None,
+        }
+    }
+
+    #[test]
+    fn test_split_namespaced_tool_name() {
+        assert_eq!(
+            split_namespaced_tool_name("time:get_current_time").unwrap(),
+            ("time", "get_current_time")
+        );
+        assert_eq!(split_namespaced_tool_name("a:b:c").unwrap(), ("a", "b:c"));
+        assert!(split_namespaced_tool_name("no_prefix").is_err());
+        assert!(split_namespaced_tool_name(":tool").is_err());
+        assert!(split_namespaced_tool_name("time:").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_list_skips_stopped_endpoints() {
+        let bridge = create_test_bridge().await;
+        assert!(bridge.list_namespaced_tools().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_call_routes_by_prefix() {
+        let bridge = create_test_bridge().await;
+
+        let result = bridge.call_namespaced_tool(create_request("tool")).await;
+        assert!(matches!(result, Err(ProxyError::InvalidRequest(_))));
+
+        let result = bridge
+            .call_namespaced_tool(create_request("missing:tool"))
+            .await;
+        assert!(matches!(result, Err(ProxyError::ServerNotFound(_))));
+
+        let result = bridge
+            .call_namespaced_tool(create_request("local-stub:tool"))
+            .await;
+        assert!(matches!(result, Err(ProxyError::ServerNotRunning(_))));
+    }
+
+    #[tokio::test]
+    async fn test_hung_endpoint_times_out_instead_of_stalling() {
+        let bridge =
+            create_bridge_with(HANGING_STUB_MCP_SERVER, true, Duration::from_millis(200)).await;
+
+        let tools = tokio::time::timeout(Duration::from_secs(5), bridge.list_namespaced_tools())
+            .await
+            .expect("listing stalled on a hung endpoint");
+        assert!(tools.unwrap().is_empty());
+
+        let result = tokio::time::timeout(
+            Duration::from_secs(5),
+            bridge.call_namespaced_tool(create_request("local-stub:tool")),
+        )
+        .await
+        .expect("call stalled on a hung endpoint");
+        assert!(matches!(result, Err(ProxyError::McpTimeout(_))));
+    }
+}
@@ -3,8 +3,10 @@
 // For remote HTTP/SSE endpoints, use axum-reverse-proxy instead (see api/mod.rs)
 
 use rmcp::model::{
-    CallToolRequestParams, CallToolResult, ListToolsResult, PaginatedRequestParams,
-    ServerCapabilities, ServerInfo,
+    AnnotateAble, CallToolRequestParams, CallToolResult, GetPromptRequestParams, GetPromptResult,
+    ListPromptsResult, ListResourcesResult, ListToolsResult, PaginatedRequestParams,
+    PromptMessageRole, RawResource, ReadResourceRequestParams, ReadResourceResult,
+    ResourceContents, ServerCapabilities, ServerInfo,
 };
 use rmcp::service::RequestContext;
 use rmcp::{ErrorData as McpError, RoleServer, ServerHandler};
@@ -12,7 +14,12 @@ use std::sync::Arc;
 use tracing::{debug, warn};
 
 use super::client::McpClient;
-use super::types::ToolDefinition;
+use super::types::{
+    PromptDefinition, PromptGetRequest, PromptGetResponse, PromptRole, ResourceContent,
+    ResourceDefinition, ToolCallResponse, ToolDefinition,
+};
+use crate::config::ToolFilter;
+use crate::error::ProxyError;
 
 /// MCP Server implementation that bridges stdio-based local MCP to HTTP/SSE
 /// This translates HTTP/SSE requests into stdio protocol for local endpoints.
@@ -21,13 +28,20 @@ use super::types::ToolDefinition;
 pub(crate) struct StdioBridge {
     client: Arc<McpClient>,
     server_name: String,
+    /// Only renames and description overrides apply here; include/exclude are enforced by the REST API
+    tools: Option<Arc<ToolFilter>>,
 }
 
 impl StdioBridge {
-    pub(crate) fn new(client: Arc<McpClient>, server_name: String) -> Self {
+    pub(crate) fn new(
+        client: Arc<McpClient>,
+        server_name: String,
+        tools: Option<Arc<ToolFilter>>,
+    ) -> Self {
         Self {
             client,
             server_name,
+            tools,
         }
     }
 }
@@ -37,7 +51,11 @@ impl ServerHandler for StdioBridge {
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
             instructions: Some(format!("Proxy to {} MCP server", self.server_name)),
-            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            capabilities: ServerCapabilities::builder()
+                .enable_tools()
+                .enable_resources()
+                .enable_prompts()
+                .build(),
             ..Default::default()
         }
     }
@@ -56,7 +74,14 @@ impl ServerHandler for StdioBridge {
             .map_err(|e| e.to_mcp_error("list tools"))?;
 
         // Convert our ToolDefinition format to rmcp::model::Tool
-        let mcp_tools: Vec<rmcp::model::Tool> = tools.into_iter().map(build_rmcp_tool).collect();
+        let mcp_tools: Vec<rmcp::model::Tool> = tools
+            .into_iter()
+            .map(|tool| match &self.tools {
+                Some(filter) => filter.expose(tool),
+                None => tool,
+            })
+            .map(build_rmcp_tool)
+            .collect();
 
         Ok(ListToolsResult {
             meta: None,
@@ -73,9 +98,19 @@ impl ServerHandler for StdioBridge {
     ) -> Result<CallToolResult, McpError> {
         debug!("Bridge server calling tool: {}", params.name);
 
+        let name = match &self.tools {
+            Some(filter) => filter.upstream_name(&params.name).ok_or_else(|| {
+                ProxyError::ToolNotAllowed(params.name.to_string()).to_mcp_error("call tool")
+            })?,
+            None => &params.name,
+        };
+
+        // Task-augmented calls can't be answered through `call_tool`, so the bridge
+        // always executes synchronously
         let tool_request = super::types::ToolCallRequest {
-            name: params.name.to_string(),
+            name: name.to_string(),
             arguments: serde_json::Value::Object(params.arguments.unwrap_or_default()),
+            task: None,
         };
 
         let response = self
@@ -84,36 +119,127 @@ impl ServerHandler for StdioBridge {
             .await
             .map_err(|e| e.to_mcp_error("call tool"))?;
 
-        // Convert our response to rmcp format
-        let content: Vec<rmcp::model::Content> = response
-            .content
-            .into_iter()
-            .map(|c| match c {
-                super::types::ToolContent::Text { text } => rmcp::model::Content::text(text),
-                super::types::ToolContent::Image { data, mime_type } => {
-                    rmcp::model::Content::image(data, mime_type)
-                }
-                super::types::ToolContent::Resource { uri, mime_type } => {
-                    warn!("Resource content type not fully supported yet: {}", uri);
-                    rmcp::model::Content::text(format!(
-                        "Resource: {} ({})",
-                        uri,
-                        mime_type.unwrap_or_else(|| "unknown".to_string())
-                    ))
-                }
+        Ok(build_rmcp_result(response))
+    }
+
+    // List resources - forward to stdio client
+    async fn list_resources(
+        &self,
+        _params: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, McpError> {
+        debug!("Bridge server listing resources");
+        let resources = self
+            .client
+            .list_resources()
+            .await
+            .map_err(|e| e.to_mcp_error("list resources"))?;
+
+        Ok(ListResourcesResult::with_all_items(
+            resources.into_iter().map(build_rmcp_resource).collect(),
+        ))
+    }
+
+    // Read resource - forward to stdio client
+    async fn read_resource(
+        &self,
+        params: ReadResourceRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, McpError> {
+        debug!("Bridge server reading resource: {}", params.uri);
+        let contents = self
+            .client
+            .read_resource(params.uri)
+            .await
+            .map_err(|e| e.to_mcp_error("read resource"))?;
+
+        Ok(ReadResourceResult {
+            contents: contents
+                .into_iter()
+                .map(build_rmcp_resource_contents)
+                .collect(),
+        })
+    }
+
+    // List prompts - forward to stdio client
+    async fn list_prompts(
+        &self,
+        _params: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListPromptsResult, McpError> {
+        debug!("Bridge server listing prompts");
+        let prompts = self
+            .client
+            .list_prompts()
+            .await
+            .map_err(|e| e.to_mcp_error("list prompts"))?;
+
+        Ok(ListPromptsResult::with_all_items(
+            prompts.into_iter().map(build_rmcp_prompt).collect(),
+        ))
+    }
+
+    // Get prompt - forward to stdio client
+    async fn get_prompt(
+        &self,
+        params: GetPromptRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<GetPromptResult, McpError> {
+        debug!("Bridge server getting prompt: {}", params.name);
+        let response = self
+            .client
+            .get_prompt(PromptGetRequest {
+                name: params.name,
+                arguments: params.arguments,
             })
-            .collect();
+            .await
+            .map_err(|e| e.to_mcp_error("get prompt"))?;
 
-        Ok(CallToolResult {
-            meta: None,
-            content,
-            structured_content: None,
-            is_error: response.is_error,
+        build_rmcp_prompt_result(response)
+    }
+}
+
+/// Convert our tool call response to rmcp format
+pub(super) fn build_rmcp_result(response: ToolCallResponse) -> CallToolResult {
+    let content: Vec<rmcp::model::Content> = response
+        .content
+        .into_iter()
+        .map(|c| match c {
+            super::types::ToolContent::Text { text } => rmcp::model::Content::text(text),
+            super::types::ToolContent::Image { data, mime_type } => {
+                rmcp::model::Content::image(data, mime_type)
+            }
+            super::types::ToolContent::Resource {
+                uri,
+                mime_type,
+                text,
+                blob,
+            } => rmcp::model::Content::resource(match blob {
+                Some(blob) => ResourceContents::BlobResourceContents {
+                    uri,
+                    mime_type,
+                    blob,
+                    meta: None,
+                },
+                None => ResourceContents::TextResourceContents {
+                    uri,
+                    mime_type,
+                    text: text.unwrap_or_default(),
+                    meta: None,
+                },
+            }),
         })
+        .collect();
+
+    CallToolResult {
+        meta: None,
+        content,
+        structured_content: response.structured_content,
+        is_error: response.is_error,
     }
 }
 
-fn build_rmcp_tool(tool: ToolDefinition) -> rmcp::model::Tool {
+pub(super) fn build_rmcp_tool(tool: ToolDefinition) -> rmcp::model::Tool {
     let input_schema = match tool.input_schema.as_object() {
         Some(schema) => schema.clone(),
         None => {
@@ -130,13 +256,91 @@ fn build_rmcp_tool(tool: ToolDefinition) -> rmcp::model::Tool {
         title: None,
         description: tool.description.map(Into::into),
         input_schema: Arc::new(input_schema),
-        output_schema: None,
-        annotations: None,
+        output_schema: tool
+            .output_schema
+            .and_then(|schema| schema.as_object().cloned())
+            .map(Arc::new),
+        annotations: tool
+            .annotations
+            .and_then(|annotations| serde_json::from_value(annotations).ok()),
         icons: None,
         meta: None,
     }
 }
 
+fn build_rmcp_prompt(prompt: PromptDefinition) -> rmcp::model::Prompt {
+    let arguments = prompt
+        .arguments
+        .into_iter()
+        .map(|argument| rmcp::model::PromptArgument {
+            name: argument.name,
+            title: None,
+            description: argument.description,
+            required: Some(argument.required),
+        })
+        .collect();
+
+    rmcp::model::Prompt::new(prompt.name, prompt.description, Some(arguments))
+}
+
+fn build_rmcp_prompt_result(response: PromptGetResponse) -> Result<GetPromptResult, McpError> {
+    let messages = response
+        .messages
+        .into_iter()
+        .map(|message| {
+            let content = serde_json::from_value(message.content).map_err(|e| {
+                McpError::internal_error(format!("Invalid prompt message content: {}", e), None)
+            })?;
+            Ok(rmcp::model::PromptMessage {
+                role: match message.role {
+                    PromptRole::User => PromptMessageRole::User,
+                    PromptRole::Assistant => PromptMessageRole::Assistant,
+                },
+                content,
+            })
+        })
+        .collect::<Result<_, McpError>>()?;
+
+    Ok(GetPromptResult {
+        description: response.description,
+        messages,
+    })
+}
+
+fn build_rmcp_resource(resource: ResourceDefinition) -> rmcp::model::Resource {
+    RawResource {
+        description: resource.description,
+        mime_type: resource.mime_type,
+        ..RawResource::new(resource.uri, resource.name)
+    }
+    .no_annotation()
+}
+
+fn build_rmcp_resource_contents(content: ResourceContent) -> ResourceContents {
+    match content {
+        ResourceContent::Text {
+            uri,
+            mime_type,
+            text,
+        } => ResourceContents::TextResourceContents {
+            uri,
+            mime_type,
+            text,
+            meta: None,
+        },
+        ResourceContent::Blob {
+            uri,
+            mime_type,
+            blob,
+        } => ResourceContents::BlobResourceContents {
+            uri,
+            mime_type,
+            blob,
+            meta: None,
+        },
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -148,6 +352,8 @@ mod tests {
             name: "example".to_string(),
             description: Some("Example tool".to_string()),
             input_schema: json!({"type": "object"}),
+            output_schema: None,
+            annotations: None,
         };
 
         let converted = build_rmcp_tool(tool);
@@ -160,18 +366,36 @@ mod tests {
             name: "example".to_string(),
             description: None,
             input_schema: json!(true),
+            output_schema: None,
+            annotations: None,
         };
 
         let converted = build_rmcp_tool(tool);
         assert!(converted.input_schema.is_empty());
     }
 
+    #[test]
+    fn test_build_rmcp_tool_keeps_annotations() {
+        let tool = ToolDefinition {
+            name: "example".to_string(),
+            description: None,
+            input_schema: json!({"type": "object"}),
+            output_schema: None,
+            annotations: Some(json!({"readOnlyHint": true})),
+        };
+
+        let converted = build_rmcp_tool(tool);
+        assert_eq!(converted.annotations.unwrap().read_only_hint, Some(true));
+    }
+
     #[test]
     fn test_bridge_list_tools_creates_correct_mcp_tools() {
         let tool = ToolDefinition {
             name: "test_tool".to_string(),
             description: Some("A test tool".to_string()),
             input_schema: json!({"type": "object", "properties": {"arg": {"type": "string"}}}),
+            output_schema: None,
+            annotations: None,
         };
 
         let converted = build_rmcp_tool(tool);
@@ -198,6 +422,8 @@ mod tests {
                     }
                 }
             }),
+            output_schema: None,
+            annotations: None,
         };
 
         let converted = build_rmcp_tool(tool);
@@ -211,6 +437,8 @@ mod tests {
             name: "null_tool".to_string(),
             description: Some("Tool with null schema".to_string()),
             input_schema: json!(null),
+            output_schema: None,
+            annotations: None,
         };
 
         let converted = build_rmcp_tool(tool);
@@ -224,6 +452,8 @@ mod tests {
             name: "array_tool".to_string(),
             description: Some("Tool with array schema".to_string()),
             input_schema: json!([{"type": "string"}]),
+            output_schema: None,
+            annotations: None,
         };
 
         let converted = build_rmcp_tool(tool);
@@ -237,10 +467,238 @@ mod tests {
             name: "string_tool".to_string(),
             description: Some("Tool with string schema".to_string()),
             input_schema: json!("just a string"),
+            output_schema: None,
+            annotations: None,
         };
 
         let converted = build_rmcp_tool(tool);
         assert!(converted.input_schema.is_empty());
         assert_eq!(converted.name.as_ref(), "string_tool");
     }
+
+    #[test]
+    fn test_build_rmcp_resource_contents_keeps_blob() {
+        let converted = build_rmcp_resource_contents(ResourceContent::Blob {
+            uri: "file:///logo.png".to_string(),
+            mime_type: Some("image/png".to_string()),
+            blob: "iVBORw0K".to_string(),
+        });
+
+        assert!(matches!(
+            converted,
+            ResourceContents::BlobResourceContents { ref blob, .. } if blob == "iVBORw0K"
+        ));
+    }
+
+    struct PromptsServer;
+
+    impl ServerHandler for PromptsServer {
+        fn get_info(&self) -> ServerInfo {
+            ServerInfo {
+                capabilities: ServerCapabilities::builder().enable_prompts().build(),
+                ..Default::default()
+            }
+        }
+
+        async fn list_prompts(
+            &self,
+            _params: Option<PaginatedRequestParams>,
+            _context: RequestContext<RoleServer>,
+        ) -> Result<ListPromptsResult, McpError> {
+            Ok(ListPromptsResult::with_all_items(vec![
+                rmcp::model::Prompt::new("greet", Some("Say hello"), None),
+            ]))
+        }
+
+        async fn get_prompt(
+            &self,
+            params: GetPromptRequestParams,
+            _context: RequestContext<RoleServer>,
+        ) -> Result<GetPromptResult, McpError> {
+            let who = params
+                .arguments
+                .and_then(|args| args.get("who").cloned())
+                .and_then(|who| who.as_str().map(str::to_string))
+                .unwrap_or_default();
+            Ok(GetPromptResult {
+                description: None,
+                messages: vec![rmcp::model::PromptMessage::new_text(
+                    PromptMessageRole::User,
+                    format!("hello {}", who),
+                )],
+            })
+        }
+    }
+
+    /// Serve `upstream` through a bridge and connect a downstream MCP client to it
+    async fn bridge_to<S: ServerHandler>(
+        upstream: S,
+        tools: Option<ToolFilter>,
+    ) -> rmcp::service::RunningService<rmcp::RoleClient, ()> {
+        use rmcp::ServiceExt;
+
+        // upstream <-> McpClient
+        let (client_io, server_io) = tokio::io::duplex(4096);
+        tokio::spawn(async move {
+            let server = upstream.serve(server_io).await?;
+            server.waiting().await?;
+            anyhow::Ok(())
+        });
+        let client = Arc::new(McpClient::new(
+            "upstream".to_string(),
+            super::super::McpClientSettings::default(),
+        ));
+        client.init_with_transport(client_io).await.unwrap();
+
+        // bridge <-> downstream MCP client
+        let (downstream_io, bridge_io) = tokio::io::duplex(4096);
+        let bridge = StdioBridge::new(client, "upstream".to_string(), tools.map(Arc::new));
+        tokio::spawn(async move {
+            let server = bridge.serve(bridge_io).await?;
+            server.waiting().await?;
+            anyhow::Ok(())
+        });
+        ().serve(downstream_io).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_bridge_forwards_prompts() {
+        let downstream = bridge_to(PromptsServer, None).await;
+
+        let prompts = downstream.list_all_prompts().await.unwrap();
+        assert_eq!(prompts.len(), 1);
+        assert_eq!(prompts[0].name, "greet");
+
+        let result = downstream
+            .get_prompt(GetPromptRequestParams {
+                meta: None,
+                name: "greet".to_string(),
+                arguments: json!({"who": "world"}).as_object().cloned(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            result.messages[0].content,
+            rmcp::model::PromptMessageContent::text("hello world")
+        );
+    }
+
+    struct SearchServer;
+
+    impl ServerHandler for SearchServer {
+        fn get_info(&self) -> ServerInfo {
+            ServerInfo {
+                capabilities: ServerCapabilities::builder().enable_tools().build(),
+                ..Default::default()
+            }
+        }
+
+        async fn list_tools(
+            &self,
+            _params: Option<PaginatedRequestParams>,
+            _context: RequestContext<RoleServer>,
+        ) -> Result<ListToolsResult, McpError> {
+            let schema = json!({"type": "object"}).as_object().cloned().unwrap();
+            Ok(ListToolsResult::with_all_items(vec![
+                rmcp::model::Tool::new("search", "Search docs", Arc::new(schema.clone())),
+                rmcp::model::Tool::new("fetch", "Fetch a page", Arc::new(schema)),
+            ]))
+        }
+
+        async fn call_tool(
+            &self,
+            params: CallToolRequestParams,
+            _context: RequestContext<RoleServer>,
+        ) -> Result<CallToolResult, McpError> {
+            let mut result = CallToolResult::success(vec![rmcp::model::Content::text(format!(
+                "called {}",
+                params.name
+            ))]);
+            if params.name == "fetch" {
+                result.structured_content = Some(json!({"status": 200, "bytes": 512}));
+            }
+            Ok(result)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bridge_overrides_descriptions() {
+        let tools = ToolFilter {
+            descriptions: [(
+                "search".to_string(),
+                "Full-text search over the docs".to_string(),
+            )]
+            .into(),
+            ..Default::default()
+        };
+        let downstream = bridge_to(SearchServer, Some(tools)).await;
+
+        let listed = downstream.list_all_tools().await.unwrap();
+        assert_eq!(
+            listed[0].description.as_deref(),
+            Some("Full-text search over the docs")
+        );
+        assert_eq!(listed[0].input_schema["type"], "object");
+        assert_eq!(listed[1].description.as_deref(), Some("Fetch a page"));
+    }
+
+    #[tokio::test]
+    async fn test_bridge_forwards_structured_content() {
+        let downstream = bridge_to(SearchServer, None).await;
+
+        let call = |name: &'static str| {
+            downstream.call_tool(CallToolRequestParams {
+                meta: None,
+                name: name.into(),
+                arguments: None,
+                task: None,
+            })
+        };
+        let fetched = call("fetch").await.unwrap();
+        assert_eq!(
+            fetched.structured_content,
+            Some(json!({"status": 200, "bytes": 512}))
+        );
+        assert_eq!(fetched.content[0].as_text().unwrap().text, "called fetch");
+
+        let searched = call("search").await.unwrap();
+        assert_eq!(searched.structured_content, None);
+    }
+
+    #[tokio::test]
+    async fn test_bridge_renames_tools() {
+        let tools = ToolFilter {
+            rename: [("search".to_string(), "docs_search".to_string())].into(),
+            ..Default::default()
+        };
+        let downstream = bridge_to(SearchServer, Some(tools)).await;
+
+        let listed = downstream.list_all_tools().await.unwrap();
+        let names: Vec<_> = listed.iter().map(|tool| tool.name.as_ref()).collect();
+        assert_eq!(names, ["docs_search", "fetch"]);
+
+        let result = downstream
+            .call_tool(CallToolRequestParams {
+                meta: None,
+                name: "docs_search".into(),
+                arguments: None,
+                task: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(result.content[0].as_text().unwrap().text, "called search");
+
+        // The upstream name is hidden behind its alias
+        assert!(
+            downstream
+                .call_tool(CallToolRequestParams {
+                    meta: None,
+                    name: "search".into(),
+                    arguments: None,
+                    task: None,
+                })
+                .await
+                .is_err()
+        );
+    }
 }
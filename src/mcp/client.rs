@@ -1,8 +1,21 @@
+use super::http_client::{CurrentRequestId, RequestIdClient};
 use super::runtime::{McpRuntimeHandle, RuntimeState, spawn_runtime};
-use super::types::{ToolCallRequest, ToolCallResponse, ToolDefinition};
+use super::types::{
+    PromptDefinition, PromptGetRequest, PromptGetResponse, ResourceContent, ResourceDefinition,
+    ToolCallRequest, ToolCallResponse, ToolDefinition, ToolListPage, UpstreamCapabilities,
+};
+use crate::api::mcp_sse_service::SseSettings;
+use crate::config::{ClientIdentity, McpConfig};
 use crate::error::{ProxyError, Result};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use rmcp::ServiceExt;
-use rmcp::transport::{StreamableHttpClientTransport, TokioChildProcess};
+use rmcp::model::{ClientInfo, Implementation, ServerInfo};
+use rmcp::service::{ClientInitializeError, RoleClient};
+use rmcp::transport::streamable_http_client::{
+    StreamableHttpClientTransportConfig, StreamableHttpError,
+};
+use rmcp::transport::{IntoTransport, StreamableHttpClientTransport};
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::RwLock;
@@ -15,17 +28,85 @@ const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(30);
 /// Type alias for the runtime handle stored in RwLock
 type RuntimeHandleType = Arc<RwLock<Option<McpRuntimeHandle>>>;
 
+/// Tunables applied to an MCP client and its runtime worker
+#[derive(Debug, Clone)]
+pub(crate) struct McpClientSettings {
+    /// Maximum number of pages fetched when walking a paginated list
+    pub(crate) max_list_pages: usize,
+    /// Idle connections kept alive to a remote upstream
+    pub(crate) remote_pool_max_idle: usize,
+    /// How long an idle remote connection is kept alive
+    pub(crate) remote_pool_idle_timeout: Duration,
+    /// How long opening a TCP connection to a remote upstream may take
+    pub(crate) connect_timeout: Duration,
+    /// Extra attempts at a remote call that failed to connect or timed out
+    pub(crate) max_retries: u32,
+    /// Delay before the first retry, doubled for each further one
+    pub(crate) retry_backoff: Duration,
+    /// How long a graceful stop may take before the worker is aborted
+    pub(crate) stop_timeout: Duration,
+    /// How long a local child may take to exit after SIGTERM before it is killed
+    pub(crate) shutdown_grace: Duration,
+    /// Timing of the SSE streams a local endpoint serves
+    pub(crate) sse: SseSettings,
+    /// What the proxy sends upstream in the `initialize` request
+    pub(crate) client_info: ClientInfo,
+}
+
+impl Default for McpClientSettings {
+    fn default() -> Self {
+        Self::from(&McpConfig::default())
+    }
+}
+
+impl From<&McpConfig> for McpClientSettings {
+    fn from(config: &McpConfig) -> Self {
+        Self {
+            max_list_pages: config.max_list_pages,
+            remote_pool_max_idle: config.remote_pool_max_idle,
+            remote_pool_idle_timeout: Duration::from_secs(config.remote_pool_idle_timeout_secs),
+            connect_timeout: Duration::from_secs(config.connect_timeout_secs),
+            max_retries: config.max_retries,
+            retry_backoff: Duration::from_millis(config.retry_backoff_ms),
+            stop_timeout: Duration::from_secs(config.stop_timeout_secs),
+            shutdown_grace: Duration::from_secs(config.shutdown_grace_secs),
+            sse: SseSettings::from(config),
+            client_info: client_info(&config.client_info),
+        }
+    }
+}
+
+/// Build the `initialize` parameters from the configured identity
+/// Capabilities are checked when the config is loaded, so invalid ones fall back to none
+fn client_info(identity: &ClientIdentity) -> ClientInfo {
+    ClientInfo {
+        meta: None,
+        protocol_version: Default::default(),
+        capabilities: serde_json::from_value(identity.capabilities.clone().into())
+            .unwrap_or_default(),
+        client_info: Implementation {
+            name: identity.name.clone(),
+            title: None,
+            version: identity.version.clone(),
+            icons: None,
+            website_url: None,
+        },
+    }
+}
+
 /// A wrapper around rmcp RunningService for the proxy
 #[derive(Clone)]
 pub(crate) struct McpClient {
     server_name: String,
+    settings: McpClientSettings,
     runtime: RuntimeHandleType,
 }
 
 impl McpClient {
-    pub(crate) fn new(server_name: String) -> Self {
+    pub(crate) fn new(server_name: String, settings: McpClientSettings) -> Self {
         Self {
             server_name,
+            settings,
             runtime: Arc::new(RwLock::new(None)),
         }
     }
@@ -53,27 +134,52 @@ impl McpClient {
         }
     }
 
-    /// Initialize the MCP client with TokioChildProcess transport
-    pub(crate) async fn init_with_transport(&self, transport: TokioChildProcess) -> Result<()> {
+    /// Requests queued for or being handled by the runtime worker
+    pub(crate) async fn pending_requests(&self) -> usize {
+        match self.runtime.read().await.as_ref() {
+            Some(runtime) => runtime.pending_requests(),
+            None => 0,
+        }
+    }
+
+    /// Whether the runtime exited on its own, e.g. because the upstream process died
+    pub(crate) async fn has_failed(&self) -> bool {
+        if let Some(runtime) = self.runtime.read().await.as_ref() {
+            matches!(runtime.state().await, RuntimeState::Failed(_))
+        } else {
+            false
+        }
+    }
+
+    /// Initialize the MCP client over a local transport such as a child's stdio
+    pub(crate) async fn init_with_transport<T, A>(&self, transport: T) -> Result<()>
+    where
+        T: IntoTransport<RoleClient, std::io::Error, A> + Send + 'static,
+    {
         self.ensure_not_running().await?;
         info!("Initializing MCP client for server: {}", self.server_name);
 
         let ct = CancellationToken::new();
         let ct_clone = ct.clone();
+        let client_info = self.settings.client_info.clone();
 
         let service = tokio::time::timeout(HANDSHAKE_TIMEOUT, async {
-            ().serve_with_ct(transport, ct_clone).await
+            client_info.serve_with_ct(transport, ct_clone).await
         })
         .await
         .map_err(|_| {
             ct.cancel();
             ProxyError::mcp_handshake_timeout(HANDSHAKE_TIMEOUT, &self.server_name, None)
         })?
-        .map_err(|e| {
-            ProxyError::mcp_protocol(format!("Failed to initialize MCP client: {:?}", e))
-        })?;
+        .map_err(|e| init_error("Failed to initialize MCP client", e))?;
 
-        let runtime = spawn_runtime(self.server_name.clone(), service);
+        // Child processes have no headers to carry a request ID
+        let runtime = spawn_runtime(
+            self.server_name.clone(),
+            service,
+            self.settings.clone(),
+            CurrentRequestId::default(),
+        );
         let mut runtime_lock = self.runtime.write().await;
         *runtime_lock = Some(runtime);
 
@@ -81,21 +187,43 @@ impl McpClient {
         Ok(())
     }
 
-    /// Initialize the MCP client with HTTP transport for remote servers
-    pub(crate) async fn init_with_http(&self, url: &str) -> Result<()> {
+    /// Initialize the MCP client with HTTP transport for remote servers,
+    /// sending extra headers with every request
+    pub(crate) async fn init_with_http_headers(
+        &self,
+        url: &str,
+        headers: &HashMap<String, String>,
+    ) -> Result<()> {
         self.ensure_not_running().await?;
         info!(
             "Initializing MCP HTTP client for server: {} at {}",
             self.server_name, url
         );
 
-        let transport = StreamableHttpClientTransport::from_uri(url);
+        let http_client = reqwest::Client::builder()
+            .default_headers(header_map(headers)?)
+            .pool_max_idle_per_host(self.settings.remote_pool_max_idle)
+            .pool_idle_timeout(self.settings.remote_pool_idle_timeout)
+            .connect_timeout(self.settings.connect_timeout)
+            .build()
+            .map_err(|e| {
+                ProxyError::mcp_protocol(format!("Failed to build MCP HTTP client: {}", e))
+            })?;
+        let request_id = CurrentRequestId::default();
+        let transport = StreamableHttpClientTransport::with_client(
+            RequestIdClient {
+                http: http_client,
+                request_id: request_id.clone(),
+            },
+            StreamableHttpClientTransportConfig::with_uri(url),
+        );
 
         let ct = CancellationToken::new();
         let ct_clone = ct.clone();
+        let client_info = self.settings.client_info.clone();
 
         let service = tokio::time::timeout(HANDSHAKE_TIMEOUT, async {
-            ().serve_with_ct(transport, ct_clone).await
+            client_info.serve_with_ct(transport, ct_clone).await
         })
         .await
         .map_err(|_| {
@@ -103,10 +231,23 @@ impl McpClient {
             ProxyError::mcp_handshake_timeout(HANDSHAKE_TIMEOUT, &self.server_name, Some(url))
         })?
         .map_err(|e| {
-            ProxyError::mcp_protocol(format!("Failed to initialize MCP HTTP client: {:?}", e))
+            if is_connect_timeout(&e) {
+                ProxyError::mcp_connect_timeout(
+                    self.settings.connect_timeout,
+                    &self.server_name,
+                    url,
+                )
+            } else {
+                init_error("Failed to initialize MCP HTTP client", e)
+            }
         })?;
 
-        let runtime = spawn_runtime(self.server_name.clone(), service);
+        let runtime = spawn_runtime(
+            self.server_name.clone(),
+            service,
+            self.settings.clone(),
+            request_id,
+        );
         let mut runtime_lock = self.runtime.write().await;
         *runtime_lock = Some(runtime);
 
@@ -130,6 +271,19 @@ impl McpClient {
         runtime.list_tools(&self.server_name).await
     }
 
+    /// List a single page of tools, starting at `cursor`
+    pub(crate) async fn list_tools_page(&self, cursor: Option<String>) -> Result<ToolListPage> {
+        let runtime = self
+            .runtime
+            .read()
+            .await
+            .as_ref()
+            .cloned()
+            .ok_or_else(|| ProxyError::server_not_running(self.server_name.clone()))?;
+
+        runtime.list_tools_page(&self.server_name, cursor).await
+    }
+
     /// Call a tool on the MCP server
     pub(crate) async fn call_tool(&self, request: ToolCallRequest) -> Result<ToolCallResponse> {
         let runtime = self
@@ -143,6 +297,112 @@ impl McpClient {
         runtime.call_tool(&self.server_name, request).await
     }
 
+    /// List the resources the MCP server exposes
+    pub(crate) async fn list_resources(&self) -> Result<Vec<ResourceDefinition>> {
+        let runtime = self
+            .runtime
+            .read()
+            .await
+            .as_ref()
+            .cloned()
+            .ok_or_else(|| ProxyError::server_not_running(self.server_name.clone()))?;
+
+        runtime.list_resources(&self.server_name).await
+    }
+
+    /// Read the contents of a resource by URI
+    pub(crate) async fn read_resource(&self, uri: String) -> Result<Vec<ResourceContent>> {
+        let runtime = self
+            .runtime
+            .read()
+            .await
+            .as_ref()
+            .cloned()
+            .ok_or_else(|| ProxyError::server_not_running(self.server_name.clone()))?;
+
+        runtime.read_resource(&self.server_name, uri).await
+    }
+
+    /// List the prompt templates the MCP server exposes
+    pub(crate) async fn list_prompts(&self) -> Result<Vec<PromptDefinition>> {
+        let runtime = self
+            .runtime
+            .read()
+            .await
+            .as_ref()
+            .cloned()
+            .ok_or_else(|| ProxyError::server_not_running(self.server_name.clone()))?;
+
+        runtime.list_prompts(&self.server_name).await
+    }
+
+    /// Render a prompt template with the given arguments
+    pub(crate) async fn get_prompt(&self, request: PromptGetRequest) -> Result<PromptGetResponse> {
+        let runtime = self
+            .runtime
+            .read()
+            .await
+            .as_ref()
+            .cloned()
+            .ok_or_else(|| ProxyError::server_not_running(self.server_name.clone()))?;
+
+        runtime.get_prompt(&self.server_name, request).await
+    }
+
+    /// Get the status of an upstream task created by a task-augmented tool call
+    pub(crate) async fn get_task(&self, task_id: String) -> Result<serde_json::Value> {
+        let runtime = self
+            .runtime
+            .read()
+            .await
+            .as_ref()
+            .cloned()
+            .ok_or_else(|| ProxyError::server_not_running(self.server_name.clone()))?;
+
+        runtime.get_task(&self.server_name, task_id).await
+    }
+
+    /// Get the result of a completed upstream task
+    pub(crate) async fn get_task_result(&self, task_id: String) -> Result<serde_json::Value> {
+        let runtime = self
+            .runtime
+            .read()
+            .await
+            .as_ref()
+            .cloned()
+            .ok_or_else(|| ProxyError::server_not_running(self.server_name.clone()))?;
+
+        runtime.get_task_result(&self.server_name, task_id).await
+    }
+
+    /// Capabilities the upstream declared when the connection was established
+    pub(crate) async fn capabilities(&self) -> Result<UpstreamCapabilities> {
+        let runtime_lock = self.runtime.read().await;
+        let runtime = runtime_lock
+            .as_ref()
+            .ok_or_else(|| ProxyError::server_not_running(self.server_name.clone()))?;
+        runtime.capabilities().ok_or_else(|| {
+            ProxyError::mcp_protocol(format!(
+                "{} did not report its capabilities",
+                self.server_name
+            ))
+        })
+    }
+
+    /// The upstream's initialize result (protocol version, capabilities, instructions)
+    pub(crate) async fn server_info(&self) -> Result<ServerInfo> {
+        let runtime_lock = self.runtime.read().await;
+        let runtime = runtime_lock
+            .as_ref()
+            .ok_or_else(|| ProxyError::server_not_running(self.server_name.clone()))?;
+        runtime.server_info().cloned().ok_or_else(|| {
+            ProxyError::mcp_protocol(format!(
+                "{} did not report its server info",
+                self.server_name
+            ))
+        })
+    }
+
     /// Get server name
     pub(crate) fn server_name(&self) -> &str {
         &self.server_name
@@ -156,23 +416,152 @@ impl McpClient {
                 .ok_or_else(|| ProxyError::server_not_running(self.server_name.clone()))?
         };
 
-        runtime.stop(&self.server_name).await
+        // A failed worker has already dropped its transport; nothing is left to close
+        if let RuntimeState::Failed(reason) = runtime.state().await {
+            debug!(
+                "Runtime for {} already exited ({}), skipping graceful stop",
+                self.server_name, reason
+            );
+            return Ok(());
+        }
+
+        runtime
+            .stop_within(&self.server_name, self.settings.stop_timeout)
+            .await
     }
 }
 
+/// Map a failed handshake to a 503 when the server couldn't be reached, and a 502 otherwise
+fn init_error(context: &str, error: ClientInitializeError) -> ProxyError {
+    let message = format!("{}: {:?}", context, error);
+    let unreachable = match &error {
+        ClientInitializeError::ConnectionClosed(_) => true,
+        ClientInitializeError::TransportError { error, .. } => {
+            let inner = error.error.as_ref();
+            match inner.downcast_ref::<StreamableHttpError<reqwest::Error>>() {
+                // Includes connections dropped before a response arrived
+                Some(StreamableHttpError::Client(e)) => e.is_request() || e.is_timeout(),
+                Some(StreamableHttpError::Io(_)) => true,
+                Some(_) => false,
+                None => inner.is::<std::io::Error>(),
+            }
+        }
+        _ => false,
+    };
+
+    if unreachable {
+        ProxyError::mcp_unavailable(message)
+    } else {
+        ProxyError::mcp_protocol(message)
+    }
+}
+
+/// Whether the handshake failed because the TCP connection couldn't be opened in time
+fn is_connect_timeout(error: &ClientInitializeError) -> bool {
+    match error {
+        ClientInitializeError::TransportError { error, .. } => matches!(
+            error.error.downcast_ref::<StreamableHttpError<reqwest::Error>>(),
+            Some(StreamableHttpError::Client(e)) if e.is_connect() && e.is_timeout()
+        ),
+        _ => false,
+    }
+}
+
+/// Convert configured header pairs into a validated header map
+pub(crate) fn header_map(headers: &HashMap<String, String>) -> Result<HeaderMap> {
+    let mut map = HeaderMap::new();
+    for (name, value) in headers {
+        let name = HeaderName::from_bytes(name.as_bytes()).map_err(|e| {
+            ProxyError::invalid_request(format!("Invalid header name {}: {}", name, e))
+        })?;
+        let value = HeaderValue::from_str(value).map_err(|e| {
+            ProxyError::invalid_request(format!("Invalid value for header {}: {}", name, e))
+        })?;
+        map.insert(name, value);
+    }
+    Ok(map)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::mcp::types::ToolContent;
 
     #[test]
     fn test_create_client() {
-        let client = McpClient::new("test-server".to_string());
+        let client = McpClient::new("test-server".to_string(), McpClientSettings::default());
         assert_eq!(client.server_name(), "test-server");
     }
 
+    #[test]
+    fn test_settings_from_config() {
+        let config = McpConfig {
+            remote_pool_max_idle: 2,
+            remote_pool_idle_timeout_secs: 15,
+            ..Default::default()
+        };
+
+        let settings = McpClientSettings::from(&config);
+        assert_eq!(settings.remote_pool_max_idle, 2);
+        assert_eq!(settings.remote_pool_idle_timeout, Duration::from_secs(15));
+        assert_eq!(settings.connect_timeout, Duration::from_secs(10));
+    }
+
+    #[derive(Clone, Default)]
+    struct RecordingServer {
+        seen: Arc<std::sync::Mutex<Option<ClientInfo>>>,
+    }
+
+    impl rmcp::ServerHandler for RecordingServer {
+        async fn initialize(
+            &self,
+            request: rmcp::model::InitializeRequestParams,
+            _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+        ) -> std::result::Result<rmcp::model::InitializeResult, rmcp::ErrorData> {
+            *self.seen.lock().unwrap() = Some(request);
+            Ok(self.get_info())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_handshake_sends_configured_client_info() {
+        let mut config = McpConfig::default();
+        config.client_info.name = "acme-gateway".to_string();
+        config.client_info.version = "2.1.0".to_string();
+        config.client_info.capabilities.insert(
+            "roots".to_string(),
+            serde_json::json!({"listChanged": true}),
+        );
+        let client = McpClient::new("upstream".to_string(), McpClientSettings::from(&config));
+
+        let upstream = RecordingServer::default();
+        let seen = upstream.seen.clone();
+        let (client_io, server_io) = tokio::io::duplex(4096);
+        tokio::spawn(async move {
+            let server = upstream.serve(server_io).await?;
+            server.waiting().await?;
+            anyhow::Ok(())
+        });
+        client.init_with_transport(client_io).await.unwrap();
+
+        let request = seen.lock().unwrap().clone().unwrap();
+        assert_eq!(request.client_info.name, "acme-gateway");
+        assert_eq!(request.client_info.version, "2.1.0");
+        assert_eq!(request.capabilities.roots.unwrap().list_changed, Some(true));
+
+        client.stop().await.unwrap();
+    }
+
+    #[test]
+    fn test_default_client_info_names_the_proxy() {
+        let info = McpClientSettings::default().client_info;
+        assert_eq!(info.client_info.name, "rusted-tools");
+        assert_eq!(info.client_info.version, env!("CARGO_PKG_VERSION"));
+    }
+
     #[tokio::test]
     async fn test_client_not_initialized() {
-        let client = McpClient::new("test-server".to_string());
+        let client = McpClient::new("test-server".to_string(), McpClientSettings::default());
 
         // Attempting to use an uninitialized client should fail
         let result = client.list_tools().await;
@@ -183,4 +572,127 @@ mod tests {
             assert!(e.to_string().contains("not running"));
         }
     }
+
+    #[tokio::test]
+    async fn test_refused_connection_is_unavailable() {
+        let client = McpClient::new("test-server".to_string(), McpClientSettings::default());
+
+        let err = client
+            .init_with_http_headers("http://127.0.0.1:1/mcp", &HashMap::new())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ProxyError::McpUnavailable(_)), "{:?}", err);
+        assert_eq!(
+            err.status_code(),
+            axum::http::StatusCode::SERVICE_UNAVAILABLE
+        );
+    }
+
+    #[tokio::test]
+    async fn test_hanging_connect_hits_connect_timeout() {
+        // A listener whose accept queue is full drops further SYNs, like a non-routable address
+        let socket = tokio::net::TcpSocket::new_v4().unwrap();
+        socket.bind("127.0.0.1:0".parse().unwrap()).unwrap();
+        let listener = socket.listen(0).unwrap();
+        let addr = listener.local_addr().unwrap();
+        let _queued = tokio::net::TcpStream::connect(addr).await.unwrap();
+
+        let settings = McpClientSettings {
+            connect_timeout: Duration::from_millis(200),
+            ..Default::default()
+        };
+        let client = McpClient::new("test-server".to_string(), settings);
+
+        let started = std::time::Instant::now();
+        let err = client
+            .init_with_http_headers(&format!("http://{}/mcp", addr), &HashMap::new())
+            .await
+            .unwrap_err();
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(matches!(err, ProxyError::McpTimeout(_)), "{:?}", err);
+        assert!(err.to_string().contains("Connecting to http://"), "{}", err);
+        assert!(!err.to_string().contains("handshake"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_closed_stdio_is_unavailable() {
+        let client = McpClient::new("test-server".to_string(), McpClientSettings::default());
+        let (client_io, server_io) = tokio::io::duplex(64);
+        drop(server_io);
+
+        let err = client.init_with_transport(client_io).await.unwrap_err();
+        assert!(matches!(err, ProxyError::McpUnavailable(_)), "{:?}", err);
+        // Startup retry patterns still match the underlying rmcp error
+        assert!(err.to_string().contains("BrokenPipe"), "{}", err);
+    }
+
+    /// Echoes the `id` argument back, answering later ids first
+    struct EchoServer;
+
+    impl rmcp::ServerHandler for EchoServer {
+        fn get_info(&self) -> rmcp::model::ServerInfo {
+            rmcp::model::ServerInfo {
+                capabilities: rmcp::model::ServerCapabilities::builder()
+                    .enable_tools()
+                    .build(),
+                ..Default::default()
+            }
+        }
+
+        async fn call_tool(
+            &self,
+            params: rmcp::model::CallToolRequestParams,
+            _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+        ) -> std::result::Result<rmcp::model::CallToolResult, rmcp::ErrorData> {
+            let id = params
+                .arguments
+                .and_then(|args| args.get("id").and_then(|v| v.as_u64()))
+                .unwrap_or_default();
+            tokio::time::sleep(Duration::from_millis(50u64.saturating_sub(id))).await;
+            Ok(rmcp::model::CallToolResult::success(vec![
+                rmcp::model::Content::text(id.to_string()),
+            ]))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_calls_get_their_own_responses() {
+        let (client_io, server_io) = tokio::io::duplex(64 * 1024);
+        tokio::spawn(async move {
+            let server = EchoServer.serve(server_io).await?;
+            server.waiting().await?;
+            anyhow::Ok(())
+        });
+
+        let client = Arc::new(McpClient::new(
+            "test-server".to_string(),
+            McpClientSettings::default(),
+        ));
+        client.init_with_transport(client_io).await.unwrap();
+
+        let calls = (0..32u64).map(|id| {
+            let client = client.clone();
+            tokio::spawn(async move {
+                let response = client
+                    .call_tool(ToolCallRequest {
+                        name: "echo".to_string(),
+                        arguments: serde_json::json!({ "id": id }),
+                        task: None,
+                    })
+                    .await
+                    .unwrap();
+                (id, response)
+            })
+        });
+
+        for call in calls.collect::<Vec<_>>() {
+            let (id, response) = call.await.unwrap();
+            match response.content.as_slice() {
+                [ToolContent::Text { text }] => {
+                    assert_eq!(text, &id.to_string())
+                }
+                other => panic!("unexpected content for call {}: {:?}", id, other),
+            }
+        }
+    }
 }
@@ -0,0 +1,189 @@
+// Streamable HTTP client for remote upstreams that tags requests with the caller's request ID
+
+use crate::api::request_id::REQUEST_ID_HEADER;
+use futures::{StreamExt, stream::BoxStream};
+use reqwest::header::{ACCEPT, CONTENT_TYPE, HeaderValue, WWW_AUTHENTICATE};
+use rmcp::model::{ClientJsonRpcMessage, ServerJsonRpcMessage};
+use rmcp::transport::common::http_header::{
+    EVENT_STREAM_MIME_TYPE, HEADER_SESSION_ID, JSON_MIME_TYPE,
+};
+use rmcp::transport::streamable_http_client::{
+    AuthRequiredError, SseError, StreamableHttpClient, StreamableHttpError,
+    StreamableHttpPostResponse,
+};
+use sse_stream::{Sse, SseStream};
+use std::borrow::Cow;
+use std::sync::{Arc, Mutex};
+
+/// Request ID of the call a runtime worker is making upstream
+/// The worker handles one call at a time, so it sets this before each one
+#[derive(Clone, Default)]
+pub(crate) struct CurrentRequestId(Arc<Mutex<Option<HeaderValue>>>);
+
+impl CurrentRequestId {
+    pub(crate) fn set(&self, id: Option<HeaderValue>) {
+        *self.0.lock().unwrap_or_else(|e| e.into_inner()) = id;
+    }
+
+    fn get(&self) -> Option<HeaderValue> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+/// `reqwest::Client` that adds `X-Request-Id` to the messages it posts
+/// Mirrors rmcp's own reqwest implementation, which has no hook for per-request headers
+#[derive(Clone)]
+pub(crate) struct RequestIdClient {
+    pub(crate) http: reqwest::Client,
+    pub(crate) request_id: CurrentRequestId,
+}
+
+impl StreamableHttpClient for RequestIdClient {
+    type Error = reqwest::Error;
+
+    async fn post_message(
+        &self,
+        uri: Arc<str>,
+        message: ClientJsonRpcMessage,
+        session_id: Option<Arc<str>>,
+        auth_token: Option<String>,
+    ) -> Result<StreamableHttpPostResponse, StreamableHttpError<Self::Error>> {
+        let mut request = self
+            .http
+            .post(uri.as_ref())
+            .header(ACCEPT, [EVENT_STREAM_MIME_TYPE, JSON_MIME_TYPE].join(", "));
+        if let Some(id) = self.request_id.get() {
+            request = request.header(REQUEST_ID_HEADER, id);
+        }
+        if let Some(auth_token) = auth_token {
+            request = request.bearer_auth(auth_token);
+        }
+        if let Some(session_id) = session_id {
+            request = request.header(HEADER_SESSION_ID, session_id.as_ref());
+        }
+
+        let response = request.json(&message).send().await?;
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED
+            && let Some(header) = response.headers().get(WWW_AUTHENTICATE)
+        {
+            let header = header.to_str().map_err(|_| {
+                StreamableHttpError::UnexpectedServerResponse(Cow::from(
+                    "invalid www-authenticate header value",
+                ))
+            })?;
+            return Err(StreamableHttpError::AuthRequired(AuthRequiredError {
+                www_authenticate_header: header.to_string(),
+            }));
+        }
+        if matches!(
+            response.status(),
+            reqwest::StatusCode::ACCEPTED | reqwest::StatusCode::NO_CONTENT
+        ) {
+            return Ok(StreamableHttpPostResponse::Accepted);
+        }
+
+        let session_id = response
+            .headers()
+            .get(HEADER_SESSION_ID)
+            .and_then(|v| v.to_str().ok())
+            .map(String::from);
+        match response.headers().get(CONTENT_TYPE) {
+            Some(ct) if ct.as_bytes().starts_with(EVENT_STREAM_MIME_TYPE.as_bytes()) => {
+                let events = SseStream::from_byte_stream(response.bytes_stream()).boxed();
+                Ok(StreamableHttpPostResponse::Sse(events, session_id))
+            }
+            Some(ct) if ct.as_bytes().starts_with(JSON_MIME_TYPE.as_bytes()) => {
+                let message: ServerJsonRpcMessage = response.json().await?;
+                Ok(StreamableHttpPostResponse::Json(message, session_id))
+            }
+            content_type => Err(StreamableHttpError::UnexpectedContentType(
+                content_type.map(|ct| String::from_utf8_lossy(ct.as_bytes()).to_string()),
+            )),
+        }
+    }
+
+    async fn delete_session(
+        &self,
+        uri: Arc<str>,
+        session_id: Arc<str>,
+        auth_token: Option<String>,
+    ) -> Result<(), StreamableHttpError<Self::Error>> {
+        self.http.delete_session(uri, session_id, auth_token).await
+    }
+
+    async fn get_stream(
+        &self,
+        uri: Arc<str>,
+        session_id: Arc<str>,
+        last_event_id: Option<String>,
+        auth_token: Option<String>,
+    ) -> Result<BoxStream<'static, Result<Sse, SseError>>, StreamableHttpError<Self::Error>> {
+        self.http
+            .get_stream(uri, session_id, last_event_id, auth_token)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::request_id;
+    use crate::mcp::{McpClient, McpClientSettings};
+    use axum::{Router, extract::Request, middleware::Next};
+    use reqwest::header::HeaderValue;
+    use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+    use rmcp::transport::streamable_http_server::{
+        StreamableHttpServerConfig, StreamableHttpService,
+    };
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone)]
+    struct EmptyServer;
+
+    impl rmcp::ServerHandler for EmptyServer {}
+
+    #[tokio::test]
+    async fn test_request_id_is_sent_with_upstream_calls() {
+        // `X-Request-Id` of every POST the upstream receives
+        let seen = Arc::new(Mutex::new(Vec::<Option<String>>::new()));
+        let recorder = seen.clone();
+        let service = StreamableHttpService::new(
+            || Ok(EmptyServer),
+            Arc::new(LocalSessionManager::default()),
+            StreamableHttpServerConfig::default(),
+        );
+        let app = Router::new()
+            .nest_service("/mcp", service)
+            .layer(axum::middleware::from_fn(
+                move |request: Request, next: Next| {
+                    if request.method() == "POST" {
+                        let id = request.headers().get("x-request-id");
+                        let id = id.and_then(|v| v.to_str().ok()).map(String::from);
+                        recorder.lock().unwrap().push(id);
+                    }
+                    next.run(request)
+                },
+            ));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let client = McpClient::new("stub".to_string(), McpClientSettings::default());
+        client
+            .init_with_http_headers(&format!("http://{}/mcp", addr), &HashMap::new())
+            .await
+            .unwrap();
+        seen.lock().unwrap().clear();
+
+        request_id::scope(HeaderValue::from_static("trace-42"), client.list_tools())
+            .await
+            .unwrap();
+        client.list_tools().await.unwrap();
+
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![Some("trace-42".to_string()), None]
+        );
+        client.stop().await.unwrap();
+    }
+}
@@ -1,8 +1,12 @@
+pub(crate) mod aggregate;
 pub(crate) mod bridge;
 pub(crate) mod client;
+pub(crate) mod http_client;
 pub(crate) mod runtime;
+pub(crate) mod schema;
 pub(crate) mod types;
 
+pub(crate) use aggregate::AggregateBridge;
 pub(crate) use bridge::StdioBridge;
-pub(crate) use client::McpClient;
-pub(crate) use types::{ToolCallRequest, ToolDefinition};
+pub(crate) use client::{McpClient, McpClientSettings};
+pub(crate) use types::{ToolCallRequest, ToolCallResponse, ToolDefinition, fingerprint_tools};
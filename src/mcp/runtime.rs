@@ -1,15 +1,37 @@
-use super::types::{ToolCallRequest, ToolCallResponse, ToolContent, ToolDefinition};
+use super::client::McpClientSettings;
+use super::http_client::CurrentRequestId;
+use super::types::{
+    PromptArgument, PromptDefinition, PromptGetRequest, PromptGetResponse, PromptMessage,
+    PromptRole, ResourceContent, ResourceDefinition, ToolCallRequest, ToolCallResponse,
+    ToolContent, ToolDefinition, ToolListPage, UpstreamCapabilities,
+};
+use crate::api::request_id;
 use crate::error::{ProxyError, Result};
-use rmcp::model::{CallToolRequestParams, PaginatedRequestParams, RawContent};
+use reqwest::header::HeaderValue;
+use rmcp::model::{
+    CallToolRequest, CallToolRequestParams, CallToolResult, ClientInfo, ClientRequest,
+    CreateTaskResult, GetPromptRequestParams, GetPromptResult, GetTaskInfoParams,
+    GetTaskInfoRequest, GetTaskInfoResult, GetTaskResultParams, GetTaskResultRequest,
+    PaginatedRequestParams, PromptMessageRole, RawContent, ReadResourceRequestParams,
+    ResourceContents, ServerInfo, ServerResult, TaskResult, TaskStatus,
+};
 use rmcp::service::{RoleClient, RunningService};
+use serde::de::DeserializeOwned;
 use serde_json::Value;
+use std::collections::HashSet;
+use std::future::Future;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
 use tokio::sync::{Mutex, RwLock, mpsc, oneshot};
 use tokio::task::JoinHandle;
-use tracing::{debug, error};
+use tracing::{Instrument, Span, debug, error, warn};
 
 const REQUEST_BUFFER: usize = 32;
 
+/// How often the worker checks whether the upstream transport is still alive
+const LIVENESS_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub(crate) enum RuntimeState {
     Running,
@@ -19,19 +41,59 @@ pub(crate) enum RuntimeState {
 
 #[derive(Clone)]
 pub(crate) struct McpRuntimeHandle {
-    tx: mpsc::Sender<ServiceRequest>,
+    tx: mpsc::Sender<(Option<HeaderValue>, ServiceRequest)>,
     state: Arc<RwLock<RuntimeState>>,
     join: Arc<Mutex<Option<JoinHandle<()>>>>,
+    /// The upstream's initialize result
+    server_info: Option<Arc<ServerInfo>>,
+    /// Requests submitted to the worker and not yet answered
+    pending: Arc<AtomicUsize>,
 }
 
 enum ServiceRequest {
+    /// `span` is the caller's, so the worker's logs carry the request's fields
     ListTools {
+        span: Span,
         resp: oneshot::Sender<Result<Vec<ToolDefinition>>>,
     },
+    ListToolsPage {
+        span: Span,
+        cursor: Option<String>,
+        resp: oneshot::Sender<Result<ToolListPage>>,
+    },
     CallTool {
+        span: Span,
         request: ToolCallRequest,
         resp: oneshot::Sender<Result<ToolCallResponse>>,
     },
+    ListResources {
+        span: Span,
+        resp: oneshot::Sender<Result<Vec<ResourceDefinition>>>,
+    },
+    ReadResource {
+        span: Span,
+        uri: String,
+        resp: oneshot::Sender<Result<Vec<ResourceContent>>>,
+    },
+    ListPrompts {
+        span: Span,
+        resp: oneshot::Sender<Result<Vec<PromptDefinition>>>,
+    },
+    GetPrompt {
+        span: Span,
+        request: PromptGetRequest,
+        resp: oneshot::Sender<Result<PromptGetResponse>>,
+    },
+    GetTask {
+        span: Span,
+        task_id: String,
+        resp: oneshot::Sender<Result<Value>>,
+    },
+    GetTaskResult {
+        span: Span,
+        task_id: String,
+        resp: oneshot::Sender<Result<Value>>,
+    },
     Stop {
         resp: oneshot::Sender<Result<()>>,
     },
@@ -39,23 +101,115 @@ enum ServiceRequest {
 
 pub(crate) fn spawn_runtime(
     server_name: String,
-    service: RunningService<RoleClient, ()>,
+    service: RunningService<RoleClient, ClientInfo>,
+    settings: McpClientSettings,
+    current_request_id: CurrentRequestId,
 ) -> McpRuntimeHandle {
     let (tx, mut rx) = mpsc::channel(REQUEST_BUFFER);
     let state = Arc::new(RwLock::new(RuntimeState::Running));
     let state_clone = Arc::clone(&state);
+    let server_info = service.peer_info().cloned().map(Arc::new);
+    let pending = Arc::new(AtomicUsize::new(0));
+    let pending_clone = Arc::clone(&pending);
 
     let join = tokio::spawn(async move {
         let mut service = service;
+        let mut liveness = tokio::time::interval(LIVENESS_CHECK_INTERVAL);
 
         loop {
-            match rx.recv().await {
-                Some(ServiceRequest::ListTools { resp }) => {
-                    let result = list_tools_from_service(&server_name, &service).await;
+            let request = tokio::select! {
+                request = rx.recv() => request.map(|(request_id, request)| {
+                    current_request_id.set(request_id);
+                    request
+                }),
+                _ = liveness.tick() => {
+                    // The service loop ends when the upstream goes away (e.g. the child exits)
+                    if service.is_transport_closed() {
+                        error!("MCP transport for {} closed unexpectedly", server_name);
+                        *state_clone.write().await =
+                            RuntimeState::Failed("transport closed".to_string());
+                        break;
+                    }
+                    continue;
+                }
+            };
+
+            match request {
+                Some(ServiceRequest::ListTools { span, resp }) => {
+                    let result =
+                        list_tools_from_service(&server_name, &service, settings.max_list_pages)
+                            .instrument(span)
+                            .await;
+                    let _ = resp.send(result);
+                }
+                Some(ServiceRequest::ListToolsPage { span, cursor, resp }) => {
+                    let result = list_tools_page_from_service(&server_name, &service, cursor)
+                        .instrument(span)
+                        .await;
                     let _ = resp.send(result);
                 }
-                Some(ServiceRequest::CallTool { request, resp }) => {
-                    let result = call_tool_on_service(&server_name, &service, request).await;
+                Some(ServiceRequest::CallTool {
+                    span,
+                    request,
+                    resp,
+                }) => {
+                    let result = call_tool_on_service(&server_name, &service, request)
+                        .instrument(span)
+                        .await;
+                    let _ = resp.send(result);
+                }
+                Some(ServiceRequest::ListResources { span, resp }) => {
+                    let result = list_resources_from_service(
+                        &server_name,
+                        &service,
+                        settings.max_list_pages,
+                    )
+                    .instrument(span)
+                    .await;
+                    let _ = resp.send(result);
+                }
+                Some(ServiceRequest::ReadResource { span, uri, resp }) => {
+                    let result = read_resource_from_service(&server_name, &service, uri)
+                        .instrument(span)
+                        .await;
+                    let _ = resp.send(result);
+                }
+                Some(ServiceRequest::ListPrompts { span, resp }) => {
+                    let result =
+                        list_prompts_from_service(&server_name, &service, settings.max_list_pages)
+                            .instrument(span)
+                            .await;
+                    let _ = resp.send(result);
+                }
+                Some(ServiceRequest::GetPrompt {
+                    span,
+                    request,
+                    resp,
+                }) => {
+                    let result = get_prompt_from_service(&server_name, &service, request)
+                        .instrument(span)
+                        .await;
+                    let _ = resp.send(result);
+                }
+                Some(ServiceRequest::GetTask {
+                    span,
+                    task_id,
+                    resp,
+                }) => {
+                    let result = get_task_from_service(&server_name, &service, task_id)
+                        .instrument(span)
+                        .await
+                        .and_then(|info| Ok(serde_json::to_value(info)?));
+                    let _ = resp.send(result);
+                }
+                Some(ServiceRequest::GetTaskResult {
+                    span,
+                    task_id,
+                    resp,
+                }) => {
+                    let result = get_task_result_from_service(&server_name, &service, task_id)
+                        .instrument(span)
+                        .await;
                     let _ = resp.send(result);
                 }
                 Some(ServiceRequest::Stop { resp }) => {
@@ -78,6 +232,7 @@ pub(crate) fn spawn_runtime(
                     break;
                 }
             }
+            pending_clone.fetch_sub(1, Ordering::Relaxed);
         }
     });
 
@@ -85,6 +240,8 @@ pub(crate) fn spawn_runtime(
         tx,
         state,
         join: Arc::new(Mutex::new(Some(join))),
+        server_info,
+        pending,
     }
 }
 
@@ -93,13 +250,57 @@ impl McpRuntimeHandle {
         self.state.read().await.clone()
     }
 
+    /// The upstream's initialize result (protocol version, capabilities, instructions)
+    pub(crate) fn server_info(&self) -> Option<&ServerInfo> {
+        self.server_info.as_deref()
+    }
+
+    /// Requests queued for or being handled by the worker
+    pub(crate) fn pending_requests(&self) -> usize {
+        self.pending.load(Ordering::Relaxed)
+    }
+
+    /// Capabilities captured from the upstream's initialize result
+    pub(crate) fn capabilities(&self) -> Option<UpstreamCapabilities> {
+        self.server_info().map(convert_server_info)
+    }
+
     pub(crate) async fn list_tools(&self, server_name: &str) -> Result<Vec<ToolDefinition>> {
         self.ensure_running(server_name).await?;
 
         let (resp_tx, resp_rx) = oneshot::channel();
         if self
-            .tx
-            .send(ServiceRequest::ListTools { resp: resp_tx })
+            .submit(ServiceRequest::ListTools {
+                span: Span::current(),
+                resp: resp_tx,
+            })
+            .await
+            .is_err()
+        {
+            return Err(self
+                .runtime_failed(server_name, "worker channel closed")
+                .await);
+        }
+
+        resp_rx
+            .await
+            .map_err(|_| ProxyError::mcp_cancelled("list tools", server_name))?
+    }
+
+    pub(crate) async fn list_tools_page(
+        &self,
+        server_name: &str,
+        cursor: Option<String>,
+    ) -> Result<ToolListPage> {
+        self.ensure_running(server_name).await?;
+
+        let (resp_tx, resp_rx) = oneshot::channel();
+        if self
+            .submit(ServiceRequest::ListToolsPage {
+                span: Span::current(),
+                cursor,
+                resp: resp_tx,
+            })
             .await
             .is_err()
         {
@@ -122,8 +323,8 @@ impl McpRuntimeHandle {
 
         let (resp_tx, resp_rx) = oneshot::channel();
         if self
-            .tx
-            .send(ServiceRequest::CallTool {
+            .submit(ServiceRequest::CallTool {
+                span: Span::current(),
                 request,
                 resp: resp_tx,
             })
@@ -140,13 +341,163 @@ impl McpRuntimeHandle {
             .map_err(|_| ProxyError::mcp_cancelled("call tool", server_name))?
     }
 
+    pub(crate) async fn list_resources(
+        &self,
+        server_name: &str,
+    ) -> Result<Vec<ResourceDefinition>> {
+        self.ensure_running(server_name).await?;
+
+        let (resp_tx, resp_rx) = oneshot::channel();
+        if self
+            .submit(ServiceRequest::ListResources {
+                span: Span::current(),
+                resp: resp_tx,
+            })
+            .await
+            .is_err()
+        {
+            return Err(self
+                .runtime_failed(server_name, "worker channel closed")
+                .await);
+        }
+
+        resp_rx
+            .await
+            .map_err(|_| ProxyError::mcp_cancelled("list resources", server_name))?
+    }
+
+    pub(crate) async fn read_resource(
+        &self,
+        server_name: &str,
+        uri: String,
+    ) -> Result<Vec<ResourceContent>> {
+        self.ensure_running(server_name).await?;
+
+        let (resp_tx, resp_rx) = oneshot::channel();
+        if self
+            .submit(ServiceRequest::ReadResource {
+                span: Span::current(),
+                uri,
+                resp: resp_tx,
+            })
+            .await
+            .is_err()
+        {
+            return Err(self
+                .runtime_failed(server_name, "worker channel closed")
+                .await);
+        }
+
+        resp_rx
+            .await
+            .map_err(|_| ProxyError::mcp_cancelled("read resource", server_name))?
+    }
+
+    pub(crate) async fn list_prompts(&self, server_name: &str) -> Result<Vec<PromptDefinition>> {
+        self.ensure_running(server_name).await?;
+
+        let (resp_tx, resp_rx) = oneshot::channel();
+        if self
+            .submit(ServiceRequest::ListPrompts {
+                span: Span::current(),
+                resp: resp_tx,
+            })
+            .await
+            .is_err()
+        {
+            return Err(self
+                .runtime_failed(server_name, "worker channel closed")
+                .await);
+        }
+
+        resp_rx
+            .await
+            .map_err(|_| ProxyError::mcp_cancelled("list prompts", server_name))?
+    }
+
+    pub(crate) async fn get_prompt(
+        &self,
+        server_name: &str,
+        request: PromptGetRequest,
+    ) -> Result<PromptGetResponse> {
+        self.ensure_running(server_name).await?;
+
+        let (resp_tx, resp_rx) = oneshot::channel();
+        if self
+            .submit(ServiceRequest::GetPrompt {
+                span: Span::current(),
+                request,
+                resp: resp_tx,
+            })
+            .await
+            .is_err()
+        {
+            return Err(self
+                .runtime_failed(server_name, "worker channel closed")
+                .await);
+        }
+
+        resp_rx
+            .await
+            .map_err(|_| ProxyError::mcp_cancelled("get prompt", server_name))?
+    }
+
+    pub(crate) async fn get_task(&self, server_name: &str, task_id: String) -> Result<Value> {
+        self.ensure_running(server_name).await?;
+
+        let (resp_tx, resp_rx) = oneshot::channel();
+        if self
+            .submit(ServiceRequest::GetTask {
+                span: Span::current(),
+                task_id,
+                resp: resp_tx,
+            })
+            .await
+            .is_err()
+        {
+            return Err(self
+                .runtime_failed(server_name, "worker channel closed")
+                .await);
+        }
+
+        resp_rx
+            .await
+            .map_err(|_| ProxyError::mcp_cancelled("get task", server_name))?
+    }
+
+    pub(crate) async fn get_task_result(
+        &self,
+        server_name: &str,
+        task_id: String,
+    ) -> Result<Value> {
+        self.ensure_running(server_name).await?;
+
+        let (resp_tx, resp_rx) = oneshot::channel();
+        if self
+            .submit(ServiceRequest::GetTaskResult {
+                span: Span::current(),
+                task_id,
+                resp: resp_tx,
+            })
+            .await
+            .is_err()
+        {
+            return Err(self
+                .runtime_failed(server_name, "worker channel closed")
+                .await);
+        }
+
+        resp_rx
+            .await
+            .map_err(|_| ProxyError::mcp_cancelled("get task result", server_name))?
+    }
+
     pub(crate) async fn stop(&self, server_name: &str) -> Result<()> {
         self.ensure_running(server_name).await?;
 
         let (resp_tx, resp_rx) = oneshot::channel();
         if self
-            .tx
-            .send(ServiceRequest::Stop { resp: resp_tx })
+            .submit(ServiceRequest::Stop { resp: resp_tx })
             .await
             .is_err()
         {
@@ -175,6 +526,44 @@ impl McpRuntimeHandle {
         Ok(())
     }
 
+    /// Stop the worker, aborting it when a graceful close doesn't finish in time
+    /// Aborting drops the service and its transport, which kills a child process,
+    /// and leaves the runtime `Failed` so the forced stop stays visible
+    pub(crate) async fn stop_within(&self, server_name: &str, timeout: Duration) -> Result<()> {
+        match tokio::time::timeout(timeout, self.stop(server_name)).await {
+            Ok(result) => result,
+            Err(_) => {
+                warn!(
+                    "Graceful stop of {} timed out after {:?}, aborting worker",
+                    server_name, timeout
+                );
+                self.abort(format!(
+                    "graceful stop timed out after {:?}; worker aborted",
+                    timeout
+                ))
+                .await;
+                Ok(())
+            }
+        }
+    }
+
+    async fn abort(&self, reason: String) {
+        if let Some(join_handle) = self.join.lock().await.take() {
+            join_handle.abort();
+        }
+        *self.state.write().await = RuntimeState::Failed(reason);
+    }
+
+    /// Queue a request for the worker, tagged with the caller's request ID
+    async fn submit(&self, request: ServiceRequest) -> std::result::Result<(), ()> {
+        self.pending.fetch_add(1, Ordering::Relaxed);
+        let sent = self.tx.send((request_id::current(), request)).await;
+        if sent.is_err() {
+            self.pending.fetch_sub(1, Ordering::Relaxed);
+        }
+        sent.map_err(drop)
+    }
+
     async fn ensure_running(&self, server_name: &str) -> Result<()> {
         match self.state.read().await.clone() {
             RuntimeState::Running => Ok(()),
@@ -203,50 +592,290 @@ async fn set_state(state: &Arc<RwLock<RuntimeState>>, result: &Result<()>) {
 
 async fn list_tools_from_service(
     server_name: &str,
-    service: &RunningService<RoleClient, ()>,
+    service: &RunningService<RoleClient, ClientInfo>,
+    max_pages: usize,
 ) -> Result<Vec<ToolDefinition>> {
     debug!("Listing tools for server: {}", server_name);
 
-    let mut tool_list = Vec::new();
-    let mut cursor: Option<String> = None;
+    let tool_list = collect_pages(server_name, max_pages, |cursor| async move {
+        let page = list_tools_page_from_service(server_name, service, cursor).await?;
+        Ok((page.tools, page.next_cursor))
+    })
+    .await?;
 
-    loop {
-        let request = Some(PaginatedRequestParams {
-            meta: None,
-            cursor: cursor.clone(),
-        });
+    debug!(
+        "Found {} tools for server: {}",
+        tool_list.len(),
+        server_name
+    );
+    Ok(tool_list)
+}
 
-        match service.list_tools(request).await {
-            Ok(result) => {
-                tool_list.extend(result.tools.into_iter().map(|t| ToolDefinition {
+/// Fetch a single page of tools starting at `cursor`
+async fn list_tools_page_from_service(
+    server_name: &str,
+    service: &RunningService<RoleClient, ClientInfo>,
+    cursor: Option<String>,
+) -> Result<ToolListPage> {
+    let request = Some(PaginatedRequestParams { meta: None, cursor });
+
+    match service.list_tools(request).await {
+        Ok(result) => {
+            let tools = result
+                .tools
+                .into_iter()
+                .map(|t| ToolDefinition {
                     name: t.name.to_string(),
                     description: t.description.map(|d| d.to_string()),
                     input_schema: Value::Object((*t.input_schema).clone()),
-                }));
+                    output_schema: t.output_schema.map(|s| Value::Object((*s).clone())),
+                    annotations: t.annotations.and_then(|a| serde_json::to_value(a).ok()),
+                })
+                .collect();
+            Ok(ToolListPage {
+                tools,
+                next_cursor: result.next_cursor,
+            })
+        }
+        Err(e) => {
+            error!("Failed to list tools for {}: {}", server_name, e);
+            Err(ProxyError::mcp_service_error("list tools", e))
+        }
+    }
+}
 
-                cursor = result.next_cursor;
-                if cursor.is_none() {
-                    break;
-                }
+async fn list_resources_from_service(
+    server_name: &str,
+    service: &RunningService<RoleClient, ClientInfo>,
+    max_pages: usize,
+) -> Result<Vec<ResourceDefinition>> {
+    debug!("Listing resources for server: {}", server_name);
+
+    let resources = collect_pages(server_name, max_pages, |cursor| async move {
+        let request = Some(PaginatedRequestParams { meta: None, cursor });
+
+        match service.list_resources(request).await {
+            Ok(result) => {
+                let resources = result
+                    .resources
+                    .into_iter()
+                    .map(|r| ResourceDefinition {
+                        uri: r.raw.uri,
+                        name: r.raw.name,
+                        description: r.raw.description,
+                        mime_type: r.raw.mime_type,
+                    })
+                    .collect();
+                Ok((resources, result.next_cursor))
             }
             Err(e) => {
-                error!("Failed to list tools for {}: {}", server_name, e);
-                return Err(ProxyError::mcp_service_error("list tools", e));
+                error!("Failed to list resources for {}: {}", server_name, e);
+                Err(ProxyError::mcp_service_error("list resources", e))
             }
         }
+    })
+    .await?;
+
+    debug!(
+        "Found {} resources for server: {}",
+        resources.len(),
+        server_name
+    );
+    Ok(resources)
+}
+
+async fn read_resource_from_service(
+    server_name: &str,
+    service: &RunningService<RoleClient, ClientInfo>,
+    uri: String,
+) -> Result<Vec<ResourceContent>> {
+    debug!("Reading resource '{}' on server: {}", uri, server_name);
+
+    let request = ReadResourceRequestParams {
+        meta: None,
+        uri: uri.clone(),
+    };
+
+    match service.read_resource(request).await {
+        Ok(result) => Ok(result
+            .contents
+            .into_iter()
+            .map(convert_resource_contents)
+            .collect()),
+        Err(e) => {
+            error!(
+                "Failed to read resource '{}' on {}: {}",
+                uri, server_name, e
+            );
+            Err(ProxyError::mcp_service_error("read resource", e))
+        }
     }
+}
+
+async fn list_prompts_from_service(
+    server_name: &str,
+    service: &RunningService<RoleClient, ClientInfo>,
+    max_pages: usize,
+) -> Result<Vec<PromptDefinition>> {
+    debug!("Listing prompts for server: {}", server_name);
+
+    let prompts = collect_pages(server_name, max_pages, |cursor| async move {
+        let request = Some(PaginatedRequestParams { meta: None, cursor });
+
+        match service.list_prompts(request).await {
+            Ok(result) => {
+                let prompts = result
+                    .prompts
+                    .into_iter()
+                    .map(|p| PromptDefinition {
+                        name: p.name,
+                        description: p.description,
+                        arguments: p
+                            .arguments
+                            .unwrap_or_default()
+                            .into_iter()
+                            .map(|a| PromptArgument {
+                                name: a.name,
+                                description: a.description,
+                                required: a.required.unwrap_or(false),
+                            })
+                            .collect(),
+                    })
+                    .collect();
+                Ok((prompts, result.next_cursor))
+            }
+            Err(e) => {
+                error!("Failed to list prompts for {}: {}", server_name, e);
+                Err(ProxyError::mcp_service_error("list prompts", e))
+            }
+        }
+    })
+    .await?;
 
     debug!(
-        "Found {} tools for server: {}",
-        tool_list.len(),
+        "Found {} prompts for server: {}",
+        prompts.len(),
         server_name
     );
-    Ok(tool_list)
+    Ok(prompts)
+}
+
+async fn get_prompt_from_service(
+    server_name: &str,
+    service: &RunningService<RoleClient, ClientInfo>,
+    request: PromptGetRequest,
+) -> Result<PromptGetResponse> {
+    debug!(
+        "Getting prompt '{}' on server: {}",
+        request.name, server_name
+    );
+
+    let params = GetPromptRequestParams {
+        meta: None,
+        name: request.name.clone(),
+        arguments: request.arguments,
+    };
+
+    match service.get_prompt(params).await {
+        Ok(result) => convert_get_prompt_result(result),
+        Err(e) => {
+            error!(
+                "Failed to get prompt '{}' on {}: {}",
+                request.name, server_name, e
+            );
+            Err(ProxyError::mcp_service_error("get prompt", e))
+        }
+    }
+}
+
+fn convert_get_prompt_result(result: GetPromptResult) -> Result<PromptGetResponse> {
+    let messages = result
+        .messages
+        .into_iter()
+        .map(|message| {
+            Ok(PromptMessage {
+                role: match message.role {
+                    PromptMessageRole::User => PromptRole::User,
+                    PromptMessageRole::Assistant => PromptRole::Assistant,
+                },
+                content: serde_json::to_value(message.content)?,
+            })
+        })
+        .collect::<Result<_>>()?;
+
+    Ok(PromptGetResponse {
+        description: result.description,
+        messages,
+    })
+}
+
+fn convert_resource_contents(contents: ResourceContents) -> ResourceContent {
+    match contents {
+        ResourceContents::TextResourceContents {
+            uri,
+            mime_type,
+            text,
+            ..
+        } => ResourceContent::Text {
+            uri,
+            mime_type,
+            text,
+        },
+        ResourceContents::BlobResourceContents {
+            uri,
+            mime_type,
+            blob,
+            ..
+        } => ResourceContent::Blob {
+            uri,
+            mime_type,
+            blob,
+        },
+    }
+}
+
+/// Walk a cursor-paginated upstream list, guarding against cursors that loop
+/// or never terminate so a malformed upstream can't hang the worker
+async fn collect_pages<T, F, Fut>(
+    server_name: &str,
+    max_pages: usize,
+    mut fetch_page: F,
+) -> Result<Vec<T>>
+where
+    F: FnMut(Option<String>) -> Fut,
+    Fut: Future<Output = Result<(Vec<T>, Option<String>)>>,
+{
+    let mut items = Vec::new();
+    let mut seen_cursors = HashSet::new();
+    let mut cursor: Option<String> = None;
+
+    for _ in 0..max_pages {
+        let (page, next_cursor) = fetch_page(cursor.take()).await?;
+        items.extend(page);
+
+        match next_cursor {
+            None => return Ok(items),
+            Some(next) => {
+                if !seen_cursors.insert(next.clone()) {
+                    return Err(ProxyError::mcp_protocol(format!(
+                        "Pagination cursor '{}' repeated by {}; aborting list",
+                        next, server_name
+                    )));
+                }
+                cursor = Some(next);
+            }
+        }
+    }
+
+    Err(ProxyError::mcp_protocol(format!(
+        "Pagination from {} exceeded {} pages; aborting list",
+        server_name, max_pages
+    )))
 }
 
 async fn call_tool_on_service(
     server_name: &str,
-    service: &RunningService<RoleClient, ()>,
+    service: &RunningService<RoleClient, ClientInfo>,
     request: ToolCallRequest,
 ) -> Result<ToolCallResponse> {
     debug!("Calling tool '{}' on server: {}", request.name, server_name);
@@ -255,41 +884,34 @@ async fn call_tool_on_service(
         meta: None,
         name: request.name.clone().into(),
         arguments: request.arguments.as_object().cloned(),
-        task: None,
+        task: request.task.clone(),
     };
 
-    match service.call_tool(mcp_request).await {
+    // Send the raw request so a task-augmented call can answer with CreateTaskResult;
+    // upstreams without task support ignore the field and answer synchronously
+    match service
+        .send_request(ClientRequest::CallToolRequest(CallToolRequest::new(
+            mcp_request,
+        )))
+        .await
+    {
+        Ok(ServerResult::CallToolResult(result)) => Ok(convert_call_tool_result(result)),
         Ok(result) => {
-            let response_content: Vec<ToolContent> = result
-                .content
-                .into_iter()
-                .filter_map(|c| match c.raw {
-                    RawContent::Text(text_content) => Some(ToolContent::Text {
-                        text: text_content.text,
-                    }),
-                    RawContent::Image(image_content) => Some(ToolContent::Image {
-                        data: image_content.data,
-                        mime_type: image_content.mime_type,
-                    }),
-                    RawContent::Resource(resource_content) => match resource_content.resource {
-                        rmcp::model::ResourceContents::TextResourceContents {
-                            uri,
-                            mime_type,
-                            ..
-                        } => Some(ToolContent::Resource { uri, mime_type }),
-                        rmcp::model::ResourceContents::BlobResourceContents {
-                            uri,
-                            mime_type,
-                            ..
-                        } => Some(ToolContent::Resource { uri, mime_type }),
-                    },
-                    _ => None,
-                })
-                .collect();
-
+            let created = task_response::<CreateTaskResult>(result).ok_or_else(|| {
+                ProxyError::mcp_protocol(format!(
+                    "Unexpected response to tool call '{}' from {}",
+                    request.name, server_name
+                ))
+            })?;
+            debug!(
+                "Tool '{}' on {} accepted as task {}",
+                request.name, server_name, created.task.task_id
+            );
             Ok(ToolCallResponse {
-                content: response_content,
-                is_error: result.is_error,
+                content: Vec::new(),
+                is_error: None,
+                structured_content: None,
+                task: Some(serde_json::to_value(created.task)?),
             })
         }
         Err(e) => {
@@ -301,3 +923,349 @@ async fn call_tool_on_service(
         }
     }
 }
+
+fn convert_server_info(info: &ServerInfo) -> UpstreamCapabilities {
+    UpstreamCapabilities {
+        protocol_version: info.protocol_version.to_string(),
+        server_name: info.server_info.name.clone(),
+        server_version: info.server_info.version.clone(),
+        tools: info.capabilities.tools.is_some(),
+        resources: info.capabilities.resources.is_some(),
+        prompts: info.capabilities.prompts.is_some(),
+        logging: info.capabilities.logging.is_some(),
+    }
+}
+
+fn convert_call_tool_result(result: CallToolResult) -> ToolCallResponse {
+    let response_content: Vec<ToolContent> = result
+        .content
+        .into_iter()
+        .filter_map(|c| match c.raw {
+            RawContent::Text(text_content) => Some(ToolContent::Text {
+                text: text_content.text,
+            }),
+            RawContent::Image(image_content) => Some(ToolContent::Image {
+                data: image_content.data,
+                mime_type: image_content.mime_type,
+            }),
+            RawContent::Resource(resource_content) => match resource_content.resource {
+                rmcp::model::ResourceContents::TextResourceContents {
+                    uri,
+                    mime_type,
+                    text,
+                    ..
+                } => Some(ToolContent::Resource {
+                    uri,
+                    mime_type,
+                    text: Some(text),
+                    blob: None,
+                }),
+                rmcp::model::ResourceContents::BlobResourceContents {
+                    uri,
+                    mime_type,
+                    blob,
+                    ..
+                } => Some(ToolContent::Resource {
+                    uri,
+                    mime_type,
+                    text: None,
+                    blob: Some(blob),
+                }),
+            },
+            _ => None,
+        })
+        .collect();
+
+    ToolCallResponse {
+        content: response_content,
+        is_error: result.is_error,
+        structured_content: result.structured_content,
+        task: None,
+    }
+}
+
+/// Task responses are untagged, so rmcp usually hands them over as a `CustomResult`
+/// rather than the typed variant; read them back from their JSON either way
+fn task_response<T: DeserializeOwned>(result: ServerResult) -> Option<T> {
+    serde_json::to_value(result)
+        .ok()
+        .and_then(|value| serde_json::from_value(value).ok())
+}
+
+async fn get_task_from_service(
+    server_name: &str,
+    service: &RunningService<RoleClient, ClientInfo>,
+    task_id: String,
+) -> Result<GetTaskInfoResult> {
+    debug!("Getting task '{}' on server: {}", task_id, server_name);
+
+    let request = GetTaskInfoRequest::new(GetTaskInfoParams {
+        meta: None,
+        task_id,
+    });
+
+    match service
+        .send_request(ClientRequest::GetTaskInfoRequest(request))
+        .await
+    {
+        Ok(result) => task_response(result).ok_or_else(|| {
+            ProxyError::mcp_protocol(format!(
+                "Unexpected response to task status request from {}",
+                server_name
+            ))
+        }),
+        Err(e) => Err(ProxyError::mcp_service_error("get task", e)),
+    }
+}
+
+/// `tasks/result` blocks until the task finishes, which would hold up every other request
+/// on this worker, so the status is checked first and unfinished tasks are refused
+async fn get_task_result_from_service(
+    server_name: &str,
+    service: &RunningService<RoleClient, ClientInfo>,
+    task_id: String,
+) -> Result<Value> {
+    debug!(
+        "Getting result of task '{}' on server: {}",
+        task_id, server_name
+    );
+
+    let info = get_task_from_service(server_name, service, task_id.clone()).await?;
+    if let Some(task) = info.task
+        && matches!(task.status, TaskStatus::Working | TaskStatus::InputRequired)
+    {
+        let status = serde_json::to_value(&task.status)?;
+        return Err(ProxyError::task_not_finished(
+            &task_id,
+            status.as_str().unwrap_or_default(),
+        ));
+    }
+
+    let request = GetTaskResultRequest::new(GetTaskResultParams {
+        meta: None,
+        task_id,
+    });
+
+    match service
+        .send_request(ClientRequest::GetTaskResultRequest(request))
+        .await
+    {
+        Ok(ServerResult::CallToolResult(result)) => {
+            Ok(serde_json::to_value(convert_call_tool_result(result))?)
+        }
+        Ok(result) => task_response::<TaskResult>(result)
+            .map(serde_json::to_value)
+            .transpose()?
+            .ok_or_else(|| {
+                ProxyError::mcp_protocol(format!(
+                    "Unexpected response to task result request from {}",
+                    server_name
+                ))
+            }),
+        Err(e) => Err(ProxyError::mcp_service_error("get task result", e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::Content;
+
+    #[derive(Clone)]
+    struct TestServer;
+
+    impl rmcp::ServerHandler for TestServer {}
+
+    struct ToolsServer;
+
+    impl rmcp::ServerHandler for ToolsServer {
+        fn get_info(&self) -> ServerInfo {
+            ServerInfo {
+                capabilities: rmcp::model::ServerCapabilities::builder()
+                    .enable_tools()
+                    .build(),
+                ..Default::default()
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_runtime_captures_upstream_capabilities() {
+        use rmcp::ServiceExt;
+
+        let (client_io, server_io) = tokio::io::duplex(4096);
+        let server = tokio::spawn(async move { ToolsServer.serve(server_io).await });
+        let service = ClientInfo::default().serve(client_io).await.unwrap();
+        let _server = server.await.unwrap().unwrap();
+
+        let handle = spawn_runtime(
+            "test-server".to_string(),
+            service,
+            McpClientSettings::default(),
+            CurrentRequestId::default(),
+        );
+
+        let capabilities = handle.capabilities().unwrap();
+        assert!(capabilities.tools);
+        assert!(!capabilities.resources);
+        assert!(!capabilities.prompts);
+        assert!(!capabilities.logging);
+        assert!(!capabilities.protocol_version.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_runtime_fails_when_transport_closes() {
+        use rmcp::ServiceExt;
+
+        let (client_io, server_io) = tokio::io::duplex(4096);
+        let server = tokio::spawn(async move { TestServer.serve(server_io).await });
+        let service = ClientInfo::default().serve(client_io).await.unwrap();
+        let server = server.await.unwrap().unwrap();
+
+        let handle = spawn_runtime(
+            "test-server".to_string(),
+            service,
+            McpClientSettings::default(),
+            CurrentRequestId::default(),
+        );
+        assert_eq!(handle.state().await, RuntimeState::Running);
+
+        // Simulate the upstream dying
+        server.cancel().await.unwrap();
+
+        let failed = tokio::time::timeout(Duration::from_secs(5), async {
+            while handle.state().await == RuntimeState::Running {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await;
+        assert!(failed.is_ok());
+        assert!(matches!(handle.state().await, RuntimeState::Failed(_)));
+    }
+
+    #[tokio::test]
+    async fn test_stop_within_aborts_wedged_worker() {
+        // A worker that accepts the stop request but never answers it
+        let (tx, mut rx) = mpsc::channel(1);
+        let join = tokio::spawn(async move {
+            let _request = rx.recv().await;
+            std::future::pending::<()>().await;
+        });
+        let handle = McpRuntimeHandle {
+            tx,
+            state: Arc::new(RwLock::new(RuntimeState::Running)),
+            join: Arc::new(Mutex::new(Some(join))),
+            server_info: None,
+            pending: Default::default(),
+        };
+
+        let started = std::time::Instant::now();
+        let result = handle
+            .stop_within("test-server", Duration::from_millis(50))
+            .await;
+        assert!(result.is_ok());
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert_eq!(
+            handle.state().await,
+            RuntimeState::Failed("graceful stop timed out after 50ms; worker aborted".to_string())
+        );
+        assert!(handle.join.lock().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_collect_pages_follows_cursors() {
+        let items = collect_pages("test-server", 10, |cursor| async move {
+            match cursor.as_deref() {
+                None => Ok((vec![1, 2], Some("page-2".to_string()))),
+                Some("page-2") => Ok((vec![3], None)),
+                Some(other) => panic!("unexpected cursor {}", other),
+            }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(items, vec![1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_collect_pages_rejects_repeated_cursor() {
+        let result = collect_pages("test-server", 100, |_cursor| async move {
+            Ok((vec![1], Some("same-cursor".to_string())))
+        })
+        .await;
+
+        let err = result.unwrap_err();
+        assert!(matches!(err, ProxyError::McpProtocol(_)));
+        assert!(err.to_string().contains("repeated"));
+    }
+
+    #[tokio::test]
+    async fn test_collect_pages_enforces_max_pages() {
+        let mut page = 0;
+        let result = collect_pages("test-server", 5, |_cursor| {
+            page += 1;
+            let next = format!("cursor-{}", page);
+            async move { Ok((vec![page], Some(next))) }
+        })
+        .await;
+
+        let err = result.unwrap_err();
+        assert!(matches!(err, ProxyError::McpProtocol(_)));
+        assert!(err.to_string().contains("exceeded 5 pages"));
+    }
+
+    #[test]
+    fn test_convert_call_tool_result_keeps_text_and_error_flag() {
+        let result = CallToolResult::error(vec![Content::text("boom")]);
+
+        let response = convert_call_tool_result(result);
+        assert_eq!(response.is_error, Some(true));
+        assert!(response.task.is_none());
+        assert!(matches!(
+            response.content.as_slice(),
+            [ToolContent::Text { text }] if text == "boom"
+        ));
+    }
+
+    #[test]
+    fn test_embedded_resources_round_trip_through_bridge() {
+        use rmcp::model::ResourceContents;
+
+        let text = ResourceContents::TextResourceContents {
+            uri: "file:///notes.txt".to_string(),
+            mime_type: Some("text/plain".to_string()),
+            text: "hello".to_string(),
+            meta: None,
+        };
+        let blob = ResourceContents::BlobResourceContents {
+            uri: "file:///logo.png".to_string(),
+            mime_type: Some("image/png".to_string()),
+            blob: "iVBORw0K".to_string(),
+            meta: None,
+        };
+        let result = CallToolResult::success(vec![
+            Content::resource(text.clone()),
+            Content::resource(blob.clone()),
+        ]);
+
+        let response = convert_call_tool_result(result);
+        assert_eq!(
+            serde_json::to_value(&response.content).unwrap(),
+            serde_json::json!([
+                {"type": "resource", "uri": "file:///notes.txt", "mime_type": "text/plain", "text": "hello"},
+                {"type": "resource", "uri": "file:///logo.png", "mime_type": "image/png", "blob": "iVBORw0K"},
+            ])
+        );
+
+        let forwarded = crate::mcp::bridge::build_rmcp_result(response);
+        let resources: Vec<_> = forwarded
+            .content
+            .into_iter()
+            .map(|content| match content.raw {
+                RawContent::Resource(resource) => resource.resource,
+                other => panic!("expected a resource, got {:?}", other),
+            })
+            .collect();
+        assert_eq!(resources, [text, blob]);
+    }
+}
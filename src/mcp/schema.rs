@@ -0,0 +1,64 @@
+// JSON Schema validation helpers for tool inputs and outputs
+
+use serde_json::Value;
+
+/// Validate an instance against a JSON Schema, returning human-readable violations
+/// An invalid schema is reported as a single violation rather than an error
+pub(crate) fn schema_violations(schema: &Value, instance: &Value) -> Vec<String> {
+    let validator = match jsonschema::validator_for(schema) {
+        Ok(validator) => validator,
+        Err(e) => return vec![format!("Invalid schema: {}", e)],
+    };
+
+    validator
+        .iter_errors(instance)
+        .map(|error| {
+            let path = error.instance_path().to_string();
+            if path.is_empty() {
+                error.to_string()
+            } else {
+                format!("{}: {}", path, error)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_schema_violations_valid_instance() {
+        let schema = json!({
+            "type": "object",
+            "properties": {"count": {"type": "integer"}},
+            "required": ["count"]
+        });
+
+        assert!(schema_violations(&schema, &json!({"count": 3})).is_empty());
+    }
+
+    #[test]
+    fn test_schema_violations_reports_errors() {
+        let schema = json!({
+            "type": "object",
+            "properties": {"count": {"type": "integer"}},
+            "required": ["count"]
+        });
+
+        let violations = schema_violations(&schema, &json!({"count": "three"}));
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].starts_with("/count"));
+
+        let violations = schema_violations(&schema, &json!({}));
+        assert_eq!(violations.len(), 1);
+    }
+
+    #[test]
+    fn test_schema_violations_invalid_schema() {
+        let violations = schema_violations(&json!({"type": 12}), &json!({}));
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].starts_with("Invalid schema"));
+    }
+}
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use sha2::{Digest, Sha256};
 
 /// Represents an MCP tool definition
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -7,6 +8,18 @@ pub(crate) struct ToolDefinition {
     pub name: String,
     pub description: Option<String>,
     pub input_schema: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_schema: Option<Value>,
+    /// Behavioural hints such as `readOnlyHint`, passed through from upstream
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<Value>,
+}
+
+/// One page of an upstream tool listing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct ToolListPage {
+    pub tools: Vec<ToolDefinition>,
+    pub next_cursor: Option<String>,
 }
 
 /// Request to call an MCP tool
@@ -14,6 +27,9 @@ pub(crate) struct ToolDefinition {
 pub(crate) struct ToolCallRequest {
     pub name: String,
     pub arguments: Value,
+    /// Task metadata requesting task-based (async) execution upstream
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task: Option<serde_json::Map<String, Value>>,
 }
 
 /// Response from an MCP tool call
@@ -22,6 +38,23 @@ pub(crate) struct ToolCallResponse {
     pub content: Vec<ToolContent>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub is_error: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub structured_content: Option<Value>,
+    /// Upstream task handle when the call was accepted for async execution
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task: Option<Value>,
+}
+
+/// What an upstream server declared about itself during the MCP handshake
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct UpstreamCapabilities {
+    pub protocol_version: String,
+    pub server_name: String,
+    pub server_version: String,
+    pub tools: bool,
+    pub resources: bool,
+    pub prompts: bool,
+    pub logging: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -37,5 +70,149 @@ pub(crate) enum ToolContent {
     Resource {
         uri: String,
         mime_type: Option<String>,
+        /// Contents of a text resource
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        text: Option<String>,
+        /// Base64-encoded contents of a binary resource
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        blob: Option<String>,
+    },
+}
+
+/// A resource advertised by an MCP server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ResourceDefinition {
+    pub uri: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+}
+
+/// One item of a read resource: UTF-8 text or base64-encoded bytes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged, rename_all_fields = "camelCase")]
+pub(crate) enum ResourceContent {
+    Text {
+        uri: String,
+        mime_type: Option<String>,
+        text: String,
+    },
+    Blob {
+        uri: String,
+        mime_type: Option<String>,
+        blob: String,
     },
 }
+
+/// A prompt template advertised by an MCP server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct PromptDefinition {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub arguments: Vec<PromptArgument>,
+}
+
+/// A named argument a prompt template accepts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct PromptArgument {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub required: bool,
+}
+
+/// Request to render an MCP prompt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct PromptGetRequest {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arguments: Option<serde_json::Map<String, Value>>,
+}
+
+/// A rendered prompt, ready to be sent to a model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct PromptGetResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub messages: Vec<PromptMessage>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum PromptRole {
+    User,
+    Assistant,
+}
+
+/// One message of a rendered prompt
+/// `content` keeps the upstream's MCP content block as-is (text, image or resource)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct PromptMessage {
+    pub role: PromptRole,
+    pub content: Value,
+}
+
+/// Compute a stable fingerprint of a tool list for change detection
+/// Tools are hashed in name order so upstream ordering changes don't affect the result
+pub(crate) fn fingerprint_tools(tools: &[ToolDefinition]) -> String {
+    let mut sorted: Vec<&ToolDefinition> = tools.iter().collect();
+    sorted.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut hasher = Sha256::new();
+    for tool in sorted {
+        // serde_json serializes object keys in sorted order, so this is deterministic
+        let encoded = serde_json::to_vec(tool).unwrap_or_default();
+        hasher.update((encoded.len() as u64).to_be_bytes());
+        hasher.update(&encoded);
+    }
+
+    format!("{:x}", hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn create_test_tool(name: &str, description: &str) -> ToolDefinition {
+        ToolDefinition {
+            name: name.to_string(),
+            description: Some(description.to_string()),
+            input_schema: json!({"type": "object"}),
+            output_schema: None,
+            annotations: None,
+        }
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_tool_order() {
+        let a = vec![
+            create_test_tool("a", "first"),
+            create_test_tool("b", "second"),
+        ];
+        let b = vec![
+            create_test_tool("b", "second"),
+            create_test_tool("a", "first"),
+        ];
+
+        assert_eq!(fingerprint_tools(&a), fingerprint_tools(&b));
+    }
+
+    #[test]
+    fn test_fingerprint_detects_changes() {
+        let before = vec![create_test_tool("a", "first")];
+        let changed = vec![create_test_tool("a", "changed")];
+        let added = vec![create_test_tool("a", "first"), create_test_tool("b", "new")];
+
+        let fingerprint = fingerprint_tools(&before);
+        assert_ne!(fingerprint, fingerprint_tools(&changed));
+        assert_ne!(fingerprint, fingerprint_tools(&added));
+        assert_eq!(fingerprint.len(), 64);
+    }
+}
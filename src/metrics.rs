@@ -0,0 +1,278 @@
+// Prometheus metrics exported at /metrics
+
+use crate::config::LifecycleEvent;
+use crate::endpoint::registry::{EndpointInfo, EndpointStatus};
+use crate::error::{ProxyError, Result};
+use prometheus::{
+    HistogramOpts, HistogramVec, IntCounterVec, IntGaugeVec, Opts, Registry, TextEncoder,
+};
+use std::time::Duration;
+
+/// Latency buckets in seconds, reaching past the default request timeout
+const LATENCY_BUCKETS: [f64; 11] = [0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0];
+
+/// Every status reported by the endpoint gauge, so idle statuses export as zero
+const STATUSES: [EndpointStatus; 5] = [
+    EndpointStatus::Starting,
+    EndpointStatus::Running,
+    EndpointStatus::Stopping,
+    EndpointStatus::Stopped,
+    EndpointStatus::Failed,
+];
+
+/// Upstream MCP requests timed per endpoint
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Operation {
+    List,
+    Call,
+    ListResources,
+    ReadResource,
+    ListPrompts,
+    GetPrompt,
+    GetTask,
+    GetTaskResult,
+}
+
+impl Operation {
+    fn as_str(self) -> &'static str {
+        match self {
+            Operation::List => "list",
+            Operation::Call => "call",
+            Operation::ListResources => "list_resources",
+            Operation::ReadResource => "read_resource",
+            Operation::ListPrompts => "list_prompts",
+            Operation::GetPrompt => "get_prompt",
+            Operation::GetTask => "get_task",
+            Operation::GetTaskResult => "get_task_result",
+        }
+    }
+}
+
+/// Counters and gauges describing proxy traffic and endpoint lifecycles
+pub struct Metrics {
+    registry: Registry,
+    tool_calls: IntCounterVec,
+    list_tools: IntCounterVec,
+    endpoint_events: IntCounterVec,
+    endpoints: IntGaugeVec,
+    request_duration: HistogramVec,
+    request_errors: IntCounterVec,
+}
+
+impl Metrics {
+    pub(crate) fn new() -> Self {
+        let registry = Registry::new();
+
+        let tool_calls = IntCounterVec::new(
+            Opts::new(
+                "rusted_tools_tool_calls_total",
+                "Tool calls forwarded upstream",
+            ),
+            &["endpoint", "result"],
+        )
+        .expect("valid tool call metric");
+        let list_tools = IntCounterVec::new(
+            Opts::new(
+                "rusted_tools_list_tools_total",
+                "Tool list requests forwarded upstream",
+            ),
+            &["endpoint", "result"],
+        )
+        .expect("valid list tools metric");
+        let endpoint_events = IntCounterVec::new(
+            Opts::new(
+                "rusted_tools_endpoint_events_total",
+                "Endpoint starts, stops, restarts and failures",
+            ),
+            &["endpoint", "event"],
+        )
+        .expect("valid endpoint event metric");
+        let endpoints = IntGaugeVec::new(
+            Opts::new("rusted_tools_endpoints", "Registered endpoints by status"),
+            &["status"],
+        )
+        .expect("valid endpoint gauge");
+        let request_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "rusted_tools_upstream_request_duration_seconds",
+                "Wall-clock duration of upstream MCP requests",
+            )
+            .buckets(LATENCY_BUCKETS.to_vec()),
+            &["endpoint", "operation"],
+        )
+        .expect("valid request duration metric");
+        let request_errors = IntCounterVec::new(
+            Opts::new(
+                "rusted_tools_upstream_request_errors_total",
+                "Failed upstream MCP requests by error kind",
+            ),
+            &["endpoint", "operation", "error"],
+        )
+        .expect("valid request error metric");
+
+        for collector in [&tool_calls, &list_tools, &endpoint_events, &request_errors] {
+            registry
+                .register(Box::new(collector.clone()))
+                .expect("metric registered once");
+        }
+        registry
+            .register(Box::new(endpoints.clone()))
+            .expect("metric registered once");
+        registry
+            .register(Box::new(request_duration.clone()))
+            .expect("metric registered once");
+
+        Self {
+            registry,
+            tool_calls,
+            list_tools,
+            endpoint_events,
+            endpoints,
+            request_duration,
+            request_errors,
+        }
+    }
+
+    pub(crate) fn record_tool_call<T>(&self, endpoint: &str, result: &Result<T>) {
+        self.tool_calls
+            .with_label_values(&[endpoint, result_label(result)])
+            .inc();
+    }
+
+    pub(crate) fn record_list_tools<T>(&self, endpoint: &str, result: &Result<T>) {
+        self.list_tools
+            .with_label_values(&[endpoint, result_label(result)])
+            .inc();
+    }
+
+    /// Record how long an upstream request took and, if it failed, why
+    pub(crate) fn observe_request<T>(
+        &self,
+        endpoint: &str,
+        operation: Operation,
+        elapsed: Duration,
+        result: &Result<T>,
+    ) {
+        self.request_duration
+            .with_label_values(&[endpoint, operation.as_str()])
+            .observe(elapsed.as_secs_f64());
+        if let Err(e) = result {
+            self.request_errors
+                .with_label_values(&[endpoint, operation.as_str(), e.kind()])
+                .inc();
+        }
+    }
+
+    pub(crate) fn record_event(&self, endpoint: &str, event: LifecycleEvent) {
+        self.endpoint_events
+            .with_label_values(&[endpoint, event_label(event)])
+            .inc();
+    }
+
+    /// Encode all metrics in the Prometheus text format, refreshing the status gauge first
+    pub(crate) fn render(&self, endpoints: &[EndpointInfo]) -> Result<String> {
+        for status in &STATUSES {
+            let count = endpoints.iter().filter(|e| &e.status == status).count();
+            self.endpoints
+                .with_label_values(&[status.to_string().as_str()])
+                .set(count as i64);
+        }
+
+        TextEncoder::new()
+            .encode_to_string(&self.registry.gather())
+            .map_err(|e| ProxyError::Internal(format!("failed to encode metrics: {}", e)))
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn result_label<T>(result: &Result<T>) -> &'static str {
+    if result.is_ok() { "success" } else { "error" }
+}
+
+fn event_label(event: LifecycleEvent) -> &'static str {
+    match event {
+        LifecycleEvent::Started => "started",
+        LifecycleEvent::Stopped => "stopped",
+        LifecycleEvent::Failed => "failed",
+        LifecycleEvent::Restarted => "restarted",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::endpoint::registry::{EndpointRegistry, EndpointType};
+
+    #[test]
+    fn test_render_counts_calls_by_endpoint_and_result() {
+        let metrics = Metrics::new();
+        metrics.record_tool_call("time", &Ok(()));
+        metrics.record_tool_call::<()>("time", &Err(ProxyError::ToolNotFound("x".into())));
+        metrics.record_event("time", LifecycleEvent::Restarted);
+
+        let text = metrics.render(&[]).unwrap();
+        assert!(
+            text.contains(r#"rusted_tools_tool_calls_total{endpoint="time",result="success"} 1"#)
+        );
+        assert!(
+            text.contains(r#"rusted_tools_tool_calls_total{endpoint="time",result="error"} 1"#)
+        );
+        assert!(text.contains(
+            r#"rusted_tools_endpoint_events_total{endpoint="time",event="restarted"} 1"#
+        ));
+    }
+
+    #[test]
+    fn test_render_splits_request_errors_by_kind() {
+        let metrics = Metrics::new();
+        let elapsed = Duration::from_millis(20);
+        metrics.observe_request::<()>(
+            "time",
+            Operation::Call,
+            elapsed,
+            &Err(ProxyError::mcp_timeout(Duration::from_secs(30))),
+        );
+        metrics.observe_request::<()>(
+            "time",
+            Operation::Call,
+            elapsed,
+            &Err(ProxyError::mcp_protocol("bad response")),
+        );
+
+        let text = metrics.render(&[]).unwrap();
+        assert!(text.contains(
+            r#"rusted_tools_upstream_request_errors_total{endpoint="time",error="McpTimeout",operation="call"} 1"#
+        ));
+        assert!(text.contains(
+            r#"rusted_tools_upstream_request_errors_total{endpoint="time",error="McpProtocol",operation="call"} 1"#
+        ));
+        assert!(text.contains(
+            r#"rusted_tools_upstream_request_duration_seconds_count{endpoint="time",operation="call"} 2"#
+        ));
+    }
+
+    #[test]
+    fn test_render_reports_endpoints_by_status() {
+        let metrics = Metrics::new();
+        let registry = EndpointRegistry::new();
+        registry
+            .register(
+                "time".to_string(),
+                "time".to_string(),
+                Vec::new(),
+                EndpointType::Local,
+                None,
+                Default::default(),
+            )
+            .unwrap();
+
+        let text = metrics.render(&registry.list()).unwrap();
+        assert!(text.contains(r#"rusted_tools_endpoints{status="stopped"} 1"#));
+        assert!(text.contains(r#"rusted_tools_endpoints{status="running"} 0"#));
+    }
+}
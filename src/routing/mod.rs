@@ -1,4 +1,6 @@
 pub(crate) mod path_router;
+pub(crate) mod plan;
 pub(crate) mod tool_filter;
 
 pub use path_router::PathRouter;
+pub use plan::{RouteSummary, routes_from_config};
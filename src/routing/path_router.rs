@@ -1,5 +1,5 @@
-use crate::config::ToolFilter;
-use crate::endpoint::EndpointManager;
+use crate::config::{PathMatching, ToolFilter};
+use crate::endpoint::{EndpointInfo, EndpointManager};
 use crate::error::Result;
 use crate::mcp::McpClient;
 use std::sync::Arc;
@@ -8,19 +8,66 @@ use std::sync::Arc;
 #[derive(Clone)]
 pub struct PathRouter {
     manager: Arc<EndpointManager>,
+    matching: PathMatching,
 }
 
 impl PathRouter {
     pub fn new(manager: Arc<EndpointManager>) -> Self {
-        Self { manager }
+        Self {
+            manager,
+            matching: PathMatching::Exact,
+        }
+    }
+
+    /// Match requested paths as configured by `mcp.path_matching`
+    pub fn with_matching(mut self, matching: PathMatching) -> Self {
+        self.matching = matching;
+        self
+    }
+
+    pub(crate) fn matching(&self) -> PathMatching {
+        self.matching
     }
 
     /// Get endpoint name and filter for a path
     pub(crate) fn get_route(&self, path: &str) -> Result<(String, Option<ToolFilter>)> {
-        let info = self.manager.get_endpoint_info_by_path(path)?;
+        let info = self.resolve(path)?;
         Ok((info.name, info.tool_filter))
     }
 
+    /// The registered path, primary or alias, that a requested path matches
+    pub(crate) fn canonical_path(&self, path: &str) -> Option<String> {
+        let info = self.resolve(path).ok()?;
+        let path = self.normalize(path);
+        info.paths()
+            .find(|p| self.paths_match(p, path))
+            .map(str::to_string)
+    }
+
+    fn resolve(&self, path: &str) -> Result<EndpointInfo> {
+        let path = self.normalize(path);
+        match self.manager.get_endpoint_info_by_path(path) {
+            Err(e) if self.matching == PathMatching::CaseInsensitive => self
+                .manager
+                .list_endpoints()
+                .into_iter()
+                .find(|info| info.paths().any(|p| p.eq_ignore_ascii_case(path)))
+                .ok_or(e),
+            result => result,
+        }
+    }
+
+    fn normalize<'a>(&self, path: &'a str) -> &'a str {
+        match self.matching {
+            PathMatching::NormalizeTrailingSlash => path.trim_end_matches('/'),
+            _ => path,
+        }
+    }
+
+    fn paths_match(&self, registered: &str, requested: &str) -> bool {
+        self.matching.same_path(registered, requested)
+    }
+
     /// Get MCP client for a specific path (works for both local and remote)
     pub(crate) async fn get_client(
         &self,
@@ -34,12 +81,16 @@ impl PathRouter {
         Ok((client, tool_filter))
     }
 
-    /// List all routes
-    pub(crate) fn list_routes(&self) -> Vec<(String, String)> {
+    /// Every path an endpoint is mounted at, aliases included
+    pub(crate) fn list_mounts(&self) -> Vec<(String, String)> {
         self.manager
             .list_endpoints()
             .into_iter()
-            .map(|info| (info.path, info.name))
+            .flat_map(|info| {
+                info.paths()
+                    .map(|path| (path.to_string(), info.name.clone()))
+                    .collect::<Vec<_>>()
+            })
             .collect()
     }
 }
@@ -57,16 +108,28 @@ mod tests {
 
         let config = EndpointConfig {
             name: "test-server".to_string(),
+            aliases: Vec::new(),
             endpoint_type: EndpointKindConfig::Local {
                 command: "echo".to_string(),
                 args: vec![],
                 env: HashMap::new(),
+                clean_env: false,
                 auto_start: false,
+                health_command: None,
+                cwd: None,
+                restart_on_failure: false,
+                startup_retry: None,
+                env_file: None,
             },
             tools: Some(ToolFilter {
                 include: Some(vec!["tool1".to_string()]),
                 exclude: None,
+                ..Default::default()
             }),
+            rate_limit: None,
+            max_concurrent_requests: None,
+            enabled: true,
+            metadata: Default::default(),
         };
 
         manager
@@ -88,10 +151,20 @@ mod tests {
 
         let config = EndpointConfig {
             name: "test-server".to_string(),
+            aliases: Vec::new(),
             endpoint_type: EndpointKindConfig::Remote {
                 url: "http://localhost:8080".to_string(),
+                urls: Vec::new(),
+                require_tools_on_start: false,
+                headers: HashMap::new(),
+                connect_timeout_secs: None,
+                prewarm: false,
             },
             tools: None,
+            rate_limit: None,
+            max_concurrent_requests: None,
+            enabled: true,
+            metadata: Default::default(),
         };
 
         manager
@@ -107,4 +180,56 @@ mod tests {
             "Should require explicit start before creating a client"
         );
     }
+
+    async fn router_with_alias(matching: PathMatching) -> PathRouter {
+        let manager = Arc::new(EndpointManager::new());
+        let config = EndpointConfig {
+            name: "time".to_string(),
+            aliases: vec!["clock".to_string()],
+            endpoint_type: EndpointKindConfig::Remote {
+                url: "http://localhost:8080".to_string(),
+                urls: Vec::new(),
+                require_tools_on_start: false,
+                headers: HashMap::new(),
+                connect_timeout_secs: None,
+                prewarm: false,
+            },
+            tools: None,
+            rate_limit: None,
+            max_concurrent_requests: None,
+            enabled: true,
+            metadata: Default::default(),
+        };
+        manager.init_from_config(vec![config]).await.unwrap();
+        PathRouter::new(manager).with_matching(matching)
+    }
+
+    #[tokio::test]
+    async fn test_exact_matching_rejects_case_and_trailing_slash() {
+        let router = router_with_alias(PathMatching::Exact).await;
+
+        assert!(router.get_route("time").is_ok());
+        assert!(router.get_route("Time").is_err());
+        assert!(router.get_route("time/").is_err());
+        assert_eq!(router.canonical_path("Time"), None);
+    }
+
+    #[tokio::test]
+    async fn test_case_insensitive_matching() {
+        let router = router_with_alias(PathMatching::CaseInsensitive).await;
+
+        assert_eq!(router.get_route("TIME").unwrap().0, "time");
+        assert_eq!(router.get_route("Clock").unwrap().0, "time");
+        assert_eq!(router.canonical_path("Clock").as_deref(), Some("clock"));
+        assert!(router.get_route("time/").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_trailing_slash_matching() {
+        let router = router_with_alias(PathMatching::NormalizeTrailingSlash).await;
+
+        assert_eq!(router.get_route("clock/").unwrap().0, "time");
+        assert_eq!(router.canonical_path("time/").as_deref(), Some("time"));
+        assert!(router.get_route("Time").is_err());
+    }
 }
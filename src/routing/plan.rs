@@ -0,0 +1,134 @@
+use crate::config::{AppConfig, EndpointKindConfig, ToolFilter};
+use serde::Serialize;
+
+/// A route that would be mounted for a configured endpoint
+#[derive(Debug, Clone, Serialize)]
+pub struct RouteSummary {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub endpoint_type: &'static str,
+    pub path: String,
+    pub tool_filter: String,
+    /// Only local endpoints are started with the proxy
+    pub auto_start: Option<bool>,
+}
+
+/// Routes the proxy would mount for `config`, without registering or starting anything
+pub fn routes_from_config(config: &AppConfig) -> Vec<RouteSummary> {
+    config
+        .endpoints
+        .iter()
+        .filter(|endpoint| endpoint.enabled)
+        .map(|endpoint| {
+            let (endpoint_type, auto_start) = match &endpoint.endpoint_type {
+                EndpointKindConfig::Local { auto_start, .. } => ("local", Some(*auto_start)),
+                EndpointKindConfig::Remote { .. } => ("remote", None),
+            };
+            // Paths are derived from endpoint names, as in `EndpointManager`
+            RouteSummary {
+                name: endpoint.name.clone(),
+                endpoint_type,
+                path: format!("/mcp/{}", endpoint.name),
+                tool_filter: summarize_filter(endpoint.tools.as_ref()),
+                auto_start,
+            }
+        })
+        .collect()
+}
+
+fn summarize_filter(filter: Option<&ToolFilter>) -> String {
+    let Some(filter) = filter else {
+        return "all tools".to_string();
+    };
+
+    let mut parts = Vec::new();
+    if let Some(include) = &filter.include {
+        parts.push(format!("include {}", include.join(", ")));
+    }
+    if let Some(regex) = &filter.include_regex {
+        parts.push(format!("include /{}/", regex.as_str()));
+    }
+    if let Some(exclude) = &filter.exclude {
+        parts.push(format!("exclude {}", exclude.join(", ")));
+    }
+    if let Some(regex) = &filter.exclude_regex {
+        parts.push(format!("exclude /{}/", regex.as_str()));
+    }
+    if !filter.when_header.is_empty() {
+        parts.push(format!("{} header variant(s)", filter.when_header.len()));
+    }
+
+    if parts.is_empty() {
+        "all tools".to_string()
+    } else {
+        parts.join("; ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::EndpointConfig;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_routes_from_config() {
+        let config = AppConfig {
+            endpoints: vec![
+                EndpointConfig {
+                    name: "time".to_string(),
+                    aliases: Vec::new(),
+                    endpoint_type: EndpointKindConfig::Local {
+                        command: "docker".to_string(),
+                        args: vec![],
+                        env: HashMap::new(),
+                        clean_env: false,
+                        auto_start: true,
+                        health_command: None,
+                        cwd: None,
+                        restart_on_failure: false,
+                        startup_retry: None,
+                        env_file: None,
+                    },
+                    tools: Some(ToolFilter {
+                        include: Some(vec!["get_*".to_string()]),
+                        exclude: Some(vec!["get_secret".to_string()]),
+                        ..Default::default()
+                    }),
+                    rate_limit: None,
+                    max_concurrent_requests: None,
+                    enabled: true,
+                    metadata: Default::default(),
+                },
+                EndpointConfig {
+                    name: "docs".to_string(),
+                    aliases: Vec::new(),
+                    endpoint_type: EndpointKindConfig::Remote {
+                        url: "https://example.com/mcp".to_string(),
+                        urls: Vec::new(),
+                        require_tools_on_start: false,
+                        headers: HashMap::new(),
+                        connect_timeout_secs: None,
+                        prewarm: false,
+                    },
+                    tools: None,
+                    rate_limit: None,
+                    max_concurrent_requests: None,
+                    enabled: true,
+                    metadata: Default::default(),
+                },
+            ],
+            ..Default::default()
+        };
+
+        let routes = routes_from_config(&config);
+        assert_eq!(routes.len(), 2);
+        assert_eq!(routes[0].path, "/mcp/time");
+        assert_eq!(routes[0].endpoint_type, "local");
+        assert_eq!(routes[0].auto_start, Some(true));
+        assert_eq!(routes[0].tool_filter, "include get_*; exclude get_secret");
+        assert_eq!(routes[1].path, "/mcp/docs");
+        assert_eq!(routes[1].auto_start, None);
+        assert_eq!(routes[1].tool_filter, "all tools");
+    }
+}
@@ -1,27 +1,130 @@
-use crate::config::ToolFilter;
+use crate::config::{ToolFilter, ToolRegex};
 use crate::mcp::ToolDefinition;
+use axum::http::HeaderMap;
+use wildmatch::WildMatch;
+
+/// Why a filter rejected a tool
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FilterRejection {
+    /// An include list is set and the tool is not in it
+    NotIncluded,
+    /// The tool is in the exclude list
+    Excluded,
+}
+
+/// Outcome of evaluating a filter against a set of tool names
+#[cfg(test)]
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct FilterEvaluation {
+    pub allowed: Vec<String>,
+    pub rejected: Vec<(String, FilterRejection)>,
+}
 
 impl ToolFilter {
     /// Check if a tool should be allowed based on include/exclude filters
-    /// Include list takes precedence - if present, tool must be in it
-    /// Exclude list is then checked - if present, tool must not be in it
+    /// Include rules take precedence - if present, tool must match the list or the regex
+    /// Exclude rules are then checked - the tool must match neither
+    /// List entries containing `*` or `?` are globs, other entries match exactly
     pub(crate) fn allows(&self, tool_name: &str) -> bool {
-        // If include list exists, tool must be in it
-        if let Some(include) = &self.include
-            && !include.iter().any(|t| t == tool_name)
+        self.rejection(tool_name).is_none()
+    }
+
+    /// Explain why a tool is rejected, or `None` when it is allowed
+    pub(crate) fn rejection(&self, tool_name: &str) -> Option<FilterRejection> {
+        // If include rules exist, tool must match one of them
+        let has_include = self.include.is_some() || self.include_regex.is_some();
+        if has_include
+            && !matches(
+                self.include.as_deref(),
+                self.include_regex.as_ref(),
+                tool_name,
+            )
         {
-            return false;
+            return Some(FilterRejection::NotIncluded);
         }
 
-        // If exclude list exists, tool must not be in it
-        if let Some(exclude) = &self.exclude
-            && exclude.iter().any(|t| t == tool_name)
-        {
-            return false;
+        // If exclude rules exist, tool must match none of them
+        if matches(
+            self.exclude.as_deref(),
+            self.exclude_regex.as_ref(),
+            tool_name,
+        ) {
+            return Some(FilterRejection::Excluded);
         }
 
-        true
+        None
     }
+
+    /// Rewrite an upstream tool the way clients see it
+    pub(crate) fn expose(&self, mut tool: ToolDefinition) -> ToolDefinition {
+        if let Some(description) = self.descriptions.get(&tool.name) {
+            tool.description = Some(description.clone());
+        }
+        if let Some(alias) = self.rename.get(&tool.name) {
+            tool.name = alias.clone();
+        }
+        tool
+    }
+
+    /// Map a name a client used back to the upstream tool name
+    /// Returns `None` for an upstream name that is only exposed under an alias
+    pub(crate) fn upstream_name<'a>(&'a self, name: &'a str) -> Option<&'a str> {
+        if let Some((upstream, _)) = self.rename.iter().find(|(_, alias)| *alias == name) {
+            return Some(upstream);
+        }
+        (!self.rename.contains_key(name)).then_some(name)
+    }
+
+    /// Resolve the effective filter for a request based on its headers
+    /// The first header variant whose header value matches wins;
+    /// when none match, the default include/exclude lists are used
+    pub(crate) fn for_headers(&self, headers: &HeaderMap) -> ToolFilter {
+        let variant = self.when_header.iter().find(|variant| {
+            headers
+                .get_all(variant.header.as_str())
+                .iter()
+                .any(|value| value.to_str().is_ok_and(|v| v == variant.value))
+        });
+
+        match variant {
+            Some(variant) => ToolFilter {
+                include: variant.include.clone(),
+                exclude: variant.exclude.clone(),
+                include_regex: variant.include_regex.clone(),
+                exclude_regex: variant.exclude_regex.clone(),
+                rename: self.rename.clone(),
+                descriptions: self.descriptions.clone(),
+                when_header: Vec::new(),
+            },
+            None => ToolFilter {
+                when_header: Vec::new(),
+                ..self.clone()
+            },
+        }
+    }
+}
+
+/// Whether a tool is named in `names` or matches `regex`
+fn matches(names: Option<&[String]>, regex: Option<&ToolRegex>, tool_name: &str) -> bool {
+    names.is_some_and(|names| names.iter().any(|entry| entry_matches(entry, tool_name)))
+        || regex.is_some_and(|regex| regex.is_match(tool_name))
+}
+
+/// Match one include/exclude entry, treating `*` and `?` as wildcards
+fn entry_matches(entry: &str, tool_name: &str) -> bool {
+    if entry.contains(['*', '?']) {
+        WildMatch::new(entry).matches(tool_name)
+    } else {
+        entry == tool_name
+    }
+}
+
+/// Select the filter that applies to a request with the given headers
+pub(crate) fn resolve_filter(
+    filter: Option<ToolFilter>,
+    headers: &HeaderMap,
+) -> Option<ToolFilter> {
+    filter.map(|filter| filter.for_headers(headers))
 }
 
 /// Apply tool filters to a list of tools
@@ -34,21 +137,46 @@ pub(crate) fn apply_tool_filter(
         Some(filter) => tools
             .into_iter()
             .filter(|tool| filter.allows(&tool.name))
+            .map(|tool| filter.expose(tool))
             .collect(),
     }
 }
 
-/// Check if a specific tool name is allowed by the filter
-pub(crate) fn is_tool_allowed(tool_name: &str, filter: Option<&ToolFilter>) -> bool {
+/// Split tool names into the allowed subset and rejections with reasons
+/// Uses the same rules as `apply_tool_filter`, without needing full tool definitions
+#[cfg(test)]
+pub(crate) fn evaluate_filter<S: AsRef<str>>(
+    filter: Option<&ToolFilter>,
+    tool_names: &[S],
+) -> FilterEvaluation {
+    let mut evaluation = FilterEvaluation::default();
+    for name in tool_names {
+        let name = name.as_ref();
+        match filter.and_then(|filter| filter.rejection(name)) {
+            None => evaluation.allowed.push(name.to_string()),
+            Some(reason) => evaluation.rejected.push((name.to_string(), reason)),
+        }
+    }
+    evaluation
+}
+
+/// Upstream name of the tool a client called, or `None` when the filter doesn't allow it
+pub(crate) fn resolve_tool_name<'a>(
+    tool_name: &'a str,
+    filter: Option<&'a ToolFilter>,
+) -> Option<&'a str> {
     match filter {
-        None => true, // No filter, all tools allowed
-        Some(filter) => filter.allows(tool_name),
+        None => Some(tool_name), // No filter, all tools allowed
+        Some(filter) => filter
+            .upstream_name(tool_name)
+            .filter(|upstream| filter.allows(upstream)),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::HeaderToolFilter;
     use serde_json::json;
 
     fn create_test_tool(name: &str) -> ToolDefinition {
@@ -56,6 +184,8 @@ mod tests {
             name: name.to_string(),
             description: Some(format!("Test tool {}", name)),
             input_schema: json!({}),
+            output_schema: None,
+            annotations: None,
         }
     }
 
@@ -82,6 +212,7 @@ mod tests {
         let filter = ToolFilter {
             include: Some(vec!["tool1".to_string(), "tool2".to_string()]),
             exclude: None,
+            ..Default::default()
         };
 
         let filtered = apply_tool_filter(tools, Some(&filter));
@@ -101,6 +232,7 @@ mod tests {
         let filter = ToolFilter {
             include: None,
             exclude: Some(vec!["tool2".to_string()]),
+            ..Default::default()
         };
 
         let filtered = apply_tool_filter(tools, Some(&filter));
@@ -110,29 +242,236 @@ mod tests {
     }
 
     #[test]
-    fn test_is_tool_allowed_no_filter() {
-        assert!(is_tool_allowed("any_tool", None));
+    fn test_resolve_tool_name_no_filter() {
+        assert!(resolve_tool_name("any_tool", None).is_some());
     }
 
     #[test]
-    fn test_is_tool_allowed_with_include() {
+    fn test_resolve_tool_name_with_include() {
         let filter = ToolFilter {
             include: Some(vec!["allowed_tool".to_string()]),
             exclude: None,
+            ..Default::default()
+        };
+
+        assert!(resolve_tool_name("allowed_tool", Some(&filter)).is_some());
+        assert!(resolve_tool_name("other_tool", Some(&filter)).is_none());
+    }
+
+    fn regex(pattern: &str) -> Option<ToolRegex> {
+        Some(ToolRegex::new(pattern).unwrap())
+    }
+
+    #[test]
+    fn test_include_regex_alongside_exact_names() {
+        let filter = ToolFilter {
+            include: Some(vec!["search".to_string()]),
+            include_regex: regex("^github_"),
+            ..Default::default()
+        };
+
+        assert!(filter.allows("github_create_issue"));
+        assert!(filter.allows("search"));
+        assert_eq!(
+            filter.rejection("gitlab_create_issue"),
+            Some(FilterRejection::NotIncluded)
+        );
+    }
+
+    #[test]
+    fn test_exclude_regex_wins_over_include() {
+        let filter = ToolFilter {
+            include_regex: regex("^github_"),
+            exclude_regex: regex("_delete_|_admin$"),
+            ..Default::default()
+        };
+
+        assert!(filter.allows("github_list_repos"));
+        assert_eq!(
+            filter.rejection("github_delete_repo"),
+            Some(FilterRejection::Excluded)
+        );
+        assert!(!filter.allows("github_admin"));
+    }
+
+    #[test]
+    fn test_exclude_regex_without_include() {
+        let filter = ToolFilter {
+            exclude_regex: regex("^debug_"),
+            ..Default::default()
+        };
+
+        assert!(filter.allows("search"));
+        assert!(!filter.allows("debug_dump"));
+    }
+
+    #[test]
+    fn test_prefix_glob() {
+        let filter = ToolFilter {
+            exclude: Some(vec!["admin_*".to_string()]),
+            ..Default::default()
+        };
+
+        assert!(!filter.allows("admin_reset"));
+        assert!(!filter.allows("admin_"));
+        assert!(filter.allows("get_admin"));
+    }
+
+    #[test]
+    fn test_suffix_glob() {
+        let filter = ToolFilter {
+            include: Some(vec!["*_read".to_string()]),
+            ..Default::default()
+        };
+
+        assert!(filter.allows("file_read"));
+        assert!(!filter.allows("file_read_all"));
+        assert!(!filter.allows("file_write"));
+    }
+
+    #[test]
+    fn test_mixed_literal_and_glob_entries() {
+        let filter = ToolFilter {
+            include: Some(vec!["search".to_string(), "repo_*".to_string()]),
+            exclude: Some(vec!["*_delete".to_string(), "repo_archive".to_string()]),
+            ..Default::default()
+        };
+
+        assert!(filter.allows("search"));
+        assert!(filter.allows("repo_list"));
+        assert_eq!(
+            filter.rejection("repo_delete"),
+            Some(FilterRejection::Excluded)
+        );
+        assert!(!filter.allows("repo_archive"));
+        assert_eq!(
+            filter.rejection("searches"),
+            Some(FilterRejection::NotIncluded)
+        );
+    }
+
+    #[test]
+    fn test_question_mark_matches_one_character() {
+        let filter = ToolFilter {
+            include: Some(vec!["tool?".to_string()]),
+            ..Default::default()
+        };
+
+        assert!(filter.allows("tool1"));
+        assert!(!filter.allows("tool"));
+        assert!(!filter.allows("tool12"));
+    }
+
+    #[test]
+    fn test_rename_resolves_aliases_to_upstream_names() {
+        let filter = ToolFilter {
+            exclude: Some(vec!["delete".to_string()]),
+            rename: [
+                ("search".to_string(), "docs_search".to_string()),
+                ("delete".to_string(), "docs_delete".to_string()),
+            ]
+            .into(),
+            ..Default::default()
         };
 
-        assert!(is_tool_allowed("allowed_tool", Some(&filter)));
-        assert!(!is_tool_allowed("other_tool", Some(&filter)));
+        assert_eq!(
+            resolve_tool_name("docs_search", Some(&filter)),
+            Some("search")
+        );
+        assert_eq!(resolve_tool_name("fetch", Some(&filter)), Some("fetch"));
+        // Renamed tools are only reachable by alias, and exclusions use upstream names
+        assert_eq!(resolve_tool_name("search", Some(&filter)), None);
+        assert_eq!(resolve_tool_name("docs_delete", Some(&filter)), None);
+
+        let listed = apply_tool_filter(
+            vec![create_test_tool("search"), create_test_tool("delete")],
+            Some(&filter),
+        );
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].name, "docs_search");
+    }
+
+    fn create_tiered_filter() -> ToolFilter {
+        ToolFilter {
+            include: Some(vec!["basic_tool".to_string()]),
+            exclude: None,
+            when_header: vec![HeaderToolFilter {
+                header: "x-tier".to_string(),
+                value: "pro".to_string(),
+                include: Some(vec!["basic_tool".to_string(), "pro_tool".to_string()]),
+                exclude: None,
+                include_regex: None,
+                exclude_regex: None,
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_for_headers_selects_matching_variant() {
+        let filter = create_tiered_filter();
+        let mut headers = HeaderMap::new();
+        headers.insert("X-Tier", "pro".parse().unwrap());
+
+        let resolved = filter.for_headers(&headers);
+        assert!(resolved.allows("basic_tool"));
+        assert!(resolved.allows("pro_tool"));
     }
 
     #[test]
-    fn test_is_tool_allowed_with_exclude() {
+    fn test_for_headers_falls_back_to_default() {
+        let filter = create_tiered_filter();
+        let mut headers = HeaderMap::new();
+        headers.insert("x-tier", "free".parse().unwrap());
+
+        let resolved = filter.for_headers(&headers);
+        assert!(resolved.allows("basic_tool"));
+        assert!(!resolved.allows("pro_tool"));
+
+        let resolved = filter.for_headers(&HeaderMap::new());
+        assert!(!resolved.allows("pro_tool"));
+    }
+
+    #[test]
+    fn test_resolve_filter_without_filter() {
+        assert!(resolve_filter(None, &HeaderMap::new()).is_none());
+    }
+
+    #[test]
+    fn test_resolve_tool_name_with_exclude() {
         let filter = ToolFilter {
             include: None,
             exclude: Some(vec!["blocked_tool".to_string()]),
+            ..Default::default()
         };
 
-        assert!(!is_tool_allowed("blocked_tool", Some(&filter)));
-        assert!(is_tool_allowed("other_tool", Some(&filter)));
+        assert!(resolve_tool_name("blocked_tool", Some(&filter)).is_none());
+        assert!(resolve_tool_name("other_tool", Some(&filter)).is_some());
+    }
+
+    #[test]
+    fn test_evaluate_filter_reports_reasons() {
+        let filter = ToolFilter {
+            include: Some(vec!["read".to_string(), "write".to_string()]),
+            exclude: Some(vec!["write".to_string()]),
+            ..Default::default()
+        };
+
+        let evaluation = evaluate_filter(Some(&filter), &["read", "write", "delete"]);
+        assert_eq!(evaluation.allowed, vec!["read".to_string()]);
+        assert_eq!(
+            evaluation.rejected,
+            vec![
+                ("write".to_string(), FilterRejection::Excluded),
+                ("delete".to_string(), FilterRejection::NotIncluded),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_evaluate_filter_without_filter() {
+        let evaluation = evaluate_filter(None, &["a", "b"]);
+        assert_eq!(evaluation.allowed.len(), 2);
+        assert!(evaluation.rejected.is_empty());
     }
 }
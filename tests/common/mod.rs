@@ -24,22 +24,44 @@ pub fn create_offline_config() -> AppConfig {
         endpoints: vec![
             EndpointConfig {
                 name: "local-stub".to_string(),
+                aliases: Vec::new(),
                 endpoint_type: EndpointKindConfig::Local {
                     command: "cat".to_string(),
                     args: vec![],
                     env: HashMap::new(),
+                    clean_env: false,
                     auto_start: false,
+                    health_command: None,
+                    cwd: None,
+                    restart_on_failure: false,
+                    startup_retry: None,
+                    env_file: None,
                 },
                 tools: None,
+                rate_limit: None,
+                max_concurrent_requests: None,
+                enabled: true,
+                metadata: Default::default(),
             },
             EndpointConfig {
                 name: "remote-stub".to_string(),
+                aliases: Vec::new(),
                 endpoint_type: EndpointKindConfig::Remote {
                     url: "http://127.0.0.1:19876".to_string(),
+                    urls: Vec::new(),
+                    require_tools_on_start: false,
+                    headers: HashMap::new(),
+                    connect_timeout_secs: None,
+                    prewarm: false,
                 },
                 tools: None,
+                rate_limit: None,
+                max_concurrent_requests: None,
+                enabled: true,
+                metadata: Default::default(),
             },
         ],
+        ..Default::default()
     }
 }
 
@@ -58,11 +80,22 @@ pub fn create_live_remote_config() -> AppConfig {
         mcp: McpConfig::default(),
         endpoints: vec![EndpointConfig {
             name: "microsoft-learn".to_string(),
+            aliases: Vec::new(),
             endpoint_type: EndpointKindConfig::Remote {
                 url: "https://learn.microsoft.com/api/mcp".to_string(),
+                urls: Vec::new(),
+                require_tools_on_start: false,
+                headers: HashMap::new(),
+                connect_timeout_secs: None,
+                prewarm: false,
             },
             tools: None,
+            rate_limit: None,
+            max_concurrent_requests: None,
+            enabled: true,
+            metadata: Default::default(),
         }],
+        ..Default::default()
     }
 }
 
@@ -77,6 +110,7 @@ pub fn create_live_local_config() -> AppConfig {
         mcp: McpConfig::default(),
         endpoints: vec![EndpointConfig {
             name: "time".to_string(),
+            aliases: Vec::new(),
             endpoint_type: EndpointKindConfig::Local {
                 command: "docker".to_string(),
                 args: vec![
@@ -86,10 +120,21 @@ pub fn create_live_local_config() -> AppConfig {
                     "mcp/time".to_string(),
                 ],
                 env: HashMap::new(),
+                clean_env: false,
                 auto_start: false,
+                health_command: None,
+                cwd: None,
+                restart_on_failure: false,
+                startup_retry: None,
+                env_file: None,
             },
             tools: None,
+            rate_limit: None,
+            max_concurrent_requests: None,
+            enabled: true,
+            metadata: Default::default(),
         }],
+        ..Default::default()
     }
 }
 
@@ -105,13 +150,24 @@ pub fn create_live_full_config() -> AppConfig {
         endpoints: vec![
             EndpointConfig {
                 name: "microsoft-learn".to_string(),
+                aliases: Vec::new(),
                 endpoint_type: EndpointKindConfig::Remote {
                     url: "https://learn.microsoft.com/api/mcp".to_string(),
+                    urls: Vec::new(),
+                    require_tools_on_start: false,
+                    headers: HashMap::new(),
+                    connect_timeout_secs: None,
+                    prewarm: false,
                 },
                 tools: None,
+                rate_limit: None,
+                max_concurrent_requests: None,
+                enabled: true,
+                metadata: Default::default(),
             },
             EndpointConfig {
                 name: "time".to_string(),
+                aliases: Vec::new(),
                 endpoint_type: EndpointKindConfig::Local {
                     command: "docker".to_string(),
                     args: vec![
@@ -121,11 +177,22 @@ pub fn create_live_full_config() -> AppConfig {
                         "mcp/time".to_string(),
                     ],
                     env: HashMap::new(),
+                    clean_env: false,
                     auto_start: false,
+                    health_command: None,
+                    cwd: None,
+                    restart_on_failure: false,
+                    startup_retry: None,
+                    env_file: None,
                 },
                 tools: None,
+                rate_limit: None,
+                max_concurrent_requests: None,
+                enabled: true,
+                metadata: Default::default(),
             },
         ],
+        ..Default::default()
     }
 }
 
@@ -135,9 +202,7 @@ pub fn create_live_full_config() -> AppConfig {
 
 /// Build a test Router from the given config (no HTTP server, uses tower::oneshot).
 pub async fn build_test_app(config: &AppConfig) -> Router {
-    let manager = Arc::new(EndpointManager::new_with_restart_delay(
-        Duration::from_millis(config.mcp.restart_delay_ms),
-    ));
+    let manager = Arc::new(EndpointManager::from_config(&config.mcp));
     manager
         .init_from_config(config.endpoints.clone())
         .await
@@ -146,14 +211,23 @@ pub async fn build_test_app(config: &AppConfig) -> Router {
     let router = Arc::new(PathRouter::new(manager.clone()));
 
     let state = ApiState {
+        metrics: manager.metrics(),
+        endpoint_routes: Default::default(),
         manager,
         router,
         mcp_request_timeout: Duration::from_secs(config.mcp.request_timeout_secs),
+        validate_output: config.mcp.validate_output,
+        validate_arguments: config.mcp.validate_arguments,
+        max_json_depth: config.mcp.max_json_depth,
+        log_tool_arguments: config.logging.log_tool_arguments,
+        aggregate_tool_names: config.mcp.aggregate_tool_names,
     };
 
     Router::new()
         .merge(rusted_tools::api::routes::health_routes())
-        .merge(rusted_tools::api::routes::management_routes())
+        .merge(rusted_tools::api::routes::management_routes(
+            &config.management,
+        ))
         .merge(rusted_tools::api::routes::mcp_routes())
         .with_state(state)
 }
@@ -79,6 +79,182 @@ mod offline {
         assert!(names.contains(&"remote-stub"));
     }
 
+    #[tokio::test]
+    async fn test_built_app_mounts_under_parent_router() {
+        let config = common::create_offline_config();
+        let (proxy, manager) = rusted_tools::api::build_app(config).await.unwrap();
+        let app = axum::Router::new()
+            .route("/", axum::routing::get(|| async { "parent" }))
+            .nest("/proxy", proxy);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/proxy/health")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().contains_key("x-request-id"));
+        let json = common::response_json(response).await;
+        assert_eq!(json["status"], "ok");
+        manager.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_ready_waits_for_auto_start_attempts() {
+        use rusted_tools::config::EndpointKindConfig;
+        use std::{collections::HashMap, time::Duration};
+
+        let mut config = common::create_offline_config();
+        // Not an MCP server, so the start fails once the child exits a second later
+        config.endpoints[0].endpoint_type = EndpointKindConfig::Local {
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), "sleep 1".to_string()],
+            env: HashMap::new(),
+            clean_env: false,
+            auto_start: true,
+            health_command: None,
+            cwd: None,
+            restart_on_failure: false,
+            startup_retry: None,
+            env_file: None,
+        };
+        let (app, manager) = rusted_tools::api::build_app(config).await.unwrap();
+        let ready = || async {
+            app.clone()
+                .oneshot(
+                    Request::builder()
+                        .uri("/ready")
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap()
+        };
+
+        let response = ready().await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(common::response_json(response).await["status"], "starting");
+
+        tokio::time::timeout(Duration::from_secs(30), async {
+            while !manager.is_ready() {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .unwrap();
+        let response = ready().await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(common::response_json(response).await["status"], "ready");
+        assert_eq!(
+            manager.status("local-stub").unwrap(),
+            rusted_tools::EndpointStatus::Failed
+        );
+        manager.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_public_inspection_api_reports_endpoints() {
+        use rusted_tools::{EndpointStatus, ProxyError};
+
+        let config = common::create_offline_config();
+        let (_app, manager) = rusted_tools::api::build_app(config).await.unwrap();
+
+        let mut endpoints = manager.list();
+        endpoints.sort_by(|a, b| a.name.cmp(&b.name));
+        let names: Vec<&str> = endpoints.iter().map(|info| info.name.as_str()).collect();
+        assert_eq!(names, ["local-stub", "remote-stub"]);
+        assert_eq!(endpoints[0].path, "local-stub");
+        assert_eq!(endpoints[0].status, EndpointStatus::Stopped);
+
+        assert_eq!(
+            manager.status("local-stub").unwrap(),
+            EndpointStatus::Stopped
+        );
+        assert!(matches!(
+            manager.status("missing"),
+            Err(ProxyError::ServerNotFound(_))
+        ));
+        manager.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_list_servers_filters_by_tags() {
+        let mut config = common::create_offline_config();
+        let tag = |key: &str, value: &str| (key.to_string(), value.to_string());
+        config.endpoints[0].metadata.tags = [tag("team", "docs"), tag("env", "prod")].into();
+        config.endpoints[1].metadata.tags = [tag("team", "docs"), tag("env", "dev")].into();
+        let app = common::build_test_app(&config).await;
+
+        let list = |query: &'static str| {
+            let app = app.clone();
+            async move {
+                let response = app
+                    .oneshot(
+                        Request::builder()
+                            .uri(format!("/servers{}", query))
+                            .body(Body::empty())
+                            .unwrap(),
+                    )
+                    .await
+                    .unwrap();
+                assert_eq!(response.status(), StatusCode::OK);
+                common::response_json(response).await["servers"]
+                    .as_array()
+                    .unwrap()
+                    .clone()
+            }
+        };
+
+        let servers = list("?tag=team=docs").await;
+        assert_eq!(servers.len(), 2);
+        assert_eq!(servers[0]["tags"]["team"], "docs");
+
+        let servers = list("?tag=team=docs&tag=env=prod").await;
+        assert_eq!(servers.len(), 1);
+        assert_eq!(servers[0]["name"], "local-stub");
+        assert_eq!(servers[0]["tags"]["env"], "prod");
+
+        assert!(list("?tag=team=search").await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_disabled_endpoint_is_not_listed_or_routed() {
+        let mut config = common::create_offline_config();
+        config.endpoints[0].enabled = false;
+        let app = common::build_test_app(&config).await;
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/servers")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let json = common::response_json(response).await;
+        let servers = json["servers"].as_array().unwrap();
+        assert_eq!(servers.len(), 1);
+        assert_eq!(servers[0]["name"], "remote-stub");
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/mcp/local-stub/tools")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
     #[tokio::test]
     async fn test_server_status_local() {
         let config = common::create_offline_config();
@@ -99,6 +275,9 @@ mod offline {
         assert_eq!(json["name"], "local-stub");
         assert_eq!(json["type"], "local");
         assert_eq!(json["status"], "stopped");
+        assert!(json["uptime_seconds"].is_null());
+        assert!(json["last_error"].is_null());
+        assert!(json["pid"].is_null());
     }
 
     #[tokio::test]
@@ -123,6 +302,42 @@ mod offline {
         assert_eq!(json["status"], "stopped");
     }
 
+    #[tokio::test]
+    async fn test_tool_of_unknown_server_returns_404() {
+        let config = common::create_offline_config();
+        let app = common::build_test_app(&config).await;
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/servers/nonexistent/tools/anything")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_capabilities_of_stopped_endpoint_returns_503() {
+        let config = common::create_offline_config();
+        let app = common::build_test_app(&config).await;
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/servers/local-stub/capabilities")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
     #[tokio::test]
     async fn test_server_not_found_returns_404() {
         let config = common::create_offline_config();
@@ -178,6 +393,31 @@ mod offline {
         assert_eq!(response.status(), StatusCode::NOT_FOUND);
     }
 
+    #[tokio::test]
+    async fn test_aggregate_tools_reports_stopped_endpoints_as_warnings() {
+        let config = common::create_offline_config();
+        let app = common::build_test_app(&config).await;
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/mcp/tools")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let json = common::response_json(response).await;
+        assert_eq!(json["tools"].as_array().unwrap().len(), 0);
+
+        let warnings = json["warnings"].as_array().unwrap();
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].as_str().unwrap().contains("local-stub"));
+        assert!(warnings[1].as_str().unwrap().contains("remote-stub"));
+    }
+
     #[tokio::test]
     async fn test_mcp_tools_on_stopped_endpoint_returns_error() {
         let config = common::create_offline_config();
@@ -224,6 +464,332 @@ mod offline {
         );
     }
 
+    #[tokio::test]
+    async fn test_disabled_management_operation_returns_404() {
+        let mut config = common::create_offline_config();
+        config.management.disabled_operations = vec![
+            rusted_tools::config::ManagementOperation::Start,
+            rusted_tools::config::ManagementOperation::Restart,
+        ];
+        let app = common::build_test_app(&config).await;
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/servers/local-stub/start")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        // Read routes stay available
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/servers/local-stub/status")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_admin_key_gates_mutating_operations() {
+        let mut config = common::create_offline_config();
+        config.management.admin_api_key = Some("secret".to_string());
+        let app = common::build_test_app(&config).await;
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/servers/local-stub/stop")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/servers/local-stub/stop")
+                    .header("x-admin-key", "wrong")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        // With the key the request reaches the handler (endpoint is stopped -> 503)
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/servers/local-stub/stop")
+                    .header("x-admin-key", "secret")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        // Read routes don't require the key
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/servers")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_test_remote_is_off_by_default() {
+        let config = common::create_offline_config();
+        let app = common::build_test_app(&config).await;
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/admin/test-remote")
+                    .header("content-type", "application/json")
+                    .body(Body::from(r#"{"url":"http://127.0.0.1:19876"}"#))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_test_remote_reports_unreachable_url() {
+        let mut config = common::create_offline_config();
+        config.management.enable_test_remote = true;
+        let app = common::build_test_app(&config).await;
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/admin/test-remote")
+                    .header("content-type", "application/json")
+                    .body(Body::from(
+                        r#"{"url":"http://127.0.0.1:19876","headers":{"authorization":"Bearer x"}}"#,
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let json = common::response_json(response).await;
+        assert_eq!(json["status"], "failure");
+        assert!(json["error"].is_string());
+
+        // Nothing gets registered by the check
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/servers")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let json = common::response_json(response).await;
+        assert_eq!(json["servers"].as_array().unwrap().len(), 2);
+
+        // Invalid headers are a bad request
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/admin/test-remote")
+                    .header("content-type", "application/json")
+                    .body(Body::from(
+                        r#"{"url":"http://127.0.0.1:19876","headers":{"bad header":"x"}}"#,
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    fn add_server_request(body: &str) -> Request<Body> {
+        Request::builder()
+            .method("POST")
+            .uri("/servers")
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_add_server_then_list_it() {
+        let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+        let config = common::create_offline_config();
+        let app = common::build_test_app(&config).await;
+
+        let body = r#"{"name":"added","type":"remote","url":"http://127.0.0.1:19877"}"#;
+        let response = app.clone().oneshot(add_server_request(body)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/servers")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let json = common::response_json(response).await;
+        let servers = json["servers"].as_array().unwrap();
+        assert_eq!(servers.len(), 3);
+        assert!(
+            servers
+                .iter()
+                .any(|s| s["name"] == "added" && s["path"] == "added" && s["status"] == "stopped")
+        );
+
+        // Names are unique, and double as paths
+        let response = app.clone().oneshot(add_server_request(body)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        let body = r#"{"name":"a/b","type":"remote","url":"http://127.0.0.1:19877"}"#;
+        let response = app.oneshot(add_server_request(body)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_add_server_rejects_what_the_config_file_would() {
+        let config = common::create_offline_config();
+        let app = common::build_test_app(&config).await;
+
+        let body = r#"{"name":"checked","type":"local","command":"true","args":[],
+            "health_command":{"command":"true","interval_secs":0}}"#;
+        let response = app.clone().oneshot(add_server_request(body)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let json = common::response_json(response).await;
+        assert!(
+            json["error"]
+                .as_str()
+                .unwrap()
+                .contains("health_command.interval_secs"),
+            "{}",
+            json
+        );
+
+        let body = r#"{"name":"checked","type":"remote","url":"http://127.0.0.1:19877",
+            "connect_timeout_secs":0}"#;
+        let response = app.clone().oneshot(add_server_request(body)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        // Nothing was registered by the rejected adds
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/servers/checked/status")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_add_server_rejects_path_differing_only_in_case() {
+        let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+        let mut config = common::create_offline_config();
+        let body = r#"{"name":"Local-Stub","type":"remote","url":"http://127.0.0.1:19877"}"#;
+
+        // Exact matching routes the two paths apart
+        let app = common::build_test_app(&config).await;
+        let response = app.oneshot(add_server_request(body)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        config.mcp.path_matching = rusted_tools::config::PathMatching::CaseInsensitive;
+        let app = common::build_test_app(&config).await;
+        let response = app.oneshot(add_server_request(body)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        let json = common::response_json(response).await;
+        assert!(
+            json["error"].as_str().unwrap().contains("'local-stub'"),
+            "{}",
+            json
+        );
+    }
+
+    #[tokio::test]
+    async fn test_remove_server_deregisters_it() {
+        let config = common::create_offline_config();
+        let app = common::build_test_app(&config).await;
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("DELETE")
+                    .uri("/servers/remote-stub")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/servers/remote-stub/status")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_remove_unknown_server_returns_404() {
+        let config = common::create_offline_config();
+        let app = common::build_test_app(&config).await;
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("DELETE")
+                    .uri("/servers/nonexistent")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
     #[tokio::test]
     async fn test_concurrent_requests() {
         let config = common::create_offline_config();
@@ -249,6 +815,129 @@ mod offline {
             assert_eq!(response.status(), StatusCode::OK);
         }
     }
+
+    /// Run the binary with `args` against a temporary config file
+    fn run_cli(args: &[&str], config: &str) -> std::process::Output {
+        use std::io::Write;
+
+        let mut file = tempfile::Builder::new().suffix(".toml").tempfile().unwrap();
+        file.write_all(config.as_bytes()).unwrap();
+
+        std::process::Command::new(env!("CARGO_BIN_EXE_rusted-tools"))
+            .args(args)
+            .arg("--config")
+            .arg(file.path())
+            .output()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_validate_command_accepts_good_config() {
+        let output = run_cli(
+            &["validate"],
+            r#"
+[http]
+port = 1
+
+[[endpoints]]
+name = "docs"
+type = "remote"
+url = "http://127.0.0.1:19876/mcp"
+"#,
+        );
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("Endpoints: 1"), "{}", stdout);
+        assert!(stdout.contains("/mcp/docs (remote)"), "{}", stdout);
+    }
+
+    #[test]
+    fn test_validate_command_rejects_bad_config() {
+        let output = run_cli(
+            &["validate"],
+            r#"
+[http]
+
+[[endpoints]]
+name = "tools"
+type = "remote"
+url = "http://127.0.0.1:19876/mcp"
+"#,
+        );
+
+        assert_eq!(output.status.code(), Some(1));
+        assert!(String::from_utf8_lossy(&output.stderr).contains("reserved"));
+    }
+
+    #[test]
+    fn test_init_command_writes_loadable_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        let init = || {
+            std::process::Command::new(env!("CARGO_BIN_EXE_rusted-tools"))
+                .args(["init", "--output"])
+                .arg(&path)
+                .output()
+                .unwrap()
+        };
+
+        assert!(init().status.success());
+        let config = rusted_tools::config::load_config(&path).unwrap();
+        assert!(!config.endpoints.is_empty());
+
+        // An existing file is left alone without --force
+        let again = init();
+        assert_eq!(again.status.code(), Some(1));
+        assert!(String::from_utf8_lossy(&again.stderr).contains("--force"));
+    }
+
+    #[test]
+    fn test_list_command_prints_routes_as_json() {
+        let output = run_cli(
+            &["list", "--format", "json"],
+            r#"
+[http]
+
+[[endpoints]]
+name = "time"
+type = "local"
+command = "docker"
+args = ["run", "--rm", "-i", "mcp/time"]
+auto_start = true
+
+[endpoints.tools]
+include = ["get_*"]
+
+[[endpoints]]
+name = "docs"
+type = "remote"
+url = "http://127.0.0.1:19876/mcp"
+"#,
+        );
+
+        assert!(output.status.success());
+        let routes: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(
+            routes,
+            serde_json::json!([
+                {
+                    "name": "time",
+                    "type": "local",
+                    "path": "/mcp/time",
+                    "tool_filter": "include get_*",
+                    "auto_start": true,
+                },
+                {
+                    "name": "docs",
+                    "type": "remote",
+                    "path": "/mcp/docs",
+                    "tool_filter": "all tools",
+                    "auto_start": null,
+                },
+            ])
+        );
+    }
 }
 
 // ============================================================================
@@ -422,6 +1111,52 @@ mod live {
             "mcp/time should expose at least one tool"
         );
 
+        // Negotiated capabilities
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/servers/time/capabilities")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let json = common::response_json(response).await;
+        assert_eq!(json["capabilities"]["tools"], true);
+        assert_eq!(json["transports"]["sse"], "/mcp/time");
+
+        // Single tool definition, and 404 for unknown tools
+        let tool_name = tools[0]["name"].as_str().unwrap();
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/servers/time/tools/{}", tool_name))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let json = common::response_json(response).await;
+        assert_eq!(json["name"], tool_name);
+        assert!(json["input_schema"].is_object());
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/servers/time/tools/no-such-tool")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
         // Cleanup
         let _ = app
             .oneshot(